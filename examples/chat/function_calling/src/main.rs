@@ -35,6 +35,7 @@ async fn main() {
                     },
                     "required": ["min", "max"],
                 }),
+                strict: None,
             },
         }])
         .build()
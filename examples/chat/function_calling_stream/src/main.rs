@@ -38,6 +38,7 @@ async fn main() {
                     },
                     "required": ["min", "max"],
                 }),
+                strict: None,
             },
         }])
         .build()
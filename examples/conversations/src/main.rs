@@ -0,0 +1,60 @@
+use openai_dive::v1::api::Client;
+use openai_dive::v1::models::Gpt4Model;
+use openai_dive::v1::resources::conversation::{
+    CreateConversationParametersBuilder, UpdateConversationParametersBuilder,
+};
+use openai_dive::v1::resources::response::request::{ResponseInput, ResponseParametersBuilder};
+use openai_dive::v1::resources::response::shared::ResponseConversation;
+use std::collections::HashMap;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = Client::new_from_env();
+
+    let parameters = CreateConversationParametersBuilder::default()
+        .metadata(HashMap::from([("topic".to_string(), "demo".to_string())]))
+        .build()?;
+
+    let conversation = client.conversations().create(parameters).await?;
+
+    println!("{conversation:#?}");
+
+    for question in ["My name is Dive.", "What is my name?"] {
+        let parameters = ResponseParametersBuilder::default()
+            .model(Gpt4Model::Gpt4O.to_string())
+            .input(ResponseInput::Text(question.to_string()))
+            .conversation(ResponseConversation::Id(conversation.id.clone()))
+            .build()?;
+
+        let result = client.responses().create(parameters).await?;
+
+        println!("{:#?}", result.output);
+    }
+
+    let items = client
+        .conversations()
+        .list_items(&conversation.id, None)
+        .await?;
+
+    println!("{items:#?}");
+
+    let parameters = UpdateConversationParametersBuilder::default()
+        .metadata(HashMap::from([(
+            "topic".to_string(),
+            "introductions".to_string(),
+        )]))
+        .build()?;
+
+    let conversation = client
+        .conversations()
+        .update(&conversation.id, parameters)
+        .await?;
+
+    println!("{conversation:#?}");
+
+    let result = client.conversations().delete(&conversation.id).await?;
+
+    println!("{result:#?}");
+
+    Ok(())
+}
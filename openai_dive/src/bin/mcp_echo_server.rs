@@ -0,0 +1,10 @@
+//! A minimal MCP server over stdio with an `echo` tool, used to test `McpBridge::spawn`.
+
+use openai_dive::v1::tools::mcp_bridge::serve_echo_server;
+
+fn main() -> std::io::Result<()> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(serve_echo_server(tokio::io::stdin(), tokio::io::stdout()))
+}
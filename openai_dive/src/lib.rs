@@ -4,7 +4,7 @@
 //!
 //! ```ini
 //! [dependencies]
-//! openai_dive = "1.4"
+//! openai_dive = "2.0"
 //! ```
 //!
 //! ## Get started
@@ -26,18 +26,31 @@
 //! - [Using OpenAI-compatible APIs](#using-openai-compatible-apis)
 //! - [Set organization/project id](#set-organizationproject-id)
 //! - [Add proxy](#add-proxy)
+//! - [Custom transport](#custom-transport)
+//! - [Middleware](#middleware)
+//! - [Record and replay](#record-and-replay)
+//! - [Mock server](#mock-server)
+//! - [Pagination](#pagination)
+//! - [Retries](#retries)
+//! - [Error handling](#error-handling)
+//! - [Token counting](#token-counting)
 //! - [Available models](#available-models)
 //!
 //! ## Endpoints
 //!
 //! - [Chat](#chat)
 //!   - [Completion](#completion)
+//!   - [Streaming](#streaming)
 //!   - [Vision](#vision)
 //!   - [Voice](#voice)
 //!   - [Function calling](#function-calling)
+//!   - [Tool runner](#tool-runner)
+//!   - [Conversation history](#conversation-history)
 //!   - [Structured outputs](#structured-outputs)
 //!   - [Web search](#web-search)
+//!   - [Stored completions](#stored-completions)
 //! - [Responses](#responses)
+//! - [Conversations](#conversations)
 //! - [Images](#images)
 //! - [Audio](#audio)
 //! - [Videos](#videos)
@@ -84,6 +97,43 @@
 //!
 //! More information: [Create chat completion](https://platform.openai.com/docs/api-reference/chat/create)
 //!
+//! ### Streaming
+//!
+//! Wrap a chat completion stream in an `AccumulatingStream` to receive the chunks as they arrive while the complete `ChatCompletionResponse` (including the tool calls, finish reason and usage of every choice) is built up. Streaming requires the `stream` feature.
+//!
+//! ```rust
+//! let mut stream = AccumulatingStream::new(client.chat().create_stream(parameters).await?);
+//!
+//! while let Some(chunk) = stream.next().await {
+//!     for choice in chunk?.choices {
+//!         if let DeltaChatMessage::Assistant { content: Some(content), .. } = choice.delta {
+//!             print!("{content}");
+//!         }
+//!     }
+//! }
+//!
+//! let response = stream.response();
+//! ```
+//!
+//! When streaming a structured output or tool call arguments, `partial_content` and `partial_tool_arguments` parse the JSON received so far (closing open strings, arrays and objects) into a type whose fields are all optional. The responses `AccumulatingStream` offers `partial_output` and `partial_arguments`, and `parse_partial_json` parses any JSON prefix into a `serde_json::Value`.
+//!
+//! ```rust
+//! #[derive(Deserialize, Default)]
+//! #[serde(default)]
+//! struct PartialWeather {
+//!     location: Option<String>,
+//!     temperatures: Vec<f32>,
+//! }
+//!
+//! while let Some(chunk) = stream.next().await {
+//!     chunk?;
+//!
+//!     if let Some(weather) = stream.partial_content::<PartialWeather>() {
+//!         println!("{:?} {:?}", weather.location, weather.temperatures);
+//!     }
+//! }
+//! ```
+//!
 //! ### Vision
 //!
 //! Learn how to use vision capabilities to understand images.
@@ -186,6 +236,7 @@
 //!                 },
 //!                 "required": ["min", "max"],
 //!             }),
+//!             strict: None,
 //!         },
 //!     }])
 //!     .build()?;
@@ -237,6 +288,70 @@
 //!
 //! More information: [Function calling](https://platform.openai.com/docs/guides/function-calling)
 //!
+//! ### Tool runner
+//!
+//! Enable the feature flag `tools` to let a `ToolRunner` handle the function calling loop: it sends the registered functions along with the request, calls the handlers of the requested tools concurrently, sends their output back to the model and repeats until the model answers without calling a tool. A run that reaches its iteration limit stops with `ToolRunError::MaxIterations`, which contains the last response and the messages of the run so far. Use `run_stream` (with the `stream` feature) to receive the chunks of every completion.
+//!
+//! ```rust
+//! let mut runner = ToolRunner::new(&client);
+//!
+//! runner
+//!     .register(
+//!         ChatCompletionFunction {
+//!             name: "get_random_number".to_string(),
+//!             description: Some("Get a random number between two values".to_string()),
+//!             parameters: json!({
+//!                 "type": "object",
+//!                 "properties": {
+//!                     "min": {"type": "integer"},
+//!                     "max": {"type": "integer"},
+//!                 },
+//!                 "required": ["min", "max"],
+//!             }),
+//!             strict: None,
+//!         },
+//!         |arguments| async move {
+//!             let min = arguments["min"].as_u64().ok_or("missing min")?;
+//!             let max = arguments["max"].as_u64().ok_or("missing max")?;
+//!
+//!             Ok(json!(rand::thread_rng().gen_range(min..max)))
+//!         },
+//!     )
+//!     .set_max_iterations(5);
+//!
+//! let result = runner.run(parameters).await?;
+//!
+//! println!("{:?}", result.response.choices[0].message.text());
+//! ```
+//!
+//! ### Conversation history
+//!
+//! A `Conversation` keeps the messages of a chat, appends the assistant message of every response and trims older messages to a token budget before each request. The `TruncationStrategy` drops the oldest messages, keeps the last N messages or summarizes older messages with a model call. Leading system and developer messages are always kept, and an assistant message with tool calls is never separated from its tool messages. Pass only the new messages of a turn to `create`; a repeated leading system prompt is skipped. When a request fails the conversation is left unchanged, so the turn can be retried. Tokens are counted with the `Tokenizer` when the `tokenizer` feature is enabled, otherwise they are estimated from the length of the serialized messages, which overestimates the count.
+//!
+//! ```rust
+//! let mut conversation = Conversation::new();
+//!
+//! conversation
+//!     .set_token_budget(8_000)
+//!     .set_strategy(TruncationStrategy::Summarize {
+//!         model: "gpt-4o-mini".to_string(),
+//!         keep_last: 10,
+//!     });
+//!
+//! let parameters = ChatCompletionParametersBuilder::default()
+//!     .model(Gpt4Model::Gpt4O.to_string())
+//!     .messages(vec![ChatMessage::User {
+//!         content: ChatMessageContent::Text("Hello!".to_string()),
+//!         name: None,
+//!     }])
+//!     .build()?;
+//!
+//! let result = conversation.create(&client, parameters).await?;
+//!
+//! // the conversation can be serialized to continue it in a later session
+//! let json = serde_json::to_string(&conversation)?;
+//! ```
+//!
 //! ### Structured outputs
 //!
 //! Structured Outputs is a feature that guarantees the model will always generate responses that adhere to your supplied JSON Schema, so you don't need to worry about the model omitting a required key, or hallucinating an invalid enum value.
@@ -292,6 +407,53 @@
 //! let result = client.chat().create(parameters).await?;
 //! ```
 //!
+//! Enable the feature flag `schema` to generate the JSON Schema from a Rust type deriving `schemars::JsonSchema`. The generated schema is made compatible with strict mode (`additionalProperties: false`, all properties required and `Option` fields nullable) and validated against the supported subset of JSON Schema. The same works for the parameters of function tools.
+//!
+//! ```rust
+//! #[derive(Deserialize, JsonSchema)]
+//! struct MathReasoning {
+//!     steps: Vec<Step>,
+//!     final_answer: String,
+//! }
+//!
+//! #[derive(Deserialize, JsonSchema)]
+//! struct Step {
+//!     explanation: String,
+//!     output: String,
+//! }
+//!
+//! #[derive(Deserialize, JsonSchema)]
+//! struct WeatherArguments {
+//!     city: String,
+//!     unit: Option<String>,
+//! }
+//!
+//! let parameters = ChatCompletionParametersBuilder::default()
+//!     .model("gpt-4o-2024-08-06")
+//!     .messages(messages)
+//!     .response_format(ChatCompletionResponseFormat::JsonSchema {
+//!         json_schema: JsonSchema::from_type::<MathReasoning>()?,
+//!     })
+//!     .tools(vec![ChatCompletionTool::function::<WeatherArguments>(
+//!         "get_weather",
+//!         "Get the current weather in a given city",
+//!     )?])
+//!     .build()?;
+//! ```
+//!
+//! Use `create_parsed` (available for chat completions and responses) to set the response format from the type and deserialize the answer. The output tells a parsed value apart from a refusal, a truncated answer and an answer that does not match the type.
+//!
+//! ```rust
+//! let parsed = client.chat().create_parsed::<MathReasoning>(parameters).await?;
+//!
+//! match parsed.output {
+//!     StructuredOutput::Parsed(reasoning) => println!("{}", reasoning.final_answer),
+//!     StructuredOutput::Refusal(refusal) => println!("refused: {refusal}"),
+//!     StructuredOutput::Truncated { reason, .. } => println!("truncated: {reason}"),
+//!     StructuredOutput::Invalid { error, .. } => println!("invalid: {error}"),
+//! }
+//! ```
+//!
 //! More information: [Structured outputs](https://platform.openai.com/docs/guides/structured-outputs)
 //!
 //! ### Web search
@@ -327,6 +489,27 @@
 //!
 //! More information: [Web search](https://platform.openai.com/docs/guides/web-search)
 //!
+//! ### Stored completions
+//!
+//! Chat completions created with `store` set to `true` can be listed (filtered by model and metadata), retrieved, updated, deleted and their messages listed.
+//!
+//! ```rust
+//! let query = ListChatCompletionsParametersBuilder::default()
+//!     .metadata(HashMap::from([("pipeline".to_string(), "distillation".to_string())]))
+//!     .limit(20u32)
+//!     .build()?;
+//!
+//! let completions = client.chat().list(Some(query)).await?;
+//!
+//! for completion in completions.data {
+//!     let id = completion.id.unwrap_or_default();
+//!
+//!     let messages = client.chat().list_messages(&id, None).await?;
+//!
+//!     client.chat().delete(&id).await?;
+//! }
+//! ```
+//!
 //! ## Responses
 //!
 //! OpenAI's most advanced interface for generating model responses. Supports text and image inputs, and text outputs. Create stateful interactions with the model, using the output of previous responses as input. Extend the model's capabilities with built-in tools for file search, web search, computer use, and more. Allow the model access to external systems and data using function calling.
@@ -341,6 +524,161 @@
 //! - Computer use
 //! - Function calling
 //!
+//! Wrap a response stream in an `AccumulatingStream` to receive the events as they arrive while the complete `ResponseObject` is built up from the `*.delta` and `*.done` events. Events that are missed or arrive out of order (based on their `sequence_number`) are reported as a `StreamError`. After missed events the stream continues with the next event, and the gaps are recorded in `missed_events` of the accumulator.
+//!
+//! ```rust
+//! let mut stream = AccumulatingStream::new(client.responses().create_stream(parameters).await?);
+//!
+//! while let Some(event) = stream.next().await {
+//!     event?;
+//!     println!("{}", stream.output_text());
+//! }
+//!
+//! let response = stream.accumulator().response();
+//! ```
+//!
+//! Stored and background responses can be inspected with `list_input_items` and stopped with `cancel`. Use `input_tokens` to count the input tokens of a request without creating a response.
+//!
+//! ```rust
+//! let tokens = client.responses().input_tokens(parameters.clone().into()).await?;
+//!
+//! let response = client.responses().create(parameters).await?;
+//!
+//! let items = client.responses().list_input_items(&response.id, None).await?;
+//!
+//! client.responses().cancel(&response.id).await?;
+//! ```
+//!
+//! Responses created with `background` set to true can be polled with `wait` until they finish. If the connection of a streamed background response drops, `resume_stream` reconnects and skips the events that were already received.
+//!
+//! ```rust
+//! let response = client
+//!     .responses()
+//!     .wait(&response.id, Duration::from_secs(2), Duration::from_secs(300))
+//!     .await?;
+//!
+//! let accumulator = stream.into_accumulator();
+//!
+//! let stream = client
+//!     .responses()
+//!     .resume_stream(&response.id, accumulator.sequence_number())
+//!     .await?;
+//!
+//! let mut stream = AccumulatingStream::with_accumulator(stream, accumulator);
+//! ```
+//!
+//! With the `tools` feature, `tools::responses::ToolRunner` runs the function calling loop of a response. Functions are registered with their handler, tools run by OpenAI are added with `add_tool`. In the default `ToolRunMode::Stateful` mode only the function outputs are sent back together with the `previous_response_id`, or added to the `conversation` when the parameters set one. `ToolRunMode::Stateless` sends the complete item trail with every request, including the reasoning items with their encrypted content, so nothing needs to be stored by OpenAI. A run that reaches its iteration limit stops with `ToolRunError::MaxIterations`, which contains the last response and the items of the run so far.
+//!
+//! ```rust
+//! let mut runner = ToolRunner::new(&client);
+//!
+//! runner
+//!     .set_mode(ToolRunMode::Stateless)
+//!     .register(
+//!         ResponseTool::Function {
+//!             name: "get_weather".to_string(),
+//!             description: Some("Get the current weather in a city".to_string()),
+//!             parameters: json!({
+//!                 "type": "object",
+//!                 "properties": {"city": {"type": "string"}},
+//!                 "required": ["city"],
+//!             }),
+//!             strict: false,
+//!         },
+//!         |arguments| async move { Ok(json!({"city": arguments["city"], "temperature": 21})) },
+//!     );
+//!
+//! let result = runner.run(parameters).await?;
+//!
+//! println!("{:?} ({} items)", result.response.output, result.items.len());
+//! ```
+//!
+//! `tools::computer::ComputerRunner` drives the computer use tool: it executes every `ComputerAction` in a `ComputerEnvironment` (your browser or VM automation), sends a screenshot back and stops once the model answers without a computer call. Pending safety checks are only acknowledged when the `on_safety_checks` callback returns true, otherwise the run stops with `ToolRunError::SafetyChecksNotAcknowledged`, which contains the items of the run so far, the blocked call and its checks. Like the `ToolRunner`, it stops with `ToolRunError::MaxIterations` at its iteration limit. `FakeComputer` is a headless environment that records the actions, for tests.
+//!
+//! ```rust
+//! let mut runner = ComputerRunner::new(&client, FakeComputer::new());
+//!
+//! runner.on_safety_checks(|call, checks| {
+//!     println!("{call:?} needs confirmation: {checks:?}");
+//!
+//!     false
+//! });
+//!
+//! let result = runner.run(parameters).await?;
+//!
+//! println!("{:?}", runner.environment().actions());
+//! ```
+//!
+//! `shell_call`, `local_shell_call` and `apply_patch_call` items are executed locally with opt-in executors. `ApplyPatchExecutor` applies the V4A diffs of `apply_patch` calls to the files below a root directory, rejects paths outside of it and refuses to create a file that already exists. `ShellExecutor` (with the `shell` feature) runs commands in a working directory with an environment variable allow-list, a timeout, output truncation and an optional approval hook. Both return the output item to send back to the model.
+//!
+//! ```rust
+//! let patches = ApplyPatchExecutor::new("./workspace");
+//!
+//! let mut shell = ShellExecutor::new("./workspace");
+//!
+//! shell
+//!     .set_allowed_env(["PATH", "HOME"])
+//!     .set_timeout(Duration::from_secs(10))
+//!     .on_approval(|command| !command.contains("rm "));
+//!
+//! let mut outputs = Vec::new();
+//!
+//! for item in &response.output {
+//!     match item {
+//!         ResponseOutput::ApplyPatchCall(call) => outputs.push(patches.execute(call)),
+//!         ResponseOutput::ShellCall(call) => outputs.push(shell.execute_shell(call).await),
+//!         ResponseOutput::LocalShellCall(call) => outputs.push(shell.execute_local_shell(call).await),
+//!         _ => {}
+//!     }
+//! }
+//! ```
+//!
+//! MCP tool calls that require approval show up as `mcp_approval_request` items. An `McpApprovalPolicy` approves the calls of allow-listed servers and tools and passes the other requests to an async callback, e.g. to ask a human. Set it on a `ToolRunner` to answer the requests with `mcp_approval_response` items and continue the response automatically, or call `respond_all` yourself. `McpToolCache` keeps the `mcp_list_tools` items, so the listed tools can be inspected and replayed in later requests.
+//!
+//! ```rust
+//! let mut policy = McpApprovalPolicy::new();
+//!
+//! policy
+//!     .allow_server("docs")
+//!     .allow_tool("github", "search_issues")
+//!     .on_approval(|request| async move { ask_user(&request.name, &request.arguments).await });
+//!
+//! let mut runner = ToolRunner::new(&client);
+//! runner.set_mcp_approval(policy);
+//!
+//! let result = runner.run(parameters).await?;
+//!
+//! let mut cache = McpToolCache::new();
+//! cache.update(&result.response);
+//!
+//! println!("{:?}", cache.tools("github"));
+//! ```
+//!
+//! Local MCP servers can be used with both Chat Completions and Responses through `tools::mcp_bridge::McpBridge` (with the `mcp` feature). The bridge starts the server process, talks JSON-RPC over its stdin and stdout, lists its tools as function tools and routes the tool calls of the model back to the server.
+//!
+//! ```rust
+//! let bridge = McpBridge::spawn("npx", ["-y", "@modelcontextprotocol/server-filesystem", "."]).await?;
+//!
+//! let mut runner = ToolRunner::new(&client);
+//! bridge.register_responses(&mut runner);
+//!
+//! let result = runner.run(parameters).await?;
+//! ```
+//!
+//! ## Conversations
+//!
+//! Store the state of a conversation on the server and attach responses to it with the `conversation` parameter, instead of chaining `previous_response_id`.
+//!
+//! For more information see the examples in the [examples/conversations](https://github.com/tjardoo/openai-client/tree/master/examples/conversations) directory.
+//!
+//! - Create conversation
+//! - Retrieve conversation
+//! - Update conversation
+//! - Delete conversation
+//! - List, create, retrieve and delete items
+//!
+//! More information [Conversations](https://platform.openai.com/docs/api-reference/conversations)
+//!
 //! ## Images
 //!
 //! Given a prompt and/or an input image, the model will generate a new image.
@@ -592,12 +930,188 @@
 //!     http_client,
 //!     base_url: "https://api.openai.com/v1".to_string(),
 //!     api_key,
-//!     headers: None,
-//!     organization: None,
-//!     project: None,
+//!     ..Default::default()
 //! };
 //! ```
 //!
+//! ### Custom transport
+//!
+//! Requests are sent through the `Transport` trait, with `ReqwestTransport` (using `http_client`) as the default. Implement the trait to use another HTTP stack, a test double or a recording transport. The trait and its request and response types use the types of the `http` crate and are available without the `reqwest` feature. Multipart bodies can be encoded with `MultipartForm::encode`.
+//!
+//! ```rust
+//! use openai_dive::v1::transport::{HttpRequest, HttpResponse, Transport};
+//!
+//! #[derive(Debug)]
+//! struct MyTransport;
+//!
+//! #[async_trait::async_trait]
+//! impl Transport for MyTransport {
+//!     async fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+//!         // send `request.method`, `request.url`, `request.headers` and `request.body`
+//!     }
+//! }
+//!
+//! let mut client = Client::new_from_env();
+//!
+//! client.set_transport(MyTransport);
+//! ```
+//!
+//! ### Middleware
+//!
+//! Middleware intercepts every request (JSON, multipart, raw bytes and streaming) before it is sent and every response before its status code is checked. Middleware runs in the order it is added. `handle` also runs for streaming requests: it sees the real status code and headers, and the body of error responses, but the body of a successful stream is an empty placeholder. Implement `handle_stream` as well to inspect streamed bodies.
+//!
+//! ```rust
+//! use openai_dive::v1::middleware::{Middleware, Next};
+//! use openai_dive::v1::transport::{HttpRequest, HttpResponse};
+//!
+//! #[derive(Debug)]
+//! struct AuditLog;
+//!
+//! #[async_trait::async_trait]
+//! impl Middleware for AuditLog {
+//!     async fn handle(&self, request: HttpRequest, next: Next<'_>) -> Result<HttpResponse, APIError> {
+//!         let url = request.url.clone();
+//!         let response = next.run(request).await?;
+//!
+//!         println!("{url} -> {}", response.status);
+//!
+//!         Ok(response)
+//!     }
+//! }
+//!
+//! let mut client = Client::new_from_env();
+//!
+//! client.add_middleware(AuditLog);
+//! ```
+//!
+//! ### Record and replay
+//!
+//! Enable the feature flag `cassette` to record every request/response pair (including streamed events and binary bodies) to a JSON cassette file and replay them later without network access. Requests are matched on method, path and JSON body. Credentials in headers and the query string are redacted from the cassette. A request without a matching recording fails with a `CassetteError`, which is never retried.
+//!
+//! ```rust
+//! // record once against the API
+//! let mut client = Client::new_from_env();
+//! client.record_cassette("tests/cassettes/chat.json");
+//!
+//! // replay in CI
+//! let mut client = Client::new("sk-test".to_string());
+//! client.replay_cassette("tests/cassettes/chat.json")?;
+//! ```
+//!
+//! ### Mock server
+//!
+//! Enable the feature flag `testing` to start an in-process mock OpenAI server. Chat completions and responses (including streamed events), embeddings, models, files, uploads, batches, vector stores and their files, audio and images have a canned default response; other routes answer with a 404 error unless they are mocked. Register mocks to inject errors, latency or custom responses, and inspect the received requests afterwards.
+//!
+//! ```rust
+//! use openai_dive::v1::testing::{Mock, MockResponse, MockServer};
+//!
+//! let server = MockServer::start().await;
+//!
+//! server.mock(
+//!     Mock::post("/chat/completions")
+//!         .respond_with(MockResponse::error(429, "Rate limit reached", "requests", Some("rate_limit_exceeded")))
+//!         .times(1),
+//! );
+//!
+//! let client = server.client(); // or `client.set_base_url(&server.uri())`
+//!
+//! let result = client.chat().create(parameters).await;
+//!
+//! assert_eq!(server.received_requests().len(), 1);
+//! ```
+//!
+//! ### Pagination
+//!
+//! Every list endpoint has a `paginate` helper (`paginate_messages`, `paginate_items`, ... for nested lists) that returns a `Paginator`. It follows the `after`/`before` cursors until the last page; `collect_all` collects the items, and with the `stream` feature `into_stream` and `pages` stream them. `Paginator::new` turns any other list call into a paginator, e.g. the usage endpoints, which follow their `next_page` cursor.
+//!
+//! ```rust
+//! use openai_dive::v1::pagination::Paginator;
+//! use openai_dive::v1::resources::usage::UsageParameters;
+//!
+//! let client = Client::new_from_env();
+//!
+//! // collect at most 1000 items
+//! let files = client
+//!     .files()
+//!     .paginate(ListFilesParameters::default())
+//!     .collect_all(1000)
+//!     .await?;
+//!
+//! let messages = client
+//!     .chat()
+//!     .paginate_messages("chatcmpl-abc123", ListParameters::default())
+//!     .collect_all(100)
+//!     .await?;
+//!
+//! // requires the `stream` feature
+//! let mut batches = client
+//!     .batches()
+//!     .paginate(SimpleListParameters::default())
+//!     .into_stream();
+//!
+//! while let Some(batch) = batches.next().await {
+//!     println!("{:?}", batch?.id);
+//! }
+//!
+//! let buckets = Paginator::new(UsageParameters::default(), |query| {
+//!     let client = &client;
+//!     async move { client.usage().completions(query).await }
+//! })
+//! .collect_all(100)
+//! .await?;
+//! ```
+//!
+//! A page that has more items but no cursor ends the stream with an error instead of silently stopping.
+//!
+//! ### Retries
+//!
+//! By default every request is sent once. Set a retry policy to automatically retry rate limited (429) requests, server errors and connection failures with exponential backoff. Errors are retried when their status code is listed in `retryable_status_codes` and the `retryable_error` predicate (`APIError::is_retryable` by default, which skips an exhausted `insufficient_quota`) allows it, unless the server sends an `x-should-retry` header. Delays requested by the server via the `Retry-After` or `x-ratelimit-reset-*` headers are honoured (capped at `max_delay`). Retries require the `tokio` feature.
+//!
+//! ```rust
+//! use openai_dive::v1::retry::RetryPolicyBuilder;
+//!
+//! let mut client = Client::new_from_env();
+//!
+//! client.set_retry_policy(
+//!     RetryPolicyBuilder::default()
+//!         .max_attempts(5u32)
+//!         .base_delay(Duration::from_millis(500))
+//!         .max_delay(Duration::from_secs(30))
+//!         .retryable_error(|error| error.is_retryable() && !error.is_context_length_exceeded())
+//!         .build()?,
+//! );
+//! ```
+//!
+//! ### Error handling
+//!
+//! Errors returned by the API carry an `ErrorResponse` with the HTTP status, the parsed `error` object (`message`, `type`, `param` and `code`), the `x-request-id` header, the rate limit headers and the `Retry-After` delay.
+//!
+//! ```rust
+//! match client.chat().create(parameters).await {
+//!     Ok(result) => println!("{result:#?}"),
+//!     Err(error) if error.is_context_length_exceeded() => {
+//!         // shorten the conversation and try again
+//!     }
+//!     Err(error) => {
+//!         eprintln!("{error} (code: {:?}, request id: {:?})", error.code(), error.request_id());
+//!     }
+//! }
+//! ```
+//!
+//! ### Token counting
+//!
+//! The `Tokenizer` counts tokens offline with the `o200k_base` and `cl100k_base` encodings, to estimate the size of a request before sending it. The vocabularies are embedded, or can be loaded from a local `.tiktoken` file with `Tokenizer::from_file`. Token counting requires the `tokenizer` feature.
+//!
+//! ```rust
+//! let tokenizer = Tokenizer::for_model("gpt-4o");
+//!
+//! // messages (including the per-message overhead and images by detail) and tool definitions
+//! let prompt_tokens = tokenizer.count_parameters(&parameters);
+//!
+//! // pre-tokenized embeddings input
+//! let input = tokenizer.embedding_input("The food was delicious and the waiter...");
+//! ```
+//!
 //! ### Available Models
 //!
 //! You can use these predefined constants to set the model in the parameters or use any string representation (ie. for your custom models).
@@ -1,17 +1,28 @@
 use crate::v1::helpers::check_status_code;
+#[cfg(feature = "stream")]
+use crate::v1::helpers::error_from_response;
+use crate::v1::middleware::{Middleware, Next};
+use crate::v1::resources::shared::MultipartForm;
+use crate::v1::retry::RetryPolicy;
+#[cfg(feature = "stream")]
+use crate::v1::transport::{ByteStream, StreamingResponse};
+use crate::v1::transport::{HttpRequest, HttpResponse, RequestBody, ReqwestTransport, Transport};
 use crate::v1::{error::APIError, resources::shared::Headers};
 use bytes::Bytes;
 #[cfg(feature = "stream")]
 use futures::{stream::StreamExt, Stream};
-use reqwest::{multipart::Form, Method, RequestBuilder};
 #[cfg(feature = "stream")]
-use reqwest_eventsource::{Event, EventSource, RequestBuilderExt};
+use reqwest::header::ACCEPT;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::Method;
 #[cfg(feature = "stream")]
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::future::Future;
 #[cfg(feature = "stream")]
 use std::pin::Pin;
+use std::sync::Arc;
 
 use super::resources::shared::ResponseWrapper;
 
@@ -26,6 +37,11 @@ pub struct Client {
     pub headers: Option<HashMap<String, String>>,
     pub organization: Option<String>,
     pub project: Option<String>,
+    pub retry_policy: Option<RetryPolicy>,
+    /// The transport used to send requests, defaults to a `ReqwestTransport` using `http_client`.
+    pub transport: Option<Arc<dyn Transport>>,
+    /// The middleware applied to every request, in order.
+    pub middlewares: Vec<Arc<dyn Middleware>>,
 }
 
 impl Client {
@@ -87,100 +103,160 @@ impl Client {
         self
     }
 
+    /// Retry failed requests according to the given policy. Retries require the `tokio` feature.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) -> &mut Self {
+        self.retry_policy = Some(retry_policy);
+
+        self
+    }
+
+    /// Send requests through a custom transport instead of the `reqwest` based default.
+    pub fn set_transport<T: Transport + 'static>(&mut self, transport: T) -> &mut Self {
+        self.transport = Some(Arc::new(transport));
+
+        self
+    }
+
+    /// Add a middleware to the end of the chain that intercepts requests and responses.
+    pub fn add_middleware<M: Middleware + 'static>(&mut self, middleware: M) -> &mut Self {
+        self.middlewares.push(Arc::new(middleware));
+
+        self
+    }
+
+    pub(crate) fn transport(&self) -> Arc<dyn Transport> {
+        match &self.transport {
+            Some(transport) => transport.clone(),
+            None => Arc::new(ReqwestTransport::new(self.http_client.clone())),
+        }
+    }
+
     fn build_request(
         &self,
-        method: reqwest::Method,
+        method: Method,
         path: &str,
         content_type: Option<&str>,
-    ) -> RequestBuilder {
-        let url = format!("{}{}", &self.base_url, path);
+    ) -> Result<HttpRequest, APIError> {
+        let mut headers = HeaderMap::new();
 
-        let mut request = self
-            .http_client
-            .request(method, url)
-            .bearer_auth(&self.api_key);
+        insert_header(
+            &mut headers,
+            AUTHORIZATION.as_str(),
+            &format!("Bearer {}", self.api_key),
+        )?;
 
         if let Some(content_type) = content_type {
-            request = request.header(reqwest::header::CONTENT_TYPE, content_type);
+            insert_header(&mut headers, CONTENT_TYPE.as_str(), content_type)?;
         }
 
-        if let Some(headers) = &self.headers {
-            for (key, value) in headers {
-                request = request.header(key, value);
+        if let Some(custom_headers) = &self.headers {
+            for (key, value) in custom_headers {
+                insert_header(&mut headers, key, value)?;
             }
         }
 
         if let Some(organization) = &self.organization {
-            request = request.header("OpenAI-Organization", organization);
+            insert_header(&mut headers, "OpenAI-Organization", organization)?;
         }
 
         if let Some(project) = &self.project {
-            request = request.header("OpenAI-Project", project);
+            insert_header(&mut headers, "OpenAI-Project", project)?;
         }
 
-        request
+        Ok(HttpRequest {
+            method,
+            url: format!("{}{}", &self.base_url, path),
+            headers,
+            body: RequestBody::Empty,
+        })
     }
 
-    pub(crate) async fn get(&self, path: &str) -> Result<String, APIError> {
-        let result = self
-            .build_request(Method::GET, path, Some(MIME_TYPE_APPLICATION_JSON))
-            .send()
-            .await;
-
-        let response = match check_status_code(result).await {
-            Ok(response) => response,
-            Err(error) => return Err(error),
-        };
-
-        let response_text = response
-            .text()
-            .await
+    fn build_json_request<T: Serialize + ?Sized>(
+        &self,
+        method: Method,
+        path: &str,
+        parameters: &T,
+    ) -> Result<HttpRequest, APIError> {
+        let body = serde_json::to_vec(parameters)
             .map_err(|error| APIError::ParseError(error.to_string()))?;
 
-        #[cfg(feature = "log")]
-        log::trace!("{response_text}");
+        Ok(HttpRequest {
+            body: RequestBody::Json(body.into()),
+            ..self.build_request(method, path, Some(MIME_TYPE_APPLICATION_JSON))?
+        })
+    }
 
-        Ok(response_text)
+    /// Sends the request through the middleware chain and checks the status code of the response, resending it as long as the retry policy allows.
+    async fn execute(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+        let transport = self.transport();
+
+        self.send_with_retries(|| async {
+            check_status_code(
+                Next::new(transport.as_ref(), &self.middlewares)
+                    .run(request.clone())
+                    .await?,
+            )
+        })
+        .await
     }
 
-    pub(crate) async fn get_raw(&self, path: &str) -> Result<Bytes, APIError> {
-        let result = self.build_request(Method::GET, path, None).send().await;
+    #[cfg(feature = "stream")]
+    async fn execute_stream(&self, request: HttpRequest) -> Result<StreamingResponse, APIError> {
+        let transport = self.transport();
 
-        let response = match check_status_code(result).await {
-            Ok(response) => response,
-            Err(error) => return Err(error),
-        };
+        self.send_with_retries(|| async {
+            let response = Next::new(transport.as_ref(), &self.middlewares)
+                .run_stream(request.clone())
+                .await?;
 
-        let bytes = response
-            .bytes()
-            .await
-            .map_err(|error| APIError::ParseError(error.to_string()))?;
+            if response.status.is_client_error() || response.status.is_server_error() {
+                return Err(error_from_response(response.collect().await?));
+            }
 
-        Ok(bytes)
+            Ok(response)
+        })
+        .await
     }
 
-    pub(crate) async fn get_with_query<Q>(&self, path: &str, query: &Q) -> Result<String, APIError>
+    async fn send_with_retries<R, F, Fut>(&self, send: F) -> Result<R, APIError>
     where
-        Q: Serialize,
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<R, APIError>>,
     {
-        let encoded_query = serde_html_form::to_string(query).unwrap_or_else(|_| "".to_string());
+        #[cfg(feature = "tokio")]
+        if let Some(retry_policy) = &self.retry_policy {
+            let mut attempt = 1;
 
-        let path = format!("{path}?{encoded_query}");
+            loop {
+                let result = send().await;
 
-        let result = self
-            .build_request(Method::GET, &path, Some(MIME_TYPE_APPLICATION_JSON))
-            .send()
-            .await;
+                let delay = match &result {
+                    Ok(_) => None,
+                    Err(error) => retry_policy.retry_delay(attempt, error),
+                };
 
-        let response = match check_status_code(result).await {
-            Ok(response) => response,
-            Err(error) => return Err(error),
-        };
+                match delay {
+                    Some(delay) => {
+                        #[cfg(feature = "log")]
+                        log::debug!("retrying request (attempt {attempt}) in {delay:?}");
 
-        let response_text = response
-            .text()
-            .await
-            .map_err(|error| APIError::ParseError(error.to_string()))?;
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    None => return result,
+                }
+            }
+        }
+
+        send().await
+    }
+
+    pub(crate) async fn get(&self, path: &str) -> Result<String, APIError> {
+        let response = self
+            .execute(self.build_request(Method::GET, path, Some(MIME_TYPE_APPLICATION_JSON))?)
+            .await?;
+
+        let response_text = response_text(&response);
 
         #[cfg(feature = "log")]
         log::trace!("{response_text}");
@@ -188,74 +264,84 @@ impl Client {
         Ok(response_text)
     }
 
+    pub(crate) async fn get_raw(&self, path: &str) -> Result<Bytes, APIError> {
+        let response = self
+            .execute(self.build_request(Method::GET, path, None)?)
+            .await?;
+
+        Ok(response.body)
+    }
+
+    pub(crate) async fn get_with_query<Q>(&self, path: &str, query: &Q) -> Result<String, APIError>
+    where
+        Q: Serialize,
+    {
+        let encoded_query = serde_html_form::to_string(query).unwrap_or_else(|_| "".to_string());
+
+        let path = format!("{path}?{encoded_query}");
+
+        self.get(&path).await
+    }
+
     pub(crate) async fn post<T: Serialize>(
         &self,
         path: &str,
         parameters: &T,
         query_params: impl Into<Option<&HashMap<String, String>>>,
     ) -> Result<ResponseWrapper<String>, APIError> {
-        let result = self
-            .build_request(Method::POST, path, Some(MIME_TYPE_APPLICATION_JSON))
-            .query(&query_params.into())
-            .json(&parameters)
-            .send()
-            .await;
-
-        let response = match check_status_code(result).await {
-            Ok(response) => response,
-            Err(error) => return Err(error),
-        };
+        let path = with_query_params(path, query_params.into());
 
-        let header_map = response.headers().clone();
+        let response = self
+            .execute(self.build_json_request(Method::POST, &path, parameters)?)
+            .await?;
 
-        let response_text = response
-            .text()
-            .await
-            .map_err(|error| APIError::ParseError(error.to_string()))?;
-        let response_headers: Headers = header_map.into();
+        let response_text = response_text(&response);
+        let response_headers: Headers = response.headers.into();
 
         #[cfg(feature = "log")]
         log::trace!("{response_text}");
 
         Ok(ResponseWrapper {
-            data: response_text.to_string(),
+            data: response_text,
             headers: response_headers,
         })
     }
 
-    pub(crate) async fn delete(&self, path: &str) -> Result<String, APIError> {
-        let result = self
-            .build_request(Method::DELETE, path, Some(MIME_TYPE_APPLICATION_JSON))
-            .send()
-            .await;
-
-        let response = match check_status_code(result).await {
-            Ok(response) => response,
-            Err(error) => return Err(error),
-        };
+    /// Sends a POST request without a body, e.g. to cancel an object.
+    pub(crate) async fn post_empty(&self, path: &str) -> Result<String, APIError> {
+        let response = self
+            .execute(self.build_request(Method::POST, path, None)?)
+            .await?;
 
-        response
-            .text()
-            .await
-            .map_err(|error| APIError::ParseError(error.to_string()))
+        let response_text = response_text(&response);
+
+        #[cfg(feature = "log")]
+        log::trace!("{response_text}");
+
+        Ok(response_text)
     }
 
-    pub(crate) async fn post_with_form(&self, path: &str, form: Form) -> Result<String, APIError> {
-        let result = self
-            .build_request(Method::POST, path, None)
-            .multipart(form)
-            .send()
-            .await;
+    pub(crate) async fn delete(&self, path: &str) -> Result<String, APIError> {
+        let response = self
+            .execute(self.build_request(Method::DELETE, path, Some(MIME_TYPE_APPLICATION_JSON))?)
+            .await?;
+
+        Ok(response_text(&response))
+    }
 
-        let response = match check_status_code(result).await {
-            Ok(response) => response,
-            Err(error) => return Err(error),
+    pub(crate) async fn post_with_form(
+        &self,
+        path: &str,
+        form: MultipartForm,
+    ) -> Result<String, APIError> {
+        let request = HttpRequest {
+            body: RequestBody::Multipart(form),
+            ..self.build_request(Method::POST, path, None)?
         };
 
-        response
-            .text()
-            .await
-            .map_err(|error| APIError::ParseError(error.to_string()))
+        let response = self.execute(request).await?;
+
+        Ok(response_text(&response))
     }
 
     pub(crate) async fn post_raw<T: Serialize>(
@@ -263,21 +349,11 @@ impl Client {
         path: &str,
         parameters: &T,
     ) -> Result<Bytes, APIError> {
-        let result = self
-            .build_request(Method::POST, path, Some(MIME_TYPE_APPLICATION_JSON))
-            .json(&parameters)
-            .send()
-            .await;
-
-        let response = match check_status_code(result).await {
-            Ok(response) => response,
-            Err(error) => return Err(error),
-        };
+        let response = self
+            .execute(self.build_json_request(Method::POST, path, parameters)?)
+            .await?;
 
-        response
-            .bytes()
-            .await
-            .map_err(|error| APIError::ParseError(error.to_string()))
+        Ok(response.body)
     }
 
     #[cfg(feature = "stream")]
@@ -286,19 +362,35 @@ impl Client {
         path: &str,
         parameters: &I,
         query_params: impl Into<Option<&HashMap<String, String>>>,
-    ) -> Pin<Box<dyn Stream<Item = Result<O, APIError>> + Send>>
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<O, APIError>> + Send>>, APIError>
     where
         I: Serialize,
         O: DeserializeOwned + std::marker::Send + 'static,
     {
-        let event_source = self
-            .build_request(Method::POST, path, None)
-            .json(&parameters)
-            .query(&query_params.into())
-            .eventsource()
-            .unwrap();
+        let path = with_query_params(path, query_params.into());
 
-        Client::process_stream::<O>(event_source).await
+        let mut request = self.build_json_request(Method::POST, &path, parameters)?;
+        insert_header(&mut request.headers, ACCEPT.as_str(), "text/event-stream")?;
+
+        let response = self.execute_stream(request).await?;
+
+        Ok(Client::process_stream::<O>(response.body))
+    }
+
+    #[cfg(feature = "stream")]
+    pub(crate) async fn get_stream<O>(
+        &self,
+        path: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<O, APIError>> + Send>>, APIError>
+    where
+        O: DeserializeOwned + std::marker::Send + 'static,
+    {
+        let mut request = self.build_request(Method::GET, path, None)?;
+        insert_header(&mut request.headers, ACCEPT.as_str(), "text/event-stream")?;
+
+        let response = self.execute_stream(request).await?;
+
+        Ok(Client::process_stream::<O>(response.body))
     }
 
     #[cfg(feature = "stream")]
@@ -306,83 +398,79 @@ impl Client {
         &self,
         path: &str,
         parameters: &I,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<Bytes, APIError>> + Send>>, APIError>
+    ) -> Result<ByteStream, APIError>
     where
         I: Serialize,
     {
-        let stream = self
-            .build_request(Method::POST, path, Some(MIME_TYPE_APPLICATION_JSON))
-            .json(&parameters)
-            .send()
-            .await
-            .unwrap()
-            .bytes_stream()
-            .map(|item| item.map_err(|error| APIError::StreamError(error.to_string())));
-
-        Ok(Box::pin(stream)
-            as Pin<
-                Box<dyn Stream<Item = Result<Bytes, APIError>> + Send>,
-            >)
+        let response = self
+            .execute_stream(self.build_json_request(Method::POST, path, parameters)?)
+            .await?;
+
+        Ok(response.body)
     }
 
     #[cfg(feature = "stream")]
-    pub(crate) async fn process_stream<O>(
-        mut event_soure: EventSource,
+    pub(crate) fn process_stream<O>(
+        stream: ByteStream,
     ) -> Pin<Box<dyn Stream<Item = Result<O, APIError>> + Send>>
     where
         O: DeserializeOwned + Send + 'static,
     {
-        use super::error::InvalidRequestError;
-
-        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-
-        tokio::spawn(async move {
-            while let Some(event_result) = event_soure.next().await {
-                match event_result {
-                    Ok(event) => match event {
-                        Event::Open => continue,
-                        Event::Message(message) => {
-                            if message.data == "[DONE]" {
-                                break;
-                            }
-
-                            let response = match serde_json::from_str::<O>(&message.data) {
-                                Ok(result) => Ok(result),
-                                Err(error) => {
-                                    match serde_json::from_str::<InvalidRequestError>(&message.data)
-                                    {
-                                        Ok(invalid_request_error) => Err(APIError::StreamError(
-                                            invalid_request_error.to_string(),
-                                        )),
-                                        Err(_) => Err(APIError::StreamError(format!(
-                                            "{} {}",
-                                            error, message.data
-                                        ))),
-                                    }
-                                }
-                            };
-
-                            if let Err(_error) = tx.send(response) {
-                                break;
-                            }
-                        }
+        use super::error::{ErrorObject, ErrorResponse};
+        use eventsource_stream::{EventStreamError, Eventsource};
+
+        let stream = stream
+            .eventsource()
+            .take_while(|event| {
+                std::future::ready(!matches!(event, Ok(event) if event.data == "[DONE]"))
+            })
+            .map(|event| match event {
+                Ok(event) => match serde_json::from_str::<O>(&event.data) {
+                    Ok(result) => Ok(result),
+                    Err(error) => match ErrorObject::from_body(&event.data) {
+                        Some(_) => Err(APIError::InvalidRequestError(Box::new(
+                            ErrorResponse::new(200, event.data),
+                        ))),
+                        None => Err(APIError::StreamError(format!("{} {}", error, event.data))),
                     },
-                    Err(error) => {
-                        if let Err(_error) = tx.send(Err(APIError::StreamError(error.to_string())))
-                        {
-                            break;
-                        }
-                    }
-                }
-            }
+                },
+                Err(EventStreamError::Transport(error)) => Err(error),
+                Err(error) => Err(APIError::StreamError(error.to_string())),
+            });
+
+        Box::pin(stream)
+    }
+}
 
-            event_soure.close();
-        });
+fn insert_header(headers: &mut HeaderMap, key: &str, value: &str) -> Result<(), APIError> {
+    let name = HeaderName::from_bytes(key.as_bytes()).map_err(|error| {
+        APIError::ValidationError(format!("invalid header name {key}: {error}"))
+    })?;
+    let value = HeaderValue::from_str(value).map_err(|error| {
+        APIError::ValidationError(format!("invalid value for header {key}: {error}"))
+    })?;
 
-        Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(rx))
+    headers.insert(name, value);
+
+    Ok(())
+}
+
+fn with_query_params(path: &str, query_params: Option<&HashMap<String, String>>) -> String {
+    match query_params {
+        Some(query_params) if !query_params.is_empty() => {
+            let encoded_query =
+                serde_html_form::to_string(query_params).unwrap_or_else(|_| "".to_string());
+
+            format!("{path}?{encoded_query}")
+        }
+        _ => path.to_string(),
     }
 }
 
+fn response_text(response: &HttpResponse) -> String {
+    String::from_utf8_lossy(&response.body).into_owned()
+}
+
 impl Default for Client {
     fn default() -> Self {
         Client {
@@ -392,6 +480,9 @@ impl Default for Client {
             headers: None,
             organization: None,
             project: None,
+            retry_policy: None,
+            transport: None,
+            middlewares: Vec::new(),
         }
     }
 }
@@ -0,0 +1,552 @@
+use crate::v1::api::Client;
+use crate::v1::error::APIError;
+use crate::v1::resources::shared::{FileUpload, MultipartField};
+#[cfg(feature = "stream")]
+use crate::v1::transport::StreamingResponse;
+use crate::v1::transport::{HttpRequest, HttpResponse, RequestBody, Transport};
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use bytes::Bytes;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Headers whose values are never written to a cassette.
+const REDACTED_HEADERS: [&str; 4] = [
+    "authorization",
+    "api-key",
+    "openai-organization",
+    "openai-project",
+];
+
+/// Query parameters whose values are never written to a cassette.
+const REDACTED_QUERY_PARAMETERS: [&str; 4] = ["api-key", "api_key", "key", "access_token"];
+
+const REDACTED: &str = "REDACTED";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CassetteMode {
+    /// Sends every request and appends the interaction to the cassette file.
+    Record,
+    /// Answers every request from the cassette file without touching the network.
+    Replay,
+}
+
+/// A transport that records request/response pairs to a JSON file and replays them later.
+///
+/// Requests are matched on method, path (including the query string) and JSON body, ignoring key order.
+/// Each recorded interaction is replayed once, in the order it was recorded.
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    inner: Option<Arc<dyn Transport>>,
+    state: Mutex<CassetteState>,
+}
+
+#[derive(Debug, Default)]
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    replayed: Vec<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CassetteFile {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// The path and query string of the request URL.
+    pub path: String,
+    /// The request headers, with credentials redacted.
+    pub headers: BTreeMap<String, String>,
+    /// The JSON body, or a summary of the fields of a multipart body.
+    pub body: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: RecordedBody,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedBody {
+    /// A UTF-8 body, including raw server-sent event streams.
+    Text(String),
+    /// A binary body, e.g. generated audio.
+    Base64(String),
+}
+
+impl Cassette {
+    /// Records every request sent through `inner` to the cassette file at `path`, replacing existing recordings.
+    pub fn record(path: impl AsRef<Path>, inner: Arc<dyn Transport>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            mode: CassetteMode::Record,
+            inner: Some(inner),
+            state: Mutex::new(CassetteState::default()),
+        }
+    }
+
+    /// Loads the cassette file at `path` to replay its interactions.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, APIError> {
+        let contents = std::fs::read_to_string(path.as_ref())
+            .map_err(|error| APIError::FileError(error.to_string()))?;
+
+        let file: CassetteFile = serde_json::from_str(&contents)
+            .map_err(|error| APIError::ParseError(error.to_string()))?;
+
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            mode: CassetteMode::Replay,
+            inner: None,
+            state: Mutex::new(CassetteState {
+                replayed: vec![false; file.interactions.len()],
+                interactions: file.interactions,
+            }),
+        })
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the recorded interactions.
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.state.lock().unwrap().interactions.clone()
+    }
+
+    fn inner(&self) -> Result<&Arc<dyn Transport>, APIError> {
+        self.inner.as_ref().ok_or_else(|| {
+            APIError::CassetteError("the cassette has no transport to record".to_string())
+        })
+    }
+
+    fn save(&self, interaction: Interaction) -> Result<(), APIError> {
+        let mut state = self.state.lock().unwrap();
+        state.interactions.push(interaction);
+
+        let file = CassetteFile {
+            interactions: state.interactions.clone(),
+        };
+
+        let contents = serde_json::to_string_pretty(&file)
+            .map_err(|error| APIError::ParseError(error.to_string()))?;
+
+        std::fs::write(&self.path, contents).map_err(|error| APIError::FileError(error.to_string()))
+    }
+
+    fn find(&self, request: &RecordedRequest) -> Result<HttpResponse, APIError> {
+        let mut state = self.state.lock().unwrap();
+        let CassetteState {
+            interactions,
+            replayed,
+        } = &mut *state;
+
+        let index = interactions
+            .iter()
+            .zip(replayed.iter())
+            .position(|(interaction, replayed)| {
+                !replayed
+                    && interaction.request.method == request.method
+                    && interaction.request.path == request.path
+                    && interaction.request.body == request.body
+            })
+            .ok_or_else(|| {
+                APIError::CassetteError(format!(
+                    "no recorded interaction in {} matches {} {}",
+                    self.path.display(),
+                    request.method,
+                    request.path
+                ))
+            })?;
+
+        replayed[index] = true;
+
+        interactions[index].response.to_response()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Transport for Cassette {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+        let recorded_request = RecordedRequest::from_request(&request);
+
+        match self.mode {
+            CassetteMode::Replay => self.find(&recorded_request),
+            CassetteMode::Record => {
+                let response = self.inner()?.send(request).await?;
+
+                self.save(Interaction {
+                    request: recorded_request,
+                    response: RecordedResponse::from_response(&response),
+                })?;
+
+                Ok(response)
+            }
+        }
+    }
+
+    /// Streams are read completely before they are recorded or replayed.
+    #[cfg(feature = "stream")]
+    async fn send_stream(&self, request: HttpRequest) -> Result<StreamingResponse, APIError> {
+        let response = match self.mode {
+            CassetteMode::Replay => self.find(&RecordedRequest::from_request(&request))?,
+            CassetteMode::Record => {
+                let recorded_request = RecordedRequest::from_request(&request);
+                let response = self.inner()?.send_stream(request).await?.collect().await?;
+
+                self.save(Interaction {
+                    request: recorded_request,
+                    response: RecordedResponse::from_response(&response),
+                })?;
+
+                response
+            }
+        };
+
+        Ok(StreamingResponse {
+            status: response.status,
+            headers: response.headers,
+            body: Box::pin(futures::stream::once(async move { Ok(response.body) })),
+        })
+    }
+}
+
+impl RecordedRequest {
+    fn from_request(request: &HttpRequest) -> Self {
+        let path = match request.url.find("://") {
+            Some(scheme_end) => {
+                let rest = &request.url[scheme_end + 3..];
+                rest.find('/').map_or("/", |index| &rest[index..])
+            }
+            None => request.url.as_str(),
+        };
+
+        let body = match &request.body {
+            RequestBody::Empty => None,
+            RequestBody::Json(body) => Some(
+                serde_json::from_slice(body)
+                    .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(body).into_owned())),
+            ),
+            RequestBody::Multipart(form) => Some(Value::Array(
+                form.fields
+                    .iter()
+                    .map(|field| match field {
+                        MultipartField::Text { name, value } => {
+                            serde_json::json!({ "name": name, "value": value })
+                        }
+                        MultipartField::File { name, file, .. } => {
+                            serde_json::json!({ "name": name, "filename": file_name(file) })
+                        }
+                    })
+                    .collect(),
+            )),
+        };
+
+        Self {
+            method: request.method.to_string(),
+            path: redacted_path(path),
+            headers: redacted_headers(&request.headers),
+            body,
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn from_response(response: &HttpResponse) -> Self {
+        let body = match std::str::from_utf8(&response.body) {
+            Ok(text) => RecordedBody::Text(text.to_string()),
+            Err(_) => RecordedBody::Base64(general_purpose::STANDARD.encode(&response.body)),
+        };
+
+        Self {
+            status: response.status.as_u16(),
+            headers: redacted_headers(&response.headers),
+            body,
+        }
+    }
+
+    fn to_response(&self) -> Result<HttpResponse, APIError> {
+        let status = StatusCode::from_u16(self.status)
+            .map_err(|error| APIError::ParseError(error.to_string()))?;
+
+        let mut headers = HeaderMap::new();
+
+        for (key, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(key.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+
+        let body = match &self.body {
+            RecordedBody::Text(text) => Bytes::from(text.clone()),
+            RecordedBody::Base64(encoded) => general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|error| APIError::ParseError(error.to_string()))?
+                .into(),
+        };
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+impl Client {
+    /// Record every request and response to the cassette file at `path`, using the current transport.
+    pub fn record_cassette(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        let cassette = Cassette::record(path, self.transport());
+
+        self.set_transport(cassette)
+    }
+
+    /// Replay the requests and responses recorded in the cassette file at `path`.
+    pub fn replay_cassette(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, APIError> {
+        let cassette = Cassette::replay(path)?;
+
+        Ok(self.set_transport(cassette))
+    }
+}
+
+fn redacted_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if REDACTED_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+fn redacted_path(path: &str) -> String {
+    let Some((path, query)) = path.split_once('?') else {
+        return path.to_string();
+    };
+
+    let query = query
+        .split('&')
+        .map(|parameter| match parameter.split_once('=') {
+            Some((name, _))
+                if REDACTED_QUERY_PARAMETERS
+                    .iter()
+                    .any(|redacted| redacted.eq_ignore_ascii_case(name)) =>
+            {
+                format!("{name}={REDACTED}")
+            }
+            _ => parameter.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+
+    format!("{path}?{query}")
+}
+
+fn file_name(file: &FileUpload) -> Option<&str> {
+    match file {
+        FileUpload::Bytes(bytes) => Some(&bytes.filename),
+        #[cfg(all(feature = "tokio", feature = "tokio-util"))]
+        FileUpload::File(path) => Some(path),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::api::Client;
+    use crate::v1::cassette::{Cassette, RecordedBody, RecordedRequest};
+    use crate::v1::error::APIError;
+    use crate::v1::resources::audio::{AudioSpeechParametersBuilder, AudioVoice};
+    use crate::v1::resources::chat::{
+        ChatCompletionParametersBuilder, ChatMessage, ChatMessageContent,
+    };
+    #[cfg(feature = "testing")]
+    use crate::v1::testing::{Mock, MockResponse, MockServer};
+    use crate::v1::transport::{HttpRequest, RequestBody};
+    use std::path::PathBuf;
+
+    fn cassette_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("openai-dive-{name}-{}.json", std::process::id()))
+    }
+
+    fn chat_parameters(content: &str) -> crate::v1::resources::chat::ChatCompletionParameters {
+        ChatCompletionParametersBuilder::default()
+            .model("gpt-4o")
+            .messages(vec![ChatMessage::User {
+                content: ChatMessageContent::Text(content.to_string()),
+                name: None,
+            }])
+            .build()
+            .unwrap()
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_records_and_replays_interactions() {
+        let server = MockServer::start().await;
+        let cassette = cassette_path("record-replay");
+
+        // The completion is requested before the stream
+        server
+            .mock(
+                Mock::post("/chat/completions")
+                    .respond_with(MockResponse::json(serde_json::json!({
+                        "id": "chatcmpl-1",
+                        "object": "chat.completion",
+                        "created": 1,
+                        "model": "gpt-4o",
+                        "choices": []
+                    })))
+                    .times(1),
+            )
+            .mock(
+                Mock::post("/chat/completions").respond_with(MockResponse::events([
+                    serde_json::json!({"id": "chatcmpl-2", "object": "chat.completion.chunk", "created": 1, "model": "gpt-4o", "choices": [{"index": 0, "delta": {"content": "Hi"}, "finish_reason": null}]}),
+                ])),
+            )
+            .mock(
+                Mock::post("/audio/speech")
+                    .respond_with(MockResponse::bytes(vec![0xff, 0xfb, 0x90], "audio/mpeg")),
+            );
+
+        let speech_parameters = AudioSpeechParametersBuilder::default()
+            .model("tts-1")
+            .input("Hello")
+            .voice(AudioVoice::Alloy)
+            .build()
+            .unwrap();
+
+        let mut client = Client::new("sk-secret".to_string());
+        client
+            .set_base_url(&server.uri())
+            .record_cassette(&cassette);
+
+        let recorded = client
+            .chat()
+            .create(chat_parameters("Hello"))
+            .await
+            .unwrap();
+        let recorded_audio = client
+            .audio()
+            .create_speech(speech_parameters.clone())
+            .await
+            .unwrap();
+
+        #[cfg(feature = "stream")]
+        {
+            use futures::StreamExt;
+
+            let chunks: Vec<_> = client
+                .chat()
+                .create_stream(chat_parameters("Hello"))
+                .await
+                .unwrap()
+                .collect()
+                .await;
+
+            assert_eq!(chunks.len(), 1);
+        }
+
+        let base_url = server.uri();
+        drop(server);
+
+        let contents = std::fs::read_to_string(&cassette).unwrap();
+        assert!(!contents.contains("sk-secret"));
+        assert!(contents.contains("REDACTED"));
+
+        let replay = Cassette::replay(&cassette).unwrap();
+        assert!(matches!(
+            replay.interactions()[1].response.body,
+            RecordedBody::Base64(_)
+        ));
+
+        let mut client = Client::new("sk-other".to_string());
+        client
+            .set_base_url(&base_url)
+            .replay_cassette(&cassette)
+            .unwrap();
+
+        let replayed = client
+            .chat()
+            .create(chat_parameters("Hello"))
+            .await
+            .unwrap();
+        let replayed_audio = client
+            .audio()
+            .create_speech(speech_parameters)
+            .await
+            .unwrap();
+
+        assert_eq!(replayed, recorded);
+        assert_eq!(replayed_audio.bytes, recorded_audio.bytes);
+
+        #[cfg(feature = "stream")]
+        {
+            use futures::StreamExt;
+
+            let chunks: Vec<_> = client
+                .chat()
+                .create_stream(chat_parameters("Hello"))
+                .await
+                .unwrap()
+                .collect()
+                .await;
+
+            assert_eq!(
+                chunks[0].as_ref().unwrap().id.as_deref(),
+                Some("chatcmpl-2")
+            );
+        }
+
+        let result = client.chat().create(chat_parameters("Other")).await;
+
+        assert!(matches!(result, Err(APIError::CassetteError(_))));
+
+        std::fs::remove_file(cassette).unwrap();
+    }
+
+    #[test]
+    fn test_redacts_credentials_in_query_string() {
+        let request = HttpRequest {
+            method: reqwest::Method::GET,
+            url: "https://example.com/v1/models?api-key=secret&limit=2&KEY=other".to_string(),
+            headers: reqwest::header::HeaderMap::new(),
+            body: RequestBody::Empty,
+        };
+
+        assert_eq!(
+            RecordedRequest::from_request(&request).path,
+            "/v1/models?api-key=REDACTED&limit=2&KEY=REDACTED"
+        );
+    }
+}
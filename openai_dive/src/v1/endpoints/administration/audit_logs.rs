@@ -2,6 +2,7 @@ use crate::v1::{
     endpoints::administration::Administration,
     error::APIError,
     helpers::format_response,
+    pagination::paginate_method,
     resources::{
         administration::audit_log::{AuditLog, AuditLogParameters},
         shared::ListResponse,
@@ -21,7 +22,7 @@ impl Administration<'_> {
     }
 }
 
-impl AuditLogs<'_> {
+impl<'a> AuditLogs<'a> {
     /// Logs of user actions and configuration changes within this organization. To log events, you must activate logging in the Organization Settings.
     pub async fn list(
         &self,
@@ -37,4 +38,10 @@ impl AuditLogs<'_> {
 
         Ok(response)
     }
+
+    paginate_method!(
+        paginate => list(AuditLogParameters) -> AuditLog,
+        client: self.administration.client,
+        endpoint: administration().audit_logs()
+    );
 }
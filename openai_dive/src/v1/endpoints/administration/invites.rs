@@ -1,6 +1,7 @@
 use crate::v1::endpoints::administration::Administration;
 use crate::v1::error::APIError;
 use crate::v1::helpers::format_response;
+use crate::v1::pagination::paginate_method;
 use crate::v1::resources::administration::invite::CreateInviteParameters;
 use crate::v1::resources::administration::invite::Invite;
 use crate::v1::resources::shared::DeletedObject;
@@ -20,7 +21,7 @@ impl Administration<'_> {
     }
 }
 
-impl Invites<'_> {
+impl<'a> Invites<'a> {
     /// Returns a list of invites in the organization.
     pub async fn list(
         &self,
@@ -37,6 +38,12 @@ impl Invites<'_> {
         Ok(response)
     }
 
+    paginate_method!(
+        paginate => list(SimpleListParameters) -> Invite,
+        client: self.administration.client,
+        endpoint: administration().invites()
+    );
+
     // Retrieves an invite.
     pub async fn retrieve(&self, invite_id: &str) -> Result<Invite, APIError> {
         let response = self
@@ -1,6 +1,7 @@
 use crate::v1::endpoints::administration::Administration;
 use crate::v1::error::APIError;
 use crate::v1::helpers::format_response;
+use crate::v1::pagination::paginate_method;
 use crate::v1::resources::administration::project_api_key::ProjectApiKey;
 use crate::v1::resources::shared::DeletedObject;
 use crate::v1::resources::shared::ListResponse;
@@ -19,7 +20,7 @@ impl Administration<'_> {
     }
 }
 
-impl ProjectApiKeys<'_> {
+impl<'a> ProjectApiKeys<'a> {
     /// Returns a list of API keys in the project.
     pub async fn list(
         &self,
@@ -40,6 +41,12 @@ impl ProjectApiKeys<'_> {
         Ok(response)
     }
 
+    paginate_method!(
+        paginate => list(project_id, SimpleListParameters) -> ProjectApiKey,
+        client: self.administration.client,
+        endpoint: administration().project_api_keys()
+    );
+
     /// Retrieves an API key in the project.
     pub async fn retrieve(
         &self,
@@ -2,6 +2,7 @@ use crate::v1::{
     endpoints::administration::Administration,
     error::APIError,
     helpers::format_response,
+    pagination::paginate_method,
     resources::{
         administration::project_rate_limit::{ModifyProjectRateLimitParameters, ProjectRateLimit},
         shared::{ListParameters, ListResponse},
@@ -21,7 +22,7 @@ impl Administration<'_> {
     }
 }
 
-impl ProjectRateLimits<'_> {
+impl<'a> ProjectRateLimits<'a> {
     /// Returns the rate limits per model for a project.
     pub async fn list(
         &self,
@@ -42,6 +43,12 @@ impl ProjectRateLimits<'_> {
         Ok(response)
     }
 
+    paginate_method!(
+        paginate => list(project_id, ListParameters) -> ProjectRateLimit,
+        client: self.administration.client,
+        endpoint: administration().project_rate_limits()
+    );
+
     /// Updates a project rate limit.
     pub async fn modify(
         &self,
@@ -1,6 +1,7 @@
 use crate::v1::endpoints::administration::Administration;
 use crate::v1::error::APIError;
 use crate::v1::helpers::format_response;
+use crate::v1::pagination::paginate_method;
 use crate::v1::resources::administration::project_service_account::CreateProjectServiceAccountParameters;
 use crate::v1::resources::administration::project_service_account::ProjectServiceAccount;
 use crate::v1::resources::shared::DeletedObject;
@@ -20,7 +21,7 @@ impl Administration<'_> {
     }
 }
 
-impl ProjectServiceAccounts<'_> {
+impl<'a> ProjectServiceAccounts<'a> {
     /// Returns a list of users in the project.
     pub async fn list(
         &self,
@@ -41,6 +42,12 @@ impl ProjectServiceAccounts<'_> {
         Ok(response)
     }
 
+    paginate_method!(
+        paginate => list(project_id, SimpleListParameters) -> ProjectServiceAccount,
+        client: self.administration.client,
+        endpoint: administration().project_service_accounts()
+    );
+
     /// Retrieves a service account in the project.
     pub async fn retrieve(
         &self,
@@ -1,6 +1,7 @@
 use crate::v1::endpoints::administration::Administration;
 use crate::v1::error::APIError;
 use crate::v1::helpers::format_response;
+use crate::v1::pagination::paginate_method;
 use crate::v1::resources::administration::project_user::CreateProjectUserParameters;
 use crate::v1::resources::administration::project_user::ModifyProjectUserParameters;
 use crate::v1::resources::administration::project_user::ProjectUser;
@@ -21,7 +22,7 @@ impl Administration<'_> {
     }
 }
 
-impl ProjectUsers<'_> {
+impl<'a> ProjectUsers<'a> {
     /// Returns a list of users in the project.
     pub async fn list(
         &self,
@@ -42,6 +43,12 @@ impl ProjectUsers<'_> {
         Ok(response)
     }
 
+    paginate_method!(
+        paginate => list(project_id, SimpleListParameters) -> ProjectUser,
+        client: self.administration.client,
+        endpoint: administration().project_users()
+    );
+
     /// Retrieves a user in the project.
     pub async fn retrieve(&self, project_id: &str, user_id: &str) -> Result<ProjectUser, APIError> {
         let response = self
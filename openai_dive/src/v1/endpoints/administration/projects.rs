@@ -1,6 +1,7 @@
 use crate::v1::endpoints::administration::Administration;
 use crate::v1::error::APIError;
 use crate::v1::helpers::format_response;
+use crate::v1::pagination::paginate_method;
 use crate::v1::resources::administration::project::CreateProjectParameters;
 use crate::v1::resources::administration::project::ModifyProjectParameters;
 use crate::v1::resources::administration::project::Project;
@@ -20,7 +21,7 @@ impl Administration<'_> {
     }
 }
 
-impl Projects<'_> {
+impl<'a> Projects<'a> {
     /// Lists all of the projects in the organization.
     pub async fn list(
         &self,
@@ -37,6 +38,12 @@ impl Projects<'_> {
         Ok(response)
     }
 
+    paginate_method!(
+        paginate => list(SimpleListParameters) -> Project,
+        client: self.administration.client,
+        endpoint: administration().projects()
+    );
+
     /// Retrieves a project by their identifier.
     pub async fn retrieve(&self, project_id: &str) -> Result<Project, APIError> {
         let response = self
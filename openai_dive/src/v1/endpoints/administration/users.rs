@@ -1,6 +1,7 @@
 use crate::v1::endpoints::administration::Administration;
 use crate::v1::error::APIError;
 use crate::v1::helpers::format_response;
+use crate::v1::pagination::paginate_method;
 use crate::v1::resources::administration::user::ModifyUserParameters;
 use crate::v1::resources::administration::user::User;
 use crate::v1::resources::shared::DeletedObject;
@@ -20,7 +21,7 @@ impl Administration<'_> {
     }
 }
 
-impl Users<'_> {
+impl<'a> Users<'a> {
     /// Lists all of the users in the organization.
     pub async fn list(
         &self,
@@ -37,6 +38,12 @@ impl Users<'_> {
         Ok(response)
     }
 
+    paginate_method!(
+        paginate => list(SimpleListParameters) -> User,
+        client: self.administration.client,
+        endpoint: administration().users()
+    );
+
     /// Retrieves a user by their identifier.
     pub async fn retrieve(&self, user_id: &str) -> Result<User, APIError> {
         let response = self
@@ -5,6 +5,7 @@ use crate::v1::resources::audio::AudioSpeechResponse;
 #[cfg(feature = "stream")]
 use crate::v1::resources::audio::AudioSpeechResponseChunkResponse;
 use crate::v1::resources::audio::{AudioTranscriptionParameters, AudioTranslationParameters};
+use crate::v1::resources::shared::MultipartForm;
 #[cfg(feature = "stream")]
 use futures::Stream;
 #[cfg(feature = "stream")]
@@ -40,13 +41,9 @@ impl Audio<'_> {
         &self,
         parameters: AudioTranscriptionParameters,
     ) -> Result<String, APIError> {
-        let mut form = reqwest::multipart::Form::new();
-
-        let file = parameters.file.into_part().await?;
-
-        form = form.part("file", file);
-
-        form = form.text("model", parameters.model);
+        let mut form = MultipartForm::new()
+            .file("file", parameters.file)
+            .text("model", parameters.model);
 
         if let Some(prompt) = parameters.prompt {
             form = form.text("prompt", prompt);
@@ -91,7 +88,7 @@ impl Audio<'_> {
                     }
                 }
                 _ => {
-                    return Err(APIError::BadRequestError(
+                    return Err(APIError::ValidationError(
                         "extra_body must be formatted as a map of key: value".to_string(),
                     ));
                 }
@@ -111,12 +108,9 @@ impl Audio<'_> {
         &self,
         parameters: AudioTranslationParameters,
     ) -> Result<String, APIError> {
-        let mut form = reqwest::multipart::Form::new();
-
-        let file = parameters.file.into_part().await?;
-        form = form.part("file", file);
-
-        form = form.text("model", parameters.model);
+        let mut form = MultipartForm::new()
+            .file("file", parameters.file)
+            .text("model", parameters.model);
 
         if let Some(prompt) = parameters.prompt {
             form = form.text("prompt", prompt);
@@ -161,8 +155,7 @@ impl Audio<'_> {
         let stream = Box::pin(
             self.client
                 .post_stream_raw("/audio/speech", &stream_parameters)
-                .await
-                .unwrap()
+                .await?
                 .map(|item| item.map(|bytes| AudioSpeechResponseChunkResponse { bytes })),
         );
 
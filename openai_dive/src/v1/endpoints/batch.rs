@@ -2,6 +2,7 @@ use crate::v1::{
     api::Client,
     error::APIError,
     helpers::format_response,
+    pagination::paginate_method,
     resources::{
         batch::{Batch, CreateBatchParameters},
         shared::{ListResponse, SimpleListParameters},
@@ -19,7 +20,7 @@ impl Client {
     }
 }
 
-impl Batches<'_> {
+impl<'a> Batches<'a> {
     /// Creates and executes a batch from an uploaded file of requests
     pub async fn create(&self, parameters: CreateBatchParameters) -> Result<Batch, APIError> {
         let response = self.client.post("/batches", &parameters, None).await?;
@@ -61,4 +62,10 @@ impl Batches<'_> {
 
         Ok(response)
     }
+
+    paginate_method!(
+        paginate => list(SimpleListParameters) -> Batch,
+        client: self.client,
+        endpoint: batches()
+    );
 }
@@ -1,13 +1,27 @@
 use crate::v1::error::APIError;
+use crate::v1::pagination::paginate_method;
 #[cfg(feature = "stream")]
-use crate::v1::resources::chat::ChatCompletionChunkResponse;
+use crate::v1::partial_json::parse_partial;
 #[cfg(feature = "stream")]
-use crate::v1::resources::chat::DeltaChatMessage;
-use crate::v1::resources::chat::{ChatCompletionParameters, ChatCompletionResponse};
-use crate::v1::resources::shared::ResponseWrapper;
+use crate::v1::resources::chat::{
+    ChatCompletionAccumulator, ChatCompletionChunkResponse, DeltaChatMessage,
+};
+use crate::v1::resources::chat::{
+    ChatCompletionParameters, ChatCompletionResponse, ChatCompletionStoredMessage,
+    ListChatCompletionsParameters, UpdateChatCompletionParameters,
+};
+#[cfg(feature = "schema")]
+use crate::v1::resources::chat::{ChatCompletionResponseFormat, ChatMessage, JsonSchema};
+#[cfg(feature = "schema")]
+use crate::v1::resources::shared::FinishReason;
+use crate::v1::resources::shared::{DeletedObject, ListParameters, ListResponse, ResponseWrapper};
+#[cfg(feature = "schema")]
+use crate::v1::schema::{Parsed, StructuredOutput};
 use crate::v1::{api::Client, helpers::format_response};
 #[cfg(feature = "stream")]
-use futures::Stream;
+use futures::{Stream, StreamExt};
+#[cfg(any(feature = "schema", feature = "stream"))]
+use serde::de::DeserializeOwned;
 #[cfg(feature = "stream")]
 use std::pin::Pin;
 #[cfg(feature = "stream")]
@@ -24,7 +38,7 @@ impl Client {
     }
 }
 
-impl Chat<'_> {
+impl<'a> Chat<'a> {
     /// Creates a model response for the given chat conversation.
     pub async fn create(
         &self,
@@ -60,6 +74,138 @@ impl Chat<'_> {
         })
     }
 
+    /// Get a stored chat completion. Only chat completions that have been created with the `store` parameter set to true will be returned.
+    pub async fn retrieve(&self, completion_id: &str) -> Result<ChatCompletionResponse, APIError> {
+        let response = self
+            .client
+            .get(&format!("/chat/completions/{completion_id}"))
+            .await?;
+
+        let response: ChatCompletionResponse = format_response(response)?;
+
+        Ok(response)
+    }
+
+    /// List stored chat completions. Only chat completions that have been stored with the `store` parameter set to true will be returned.
+    pub async fn list(
+        &self,
+        query: Option<ListChatCompletionsParameters>,
+    ) -> Result<ListResponse<ChatCompletionResponse>, APIError> {
+        let response = self
+            .client
+            .get_with_query("/chat/completions", &query)
+            .await?;
+
+        let response: ListResponse<ChatCompletionResponse> = format_response(response)?;
+
+        Ok(response)
+    }
+
+    paginate_method!(
+        paginate => list(ListChatCompletionsParameters) -> ChatCompletionResponse,
+        client: self.client,
+        endpoint: chat()
+    );
+
+    /// Modify a stored chat completion. Currently, the only supported modification is to update the metadata field.
+    pub async fn update(
+        &self,
+        completion_id: &str,
+        parameters: UpdateChatCompletionParameters,
+    ) -> Result<ChatCompletionResponse, APIError> {
+        let response = self
+            .client
+            .post(
+                &format!("/chat/completions/{completion_id}"),
+                &parameters,
+                None,
+            )
+            .await?;
+
+        let response: ChatCompletionResponse = format_response(response.data)?;
+
+        Ok(response)
+    }
+
+    /// Delete a stored chat completion.
+    pub async fn delete(&self, completion_id: &str) -> Result<DeletedObject, APIError> {
+        let response = self
+            .client
+            .delete(&format!("/chat/completions/{completion_id}"))
+            .await?;
+
+        let response: DeletedObject = format_response(response)?;
+
+        Ok(response)
+    }
+
+    /// Get the messages in a stored chat completion.
+    pub async fn list_messages(
+        &self,
+        completion_id: &str,
+        query: Option<ListParameters>,
+    ) -> Result<ListResponse<ChatCompletionStoredMessage>, APIError> {
+        let response = self
+            .client
+            .get_with_query(
+                &format!("/chat/completions/{completion_id}/messages"),
+                &query,
+            )
+            .await?;
+
+        let response: ListResponse<ChatCompletionStoredMessage> = format_response(response)?;
+
+        Ok(response)
+    }
+
+    paginate_method!(
+        paginate_messages => list_messages(completion_id, ListParameters) -> ChatCompletionStoredMessage,
+        client: self.client,
+        endpoint: chat()
+    );
+
+    /// Creates a model response with the JSON Schema of `T` as response format and deserializes the answer into `T`.
+    #[cfg(feature = "schema")]
+    pub async fn create_parsed<T>(
+        &self,
+        parameters: ChatCompletionParameters,
+    ) -> Result<Parsed<ChatCompletionResponse, T>, APIError>
+    where
+        T: schemars::JsonSchema + DeserializeOwned,
+    {
+        let response = self
+            .create(ChatCompletionParameters {
+                response_format: Some(ChatCompletionResponseFormat::JsonSchema {
+                    json_schema: JsonSchema::from_type::<T>()?,
+                }),
+                ..parameters
+            })
+            .await?;
+
+        let choice = response.choices.first();
+
+        let output = match choice.map(|choice| (&choice.message, &choice.finish_reason)) {
+            Some((
+                ChatMessage::Assistant {
+                    refusal: Some(refusal),
+                    ..
+                },
+                _,
+            )) => StructuredOutput::Refusal(refusal.clone()),
+            Some((message, Some(FinishReason::TokenLimitReached))) => StructuredOutput::Truncated {
+                reason: "length".to_string(),
+                text: message.text().unwrap_or_default().to_string(),
+            },
+            Some((message, _)) => StructuredOutput::parse(message.text().unwrap_or_default()),
+            None => StructuredOutput::Invalid {
+                text: String::new(),
+                error: "the response contains no choices".to_string(),
+            },
+        };
+
+        Ok(Parsed { response, output })
+    }
+
     #[cfg(feature = "stream")]
     /// Creates a model response for the given chat conversation.
     pub async fn create_stream(
@@ -75,14 +221,13 @@ impl Chat<'_> {
         };
         stream_parameters.stream = Some(true);
 
-        Ok(self
-            .client
+        self.client
             .post_stream(
                 "/chat/completions",
                 &stream_parameters,
                 stream_parameters.query_params.as_ref(),
             )
-            .await)
+            .await
     }
 }
 
@@ -179,3 +324,245 @@ where
         }
     }
 }
+
+/// Passes the chunks of a chat completion stream through while accumulating them into a `ChatCompletionResponse`.
+#[cfg(feature = "stream")]
+pub struct AccumulatingStream<S> {
+    stream: S,
+    accumulator: ChatCompletionAccumulator,
+}
+
+#[cfg(feature = "stream")]
+impl<S> AccumulatingStream<S>
+where
+    S: Stream<Item = Result<ChatCompletionChunkResponse, APIError>> + Unpin,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            accumulator: ChatCompletionAccumulator::new(),
+        }
+    }
+
+    /// Returns the response accumulated from the chunks received so far.
+    pub fn response(&self) -> ChatCompletionResponse {
+        self.accumulator.response()
+    }
+
+    /// Parses the content of the first choice received so far, e.g. a streamed structured output, see `parse_partial`.
+    pub fn partial_content<T: DeserializeOwned>(&self) -> Option<T> {
+        parse_partial(self.accumulator.content(0)?)
+    }
+
+    /// Parses the arguments of a tool call of the first choice received so far, see `parse_partial`.
+    pub fn partial_tool_arguments<T: DeserializeOwned>(&self, tool_call_index: u32) -> Option<T> {
+        parse_partial(self.accumulator.tool_call_arguments(0, tool_call_index)?)
+    }
+
+    /// Consumes the rest of the stream and returns the complete response.
+    pub async fn final_response(mut self) -> Result<ChatCompletionResponse, APIError> {
+        while let Some(chunk) = self.next().await {
+            chunk?;
+        }
+
+        Ok(self.accumulator.response())
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<S> Stream for AccumulatingStream<S>
+where
+    S: Stream<Item = Result<ChatCompletionChunkResponse, APIError>> + Unpin,
+{
+    type Item = Result<ChatCompletionChunkResponse, APIError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        let poll = Pin::new(&mut this.stream).poll_next(cx);
+
+        if let Poll::Ready(Some(Ok(chunk))) = &poll {
+            this.accumulator.push(chunk);
+        }
+
+        poll
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::v1::resources::chat::{
+        ChatMessage, ListChatCompletionsParametersBuilder, UpdateChatCompletionParametersBuilder,
+    };
+    use crate::v1::resources::shared::{ListParameters, SortOrder};
+    use crate::v1::testing::{Mock, MockResponse, MockServer};
+    use reqwest::Method;
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    fn completion_json() -> Value {
+        json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1,
+            "model": "gpt-4o",
+            "choices": [],
+            "metadata": {"pipeline": "distillation"}
+        })
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_stored_completion() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::get("/chat/completions/chatcmpl-1")
+                .respond_with(MockResponse::json(completion_json())),
+        );
+
+        let completion = server.client().chat().retrieve("chatcmpl-1").await.unwrap();
+        assert_eq!(completion.id.as_deref(), Some("chatcmpl-1"));
+
+        let request = &server.received_requests()[0];
+        assert_eq!(request.method, Method::GET);
+        assert_eq!(request.path, "/chat/completions/chatcmpl-1");
+        assert!(request.body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_stored_completions_sends_query() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::get("/chat/completions").respond_with(MockResponse::json(json!({
+                "object": "list",
+                "data": [completion_json()],
+                "first_id": "chatcmpl-1",
+                "last_id": "chatcmpl-1",
+                "has_more": false
+            }))),
+        );
+
+        let query = ListChatCompletionsParametersBuilder::default()
+            .model("gpt-4o")
+            .metadata(HashMap::from([(
+                "pipeline".to_string(),
+                "distillation".to_string(),
+            )]))
+            .after("chatcmpl-0")
+            .limit(10u32)
+            .order(SortOrder::Asc)
+            .build()
+            .unwrap();
+
+        let completions = server.client().chat().list(Some(query)).await.unwrap();
+        assert_eq!(completions.data.len(), 1);
+
+        let request = &server.received_requests()[0];
+        assert_eq!(request.method, Method::GET);
+        assert_eq!(request.path, "/chat/completions");
+        assert_eq!(
+            request.query.as_deref(),
+            Some("model=gpt-4o&metadata%5Bpipeline%5D=distillation&after=chatcmpl-0&limit=10&order=asc")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_stored_completion_sends_metadata() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/chat/completions/chatcmpl-1")
+                .respond_with(MockResponse::json(completion_json())),
+        );
+
+        let parameters = UpdateChatCompletionParametersBuilder::default()
+            .metadata(HashMap::from([(
+                "pipeline".to_string(),
+                "distillation".to_string(),
+            )]))
+            .build()
+            .unwrap();
+
+        server
+            .client()
+            .chat()
+            .update("chatcmpl-1", parameters)
+            .await
+            .unwrap();
+
+        let request = &server.received_requests()[0];
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.path, "/chat/completions/chatcmpl-1");
+        assert_eq!(
+            request.json(),
+            Some(json!({"metadata": {"pipeline": "distillation"}}))
+        );
+
+        // Without metadata the field is sent as null, which removes the metadata
+        server
+            .client()
+            .chat()
+            .update("chatcmpl-1", Default::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            server.received_requests()[1].json(),
+            Some(json!({"metadata": null}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_stored_completion() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::delete("/chat/completions/chatcmpl-1").respond_with(MockResponse::json(json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion.deleted",
+                "deleted": true
+            }))),
+        );
+
+        let deleted = server.client().chat().delete("chatcmpl-1").await.unwrap();
+        assert!(deleted.deleted);
+
+        let request = &server.received_requests()[0];
+        assert_eq!(request.method, Method::DELETE);
+        assert_eq!(request.path, "/chat/completions/chatcmpl-1");
+    }
+
+    #[tokio::test]
+    async fn test_list_stored_completion_messages_sends_query() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::get("/chat/completions/chatcmpl-1/messages").respond_with(MockResponse::json(
+                json!({
+                    "object": "list",
+                    "data": [{"id": "chatcmpl-1-0", "role": "user", "content": "Hello"}],
+                    "first_id": "chatcmpl-1-0",
+                    "last_id": "chatcmpl-1-0",
+                    "has_more": false
+                }),
+            )),
+        );
+
+        let query = ListParameters {
+            limit: Some(5),
+            order: Some("desc".to_string()),
+            after: None,
+            before: None,
+        };
+
+        let messages = server
+            .client()
+            .chat()
+            .list_messages("chatcmpl-1", Some(query))
+            .await
+            .unwrap();
+        assert_eq!(messages.data[0].id, "chatcmpl-1-0");
+        assert!(matches!(messages.data[0].message, ChatMessage::User { .. }));
+
+        let request = &server.received_requests()[0];
+        assert_eq!(request.method, Method::GET);
+        assert_eq!(request.path, "/chat/completions/chatcmpl-1/messages");
+        assert_eq!(request.query.as_deref(), Some("limit=5&order=desc"));
+    }
+}
@@ -0,0 +1,422 @@
+use crate::v1::api::Client;
+use crate::v1::error::APIError;
+use crate::v1::helpers::format_response;
+use crate::v1::pagination::paginate_method;
+use crate::v1::resources::conversation::{
+    ConversationObject, CreateConversationItemsParameters, CreateConversationParameters,
+    IncludeParameters, ListConversationItemsParameters, UpdateConversationParameters,
+};
+use crate::v1::resources::response::request::{ResponseInclude, ResponseInputItem};
+use crate::v1::resources::shared::{DeletedObject, ListResponse};
+
+pub struct Conversations<'a> {
+    pub client: &'a Client,
+}
+
+impl Client {
+    /// Create and manage conversations to store and retrieve conversation state across Response API calls.
+    pub fn conversations(&self) -> Conversations<'_> {
+        Conversations { client: self }
+    }
+}
+
+impl<'a> Conversations<'a> {
+    /// Create a conversation.
+    pub async fn create(
+        &self,
+        parameters: CreateConversationParameters,
+    ) -> Result<ConversationObject, APIError> {
+        let response = self
+            .client
+            .post("/conversations", &parameters, None)
+            .await?;
+
+        let response: ConversationObject = format_response(response.data)?;
+
+        Ok(response)
+    }
+
+    /// Get a conversation.
+    pub async fn retrieve(&self, conversation_id: &str) -> Result<ConversationObject, APIError> {
+        let response = self
+            .client
+            .get(&format!("/conversations/{conversation_id}"))
+            .await?;
+
+        let response: ConversationObject = format_response(response)?;
+
+        Ok(response)
+    }
+
+    /// Update the metadata of a conversation.
+    pub async fn update(
+        &self,
+        conversation_id: &str,
+        parameters: UpdateConversationParameters,
+    ) -> Result<ConversationObject, APIError> {
+        let response = self
+            .client
+            .post(
+                &format!("/conversations/{conversation_id}"),
+                &parameters,
+                None,
+            )
+            .await?;
+
+        let response: ConversationObject = format_response(response.data)?;
+
+        Ok(response)
+    }
+
+    /// Delete a conversation. Items in the conversation will not be deleted.
+    pub async fn delete(&self, conversation_id: &str) -> Result<DeletedObject, APIError> {
+        let response = self
+            .client
+            .delete(&format!("/conversations/{conversation_id}"))
+            .await?;
+
+        let response: DeletedObject = format_response(response)?;
+
+        Ok(response)
+    }
+
+    /// List all items for a conversation with the given ID.
+    pub async fn list_items(
+        &self,
+        conversation_id: &str,
+        query: Option<ListConversationItemsParameters>,
+    ) -> Result<ListResponse<ResponseInputItem>, APIError> {
+        let response = self
+            .client
+            .get_with_query(&format!("/conversations/{conversation_id}/items"), &query)
+            .await?;
+
+        let response: ListResponse<ResponseInputItem> = format_response(response)?;
+
+        Ok(response)
+    }
+
+    paginate_method!(
+        paginate_items => list_items(conversation_id, ListConversationItemsParameters) -> ResponseInputItem,
+        client: self.client,
+        endpoint: conversations()
+    );
+
+    /// Create items in a conversation with the given ID.
+    pub async fn create_items(
+        &self,
+        conversation_id: &str,
+        parameters: CreateConversationItemsParameters,
+        include: Option<Vec<ResponseInclude>>,
+    ) -> Result<ListResponse<ResponseInputItem>, APIError> {
+        let query = serde_html_form::to_string(&IncludeParameters { include })
+            .map_err(|error| APIError::ParseError(error.to_string()))?;
+
+        let path = if query.is_empty() {
+            format!("/conversations/{conversation_id}/items")
+        } else {
+            format!("/conversations/{conversation_id}/items?{query}")
+        };
+
+        let response = self.client.post(&path, &parameters, None).await?;
+
+        let response: ListResponse<ResponseInputItem> = format_response(response.data)?;
+
+        Ok(response)
+    }
+
+    /// Get a single item from a conversation with the given IDs.
+    pub async fn retrieve_item(
+        &self,
+        conversation_id: &str,
+        item_id: &str,
+        include: Option<Vec<ResponseInclude>>,
+    ) -> Result<ResponseInputItem, APIError> {
+        let response = self
+            .client
+            .get_with_query(
+                &format!("/conversations/{conversation_id}/items/{item_id}"),
+                &IncludeParameters { include },
+            )
+            .await?;
+
+        let response: ResponseInputItem = format_response(response)?;
+
+        Ok(response)
+    }
+
+    /// Delete an item from a conversation with the given IDs. Returns the updated conversation.
+    pub async fn delete_item(
+        &self,
+        conversation_id: &str,
+        item_id: &str,
+    ) -> Result<ConversationObject, APIError> {
+        let response = self
+            .client
+            .delete(&format!("/conversations/{conversation_id}/items/{item_id}"))
+            .await?;
+
+        let response: ConversationObject = format_response(response)?;
+
+        Ok(response)
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::v1::resources::conversation::{
+        CreateConversationItemsParametersBuilder, CreateConversationParametersBuilder,
+        ListConversationItemsParametersBuilder, UpdateConversationParametersBuilder,
+    };
+    use crate::v1::resources::response::request::{ResponseInclude, ResponseInputItem};
+    use crate::v1::testing::{Mock, MockResponse, MockServer};
+    use reqwest::Method;
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    fn conversation_json() -> Value {
+        json!({
+            "id": "conv_1",
+            "object": "conversation",
+            "created_at": 1741900000,
+            "metadata": {"topic": "demo"}
+        })
+    }
+
+    fn message_json() -> Value {
+        json!({
+            "type": "message",
+            "id": "msg_1",
+            "status": "completed",
+            "role": "user",
+            "content": [{"type": "input_text", "text": "Hello!"}]
+        })
+    }
+
+    fn items_json() -> Value {
+        json!({
+            "object": "list",
+            "data": [message_json()],
+            "first_id": "msg_1",
+            "last_id": "msg_1",
+            "has_more": false
+        })
+    }
+
+    fn metadata() -> HashMap<String, String> {
+        HashMap::from([("topic".to_string(), "demo".to_string())])
+    }
+
+    #[tokio::test]
+    async fn test_create_conversation_sends_items_and_metadata() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/conversations").respond_with(MockResponse::json(conversation_json())),
+        );
+
+        let parameters = CreateConversationParametersBuilder::default()
+            .items(vec![serde_json::from_value::<ResponseInputItem>(
+                message_json(),
+            )
+            .unwrap()])
+            .metadata(metadata())
+            .build()
+            .unwrap();
+
+        let conversation = server
+            .client()
+            .conversations()
+            .create(parameters)
+            .await
+            .unwrap();
+        assert_eq!(conversation.id, "conv_1");
+
+        let request = &server.received_requests()[0];
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.path, "/conversations");
+
+        let body = request.json().unwrap();
+        assert_eq!(body["items"][0]["type"], "message");
+        assert_eq!(body["items"][0]["content"][0]["text"], "Hello!");
+        assert_eq!(body["metadata"], json!({"topic": "demo"}));
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_update_and_delete_conversation() {
+        let server = MockServer::start().await;
+        server
+            .mock(
+                Mock::get("/conversations/conv_1")
+                    .respond_with(MockResponse::json(conversation_json())),
+            )
+            .mock(
+                Mock::post("/conversations/conv_1")
+                    .respond_with(MockResponse::json(conversation_json())),
+            )
+            .mock(
+                Mock::delete("/conversations/conv_1").respond_with(MockResponse::json(json!({
+                    "id": "conv_1",
+                    "object": "conversation.deleted",
+                    "deleted": true
+                }))),
+            );
+
+        let client = server.client();
+
+        let conversation = client.conversations().retrieve("conv_1").await.unwrap();
+        assert_eq!(conversation.metadata, Some(metadata()));
+
+        let parameters = UpdateConversationParametersBuilder::default()
+            .metadata(metadata())
+            .build()
+            .unwrap();
+        client
+            .conversations()
+            .update("conv_1", parameters)
+            .await
+            .unwrap();
+
+        let deleted = client.conversations().delete("conv_1").await.unwrap();
+        assert!(deleted.deleted);
+
+        let requests = server.received_requests();
+        assert_eq!(
+            requests
+                .iter()
+                .map(|request| (request.method.clone(), request.path.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Method::GET, "/conversations/conv_1"),
+                (Method::POST, "/conversations/conv_1"),
+                (Method::DELETE, "/conversations/conv_1"),
+            ]
+        );
+        assert!(requests[0].body.is_empty());
+        assert_eq!(
+            requests[1].json(),
+            Some(json!({"metadata": {"topic": "demo"}}))
+        );
+        assert!(requests[2].body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_items_sends_query() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::get("/conversations/conv_1/items").respond_with(MockResponse::json(items_json())),
+        );
+
+        let query = ListConversationItemsParametersBuilder::default()
+            .after("msg_0")
+            .limit(10u32)
+            .order("asc")
+            .include(vec![ResponseInclude::FileSearchCallResults])
+            .build()
+            .unwrap();
+
+        let items = server
+            .client()
+            .conversations()
+            .list_items("conv_1", Some(query))
+            .await
+            .unwrap();
+        assert!(matches!(items.data[..], [ResponseInputItem::Message(_)]));
+
+        let request = &server.received_requests()[0];
+        assert_eq!(request.method, Method::GET);
+        assert_eq!(request.path, "/conversations/conv_1/items");
+        assert_eq!(
+            request.query.as_deref(),
+            Some("after=msg_0&limit=10&order=asc&include%5B%5D=file_search_call.results")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_items_sends_items_and_include() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/conversations/conv_1/items")
+                .respond_with(MockResponse::json(items_json())),
+        );
+
+        let parameters = CreateConversationItemsParametersBuilder::default()
+            .items(vec![serde_json::from_value::<ResponseInputItem>(
+                message_json(),
+            )
+            .unwrap()])
+            .build()
+            .unwrap();
+
+        let client = server.client();
+        client
+            .conversations()
+            .create_items("conv_1", parameters.clone(), None)
+            .await
+            .unwrap();
+        client
+            .conversations()
+            .create_items(
+                "conv_1",
+                parameters,
+                Some(vec![ResponseInclude::MessageOutputTextLogprobs]),
+            )
+            .await
+            .unwrap();
+
+        let requests = server.received_requests();
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[0].path, "/conversations/conv_1/items");
+        assert_eq!(requests[0].query, None);
+        assert_eq!(requests[0].json().unwrap()["items"][0]["id"], "msg_1");
+        assert!(requests[0].json().unwrap().get("include").is_none());
+        assert_eq!(
+            requests[1].query.as_deref(),
+            Some("include%5B%5D=message.output_text.logprobs")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_and_delete_item() {
+        let server = MockServer::start().await;
+        server
+            .mock(
+                Mock::get("/conversations/conv_1/items/msg_1")
+                    .respond_with(MockResponse::json(message_json())),
+            )
+            .mock(
+                Mock::delete("/conversations/conv_1/items/msg_1")
+                    .respond_with(MockResponse::json(conversation_json())),
+            );
+
+        let client = server.client();
+
+        let item = client
+            .conversations()
+            .retrieve_item(
+                "conv_1",
+                "msg_1",
+                Some(vec![ResponseInclude::MessageInputImageUrls]),
+            )
+            .await
+            .unwrap();
+        assert!(matches!(item, ResponseInputItem::Message(_)));
+
+        let conversation = client
+            .conversations()
+            .delete_item("conv_1", "msg_1")
+            .await
+            .unwrap();
+        assert_eq!(conversation.id, "conv_1");
+
+        let requests = server.received_requests();
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(requests[0].path, "/conversations/conv_1/items/msg_1");
+        assert_eq!(
+            requests[0].query.as_deref(),
+            Some("include%5B%5D=message.input_image.image_url")
+        );
+        assert_eq!(requests[1].method, Method::DELETE);
+        assert_eq!(requests[1].path, "/conversations/conv_1/items/msg_1");
+        assert!(requests[1].body.is_empty());
+    }
+}
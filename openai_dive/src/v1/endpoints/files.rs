@@ -1,10 +1,12 @@
 use crate::v1::api::Client;
 use crate::v1::error::APIError;
 use crate::v1::helpers::format_response;
+use crate::v1::pagination::paginate_method;
 use crate::v1::resources::file::ListFilesParameters;
 use crate::v1::resources::file::{File, UploadFileParameters};
 use crate::v1::resources::shared::DeletedObject;
 use crate::v1::resources::shared::ListResponse;
+use crate::v1::resources::shared::MultipartForm;
 
 pub struct Files<'a> {
     pub client: &'a Client,
@@ -17,7 +19,7 @@ impl Client {
     }
 }
 
-impl Files<'_> {
+impl<'a> Files<'a> {
     /// Returns a list of files that belong to the user's organization.
     pub async fn list(
         &self,
@@ -30,14 +32,17 @@ impl Files<'_> {
         Ok(response)
     }
 
+    paginate_method!(
+        paginate => list(ListFilesParameters) -> File,
+        client: self.client,
+        endpoint: files()
+    );
+
     /// Upload a file that can be used across various endpoints.
     pub async fn upload(&self, parameters: UploadFileParameters) -> Result<File, APIError> {
-        let mut form = reqwest::multipart::Form::new();
-
-        let file = parameters.file.into_part().await?;
-        form = form.part("file", file);
-
-        form = form.text("purpose", parameters.purpose.to_string());
+        let form = MultipartForm::new()
+            .file("file", parameters.file)
+            .text("purpose", parameters.purpose.to_string());
 
         let response = self.client.post_with_form("/files", form).await?;
 
@@ -1,6 +1,7 @@
 use crate::v1::api::Client;
 use crate::v1::error::APIError;
 use crate::v1::helpers::format_response;
+use crate::v1::pagination::paginate_method;
 use crate::v1::resources::fine_tuning::CreateFineTuningJobParameters;
 use crate::v1::resources::fine_tuning::FineTuningJob;
 use crate::v1::resources::fine_tuning::FineTuningJobCheckpoint;
@@ -20,7 +21,7 @@ impl Client {
     }
 }
 
-impl FineTuning<'_> {
+impl<'a> FineTuning<'a> {
     /// Creates a job that fine-tunes a specified model from a given dataset.
     pub async fn create(
         &self,
@@ -51,6 +52,15 @@ impl FineTuning<'_> {
         Ok(response)
     }
 
+    paginate_method!(
+        ///
+        /// The fine-tuning endpoints return no `last_id`, so the cursor is taken from the last item.
+        paginate => list(SimpleListParameters) -> FineTuningJob,
+        client: self.client,
+        endpoint: fine_tuning(),
+        item_cursor: |item| item.id.clone()
+    );
+
     /// Get info about a fine-tuning job.
     pub async fn retrieve(&self, id: &str) -> Result<FineTuningJob, APIError> {
         let response = self.client.get(&format!("/fine_tuning/jobs/{id}")).await?;
@@ -92,6 +102,15 @@ impl FineTuning<'_> {
         Ok(response)
     }
 
+    paginate_method!(
+        ///
+        /// The fine-tuning endpoints return no `last_id`, so the cursor is taken from the last item.
+        paginate_job_events => list_job_events(id, SimpleListParameters) -> FineTuningJobEvent,
+        client: self.client,
+        endpoint: fine_tuning(),
+        item_cursor: |item| item.id.clone()
+    );
+
     /// List checkpoints for a fine-tuning job.
     pub async fn list_checkpoints(
         &self,
@@ -107,4 +126,13 @@ impl FineTuning<'_> {
 
         Ok(response)
     }
+
+    paginate_method!(
+        ///
+        /// The fine-tuning endpoints return no `last_id`, so the cursor is taken from the last item.
+        paginate_checkpoints => list_checkpoints(id, SimpleListParameters) -> FineTuningJobCheckpoint,
+        client: self.client,
+        endpoint: fine_tuning(),
+        item_cursor: |item| item.id.clone()
+    );
 }
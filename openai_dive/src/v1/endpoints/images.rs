@@ -4,7 +4,7 @@ use crate::v1::helpers::format_response;
 use crate::v1::resources::image::{
     CreateImageParameters, CreateImageVariationParameters, EditImageParameters, ImageResponse,
 };
-use crate::v1::resources::shared::FileUpload;
+use crate::v1::resources::shared::{FileUpload, MultipartForm};
 
 pub struct Images<'a> {
     pub client: &'a Client,
@@ -35,56 +35,17 @@ impl Images<'_> {
 
     /// Creates an edited or extended image given an original image and a prompt.
     pub async fn edit(&self, parameters: EditImageParameters) -> Result<ImageResponse, APIError> {
-        let mut form = reqwest::multipart::Form::new();
+        let mime_type = parameters.mime_type.map(|mime_type| mime_type.to_string());
 
-        let mime_type = parameters.mime_type;
-
-        match parameters.image {
-            #[cfg(all(feature = "tokio", feature = "tokio-util"))]
-            FileUpload::File(_) => {
-                let mut image = parameters.image.into_part().await?;
-
-                if let Some(ref mime_type) = mime_type {
-                    image = image
-                        .mime_str(&mime_type.to_string())
-                        .map_err(|error| APIError::FileError(error.to_string()))?;
-                }
-                form = form.part("image", image);
-            }
+        let image_field = match parameters.image {
             #[cfg(all(feature = "tokio", feature = "tokio-util"))]
-            FileUpload::FileArray(_) => {
-                let images = parameters.image.into_parts().await?;
-                for mut image in images {
-                    if let Some(ref mime_type) = mime_type {
-                        image = image
-                            .mime_str(&mime_type.to_string())
-                            .map_err(|error| APIError::FileError(error.to_string()))?;
-                    }
-                    form = form.part("image[]", image);
-                }
-            }
-            FileUpload::Bytes(_) => {
-                let mut image = parameters.image.into_part().await?;
-
-                if let Some(ref mime_type) = mime_type {
-                    image = image
-                        .mime_str(&mime_type.to_string())
-                        .map_err(|error| APIError::FileError(error.to_string()))?;
-                }
-                form = form.part("image", image);
-            }
-            FileUpload::BytesArray(_) => {
-                let images = parameters.image.into_parts().await?;
-                for mut image in images {
-                    if let Some(ref mime_type) = mime_type {
-                        image = image
-                            .mime_str(&mime_type.to_string())
-                            .map_err(|error| APIError::FileError(error.to_string()))?;
-                    }
-                    form = form.part("image[]", image);
-                }
-            }
-        }
+            FileUpload::FileArray(_) => "image[]",
+            FileUpload::BytesArray(_) => "image[]",
+            _ => "image",
+        };
+
+        let mut form =
+            MultipartForm::new().file_with_mime_type(image_field, parameters.image, mime_type);
 
         form = form.text("prompt", parameters.prompt);
 
@@ -101,8 +62,7 @@ impl Images<'_> {
         }
 
         if let Some(mask) = parameters.mask {
-            let image = mask.into_part().await?;
-            form = form.part("mask", image);
+            form = form.file("mask", mask);
         }
 
         if let Some(model) = parameters.model {
@@ -153,10 +113,7 @@ impl Images<'_> {
         &self,
         parameters: CreateImageVariationParameters,
     ) -> Result<ImageResponse, APIError> {
-        let mut form = reqwest::multipart::Form::new();
-
-        let image = parameters.image.into_part().await?;
-        form = form.part("image", image);
+        let mut form = MultipartForm::new().file("image", parameters.image);
 
         if let Some(model) = parameters.model {
             form = form.text("model", model);
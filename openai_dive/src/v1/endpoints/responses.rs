@@ -1,11 +1,36 @@
 use crate::v1::api::Client;
 use crate::v1::error::APIError;
 use crate::v1::helpers::format_response;
-use crate::v1::resources::response::request::ResponseParameters;
-use crate::v1::resources::response::response::ResponseObject;
+use crate::v1::pagination::paginate_method;
+#[cfg(feature = "stream")]
+use crate::v1::partial_json::parse_partial;
+#[cfg(feature = "stream")]
+use crate::v1::resources::response::accumulator::ResponseAccumulator;
+use crate::v1::resources::response::request::{
+    InputTokensParameters, ListInputItemsParameters, ResponseInputItem, ResponseParameters,
+};
+#[cfg(feature = "stream")]
+use crate::v1::resources::response::response::ResponseStreamEvent;
+use crate::v1::resources::response::response::{InputTokens, ResponseObject};
+#[cfg(feature = "schema")]
+use crate::v1::resources::response::response::{OutputContent, ResponseOutput, ResponseText};
+#[cfg(feature = "schema")]
+use crate::v1::resources::response::shared::ResponseFormat;
 #[cfg(feature = "stream")]
 use crate::v1::resources::response::shared::ResponseStream;
-use crate::v1::resources::shared::DeletedObject;
+use crate::v1::resources::shared::{DeletedObject, ListResponse};
+#[cfg(feature = "schema")]
+use crate::v1::schema::{Parsed, StructuredOutput};
+#[cfg(feature = "stream")]
+use futures::{Stream, StreamExt};
+#[cfg(any(feature = "schema", feature = "stream"))]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "stream")]
+use std::pin::Pin;
+#[cfg(feature = "stream")]
+use std::task::{Context, Poll};
+#[cfg(feature = "tokio")]
+use std::time::{Duration, Instant};
 
 pub struct Responses<'a> {
     pub client: &'a Client,
@@ -18,7 +43,7 @@ impl Client {
     }
 }
 
-impl Responses<'_> {
+impl<'a> Responses<'a> {
     /// Creates a model response.
     pub async fn create(&self, parameters: ResponseParameters) -> Result<ResponseObject, APIError> {
         let response = self.client.post("/responses", &parameters, None).await?;
@@ -52,6 +77,129 @@ impl Responses<'_> {
         Ok(response)
     }
 
+    /// Cancels a model response with the given ID. Only responses created with the `background` parameter set to true can be cancelled.
+    pub async fn cancel(&self, response_id: &str) -> Result<ResponseObject, APIError> {
+        let response = self
+            .client
+            .post_empty(&format!("/responses/{response_id}/cancel"))
+            .await?;
+
+        let response: ResponseObject = format_response(response)?;
+
+        Ok(response)
+    }
+
+    /// Polls a background response with the given ID until it is no longer queued or in progress.
+    ///
+    /// Returns a `WaitTimeoutError` when the response is still running after `timeout`.
+    #[cfg(feature = "tokio")]
+    pub async fn wait(
+        &self,
+        response_id: &str,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<ResponseObject, APIError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let response = self.retrieve(response_id).await?;
+
+            if response.status.is_terminal() {
+                return Ok(response);
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(APIError::WaitTimeoutError(format!(
+                    "response {response_id} did not finish within {timeout:?}"
+                )));
+            }
+
+            tokio::time::sleep(poll_interval.min(remaining)).await;
+        }
+    }
+
+    /// Returns a list of input items for a given response.
+    pub async fn list_input_items(
+        &self,
+        response_id: &str,
+        query: Option<ListInputItemsParameters>,
+    ) -> Result<ListResponse<ResponseInputItem>, APIError> {
+        let response = self
+            .client
+            .get_with_query(&format!("/responses/{response_id}/input_items"), &query)
+            .await?;
+
+        let response: ListResponse<ResponseInputItem> = format_response(response)?;
+
+        Ok(response)
+    }
+
+    paginate_method!(
+        paginate_input_items => list_input_items(response_id, ListInputItemsParameters) -> ResponseInputItem,
+        client: self.client,
+        endpoint: responses()
+    );
+
+    /// Returns the number of input tokens of a request without creating a response.
+    pub async fn input_tokens(
+        &self,
+        parameters: InputTokensParameters,
+    ) -> Result<InputTokens, APIError> {
+        let response = self
+            .client
+            .post("/responses/input_tokens", &parameters, None)
+            .await?;
+
+        let response: InputTokens = format_response(response.data)?;
+
+        Ok(response)
+    }
+
+    /// Creates a model response with the JSON Schema of `T` as text format and deserializes the output into `T`.
+    #[cfg(feature = "schema")]
+    pub async fn create_parsed<T>(
+        &self,
+        parameters: ResponseParameters,
+    ) -> Result<Parsed<ResponseObject, T>, APIError>
+    where
+        T: schemars::JsonSchema + DeserializeOwned,
+    {
+        let response = self
+            .create(ResponseParameters {
+                text: Some(ResponseText {
+                    format: ResponseFormat::from_type::<T>()?,
+                }),
+                ..parameters
+            })
+            .await?;
+
+        let mut text = String::new();
+        let mut refusal = None;
+
+        for content in response.output.iter().flat_map(|output| match output {
+            ResponseOutput::Message(message) => message.content.as_slice(),
+            _ => &[],
+        }) {
+            match content {
+                OutputContent::Text { text: part, .. } => text.push_str(part),
+                OutputContent::Refusal { refusal: part } => refusal = Some(part.clone()),
+                OutputContent::ReasoningText { .. } => {}
+            }
+        }
+
+        let output = match (refusal, &response.incomplete_details) {
+            (Some(refusal), _) => StructuredOutput::Refusal(refusal),
+            (None, Some(details)) => StructuredOutput::Truncated {
+                reason: details.reason.clone(),
+                text,
+            },
+            (None, None) => StructuredOutput::parse(&text),
+        };
+
+        Ok(Parsed { response, output })
+    }
+
     #[cfg(feature = "stream")]
     /// Creates a model response.
     pub async fn create_stream(
@@ -61,9 +209,370 @@ impl Responses<'_> {
         let mut stream_parameters = ResponseParameters { ..parameters };
         stream_parameters.stream = Some(true);
 
-        Ok(self
-            .client
+        self.client
             .post_stream("/responses", &stream_parameters, None)
-            .await)
+            .await
+    }
+
+    #[cfg(feature = "stream")]
+    /// Streams the events of a background response with the given ID, for example to reconnect after the connection dropped.
+    ///
+    /// Events up to and including the sequence number `starting_after` are skipped, so events that were already
+    /// received are not emitted again. Use `AccumulatingStream::with_accumulator` to continue accumulating.
+    pub async fn resume_stream(
+        &self,
+        response_id: &str,
+        starting_after: Option<u32>,
+    ) -> Result<ResponseStream, APIError> {
+        let path = match starting_after {
+            Some(sequence_number) => {
+                format!("/responses/{response_id}?stream=true&starting_after={sequence_number}")
+            }
+            None => format!("/responses/{response_id}?stream=true"),
+        };
+
+        let stream = self.client.get_stream::<ResponseStreamEvent>(&path).await?;
+
+        Ok(Box::pin(stream.filter(move |event| {
+            let seen = match (event, starting_after) {
+                (Ok(event), Some(sequence_number)) => event.sequence_number() <= sequence_number,
+                _ => false,
+            };
+
+            futures::future::ready(!seen)
+        })))
+    }
+}
+
+/// Passes the events of a response stream through while accumulating them into a `ResponseObject`.
+///
+/// A `StreamError` is yielded in place of an event that does not directly follow the previous one. An event after missed
+/// events is still accumulated.
+#[cfg(feature = "stream")]
+pub struct AccumulatingStream<S> {
+    stream: S,
+    accumulator: ResponseAccumulator,
+}
+
+#[cfg(feature = "stream")]
+impl<S> AccumulatingStream<S>
+where
+    S: Stream<Item = Result<ResponseStreamEvent, APIError>> + Unpin,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            accumulator: ResponseAccumulator::new(),
+        }
+    }
+
+    /// Continues accumulating into `accumulator`, for example with a stream returned by `Responses::resume_stream`.
+    pub fn with_accumulator(stream: S, accumulator: ResponseAccumulator) -> Self {
+        Self {
+            stream,
+            accumulator,
+        }
+    }
+
+    /// Returns the accumulator with the state built from the events received so far.
+    pub fn accumulator(&self) -> &ResponseAccumulator {
+        &self.accumulator
+    }
+
+    /// Returns the accumulator, e.g. to continue accumulating after reconnecting.
+    pub fn into_accumulator(self) -> ResponseAccumulator {
+        self.accumulator
+    }
+
+    /// Returns the text of all output messages received so far.
+    pub fn output_text(&self) -> String {
+        self.accumulator.output_text()
+    }
+
+    /// Parses the text of all output messages received so far, e.g. a streamed structured output, see `parse_partial`.
+    pub fn partial_output<T: DeserializeOwned>(&self) -> Option<T> {
+        parse_partial(&self.accumulator.output_text())
+    }
+
+    /// Parses the arguments of the function or MCP tool call at `output_index` received so far, see `parse_partial`.
+    pub fn partial_arguments<T: DeserializeOwned>(&self, output_index: usize) -> Option<T> {
+        parse_partial(self.accumulator.arguments(output_index)?)
+    }
+
+    /// Consumes the rest of the stream and returns the complete response.
+    pub async fn final_response(mut self) -> Result<ResponseObject, APIError> {
+        while let Some(event) = self.next().await {
+            event?;
+        }
+
+        self.accumulator.response().ok_or_else(|| {
+            APIError::StreamError("the stream ended before the response was created".to_string())
+        })
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<S> Stream for AccumulatingStream<S>
+where
+    S: Stream<Item = Result<ResponseStreamEvent, APIError>> + Unpin,
+{
+    type Item = Result<ResponseStreamEvent, APIError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        match Pin::new(&mut this.stream).poll_next(cx) {
+            Poll::Ready(Some(Ok(event))) => match this.accumulator.push(&event) {
+                Ok(()) => Poll::Ready(Some(Ok(event))),
+                Err(error) => Poll::Ready(Some(Err(error))),
+            },
+            other => other,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::v1::error::APIError;
+    use crate::v1::resources::response::request::{
+        InputTokensParametersBuilder, ListInputItemsParametersBuilder, ResponseInput,
+        ResponseInputItem,
+    };
+    use crate::v1::resources::response::response::ReasoningStatus;
+    use crate::v1::testing::{Mock, MockResponse, MockServer};
+    use reqwest::header::CONTENT_TYPE;
+    use reqwest::Method;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancel_sends_no_body() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/responses/resp_1/cancel").respond_with(MockResponse::json(json!({
+                "id": "resp_1",
+                "object": "response",
+                "created_at": 1,
+                "model": "o3",
+                "status": "cancelled",
+                "output": [],
+                "tools": []
+            }))),
+        );
+
+        let response = server.client().responses().cancel("resp_1").await.unwrap();
+        assert_eq!(response.id, "resp_1");
+
+        let request = &server.received_requests()[0];
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.path, "/responses/resp_1/cancel");
+        assert!(request.body.is_empty());
+        assert!(request.headers.get(CONTENT_TYPE).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_input_items_sends_query() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::get("/responses/resp_1/input_items").respond_with(MockResponse::json(json!({
+                "object": "list",
+                "data": [{
+                    "type": "message",
+                    "id": "msg_1",
+                    "role": "user",
+                    "content": [{"type": "input_text", "text": "Hello"}]
+                }],
+                "first_id": "msg_1",
+                "last_id": "msg_1",
+                "has_more": false
+            }))),
+        );
+
+        let query = ListInputItemsParametersBuilder::default()
+            .limit(10u32)
+            .order("asc")
+            .build()
+            .unwrap();
+
+        let items = server
+            .client()
+            .responses()
+            .list_input_items("resp_1", Some(query))
+            .await
+            .unwrap();
+        assert!(matches!(items.data[..], [ResponseInputItem::Message(_)]));
+
+        let request = &server.received_requests()[0];
+        assert_eq!(request.method, Method::GET);
+        assert_eq!(request.path, "/responses/resp_1/input_items");
+        assert_eq!(request.query.as_deref(), Some("limit=10&order=asc"));
+        assert!(request.body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_input_tokens_sends_parameters() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/responses/input_tokens").respond_with(MockResponse::json(json!({
+                "object": "response.input_tokens",
+                "input_tokens": 7
+            }))),
+        );
+
+        let parameters = InputTokensParametersBuilder::default()
+            .model("gpt-4o")
+            .input(ResponseInput::Text("Hello".to_string()))
+            .build()
+            .unwrap();
+
+        let tokens = server
+            .client()
+            .responses()
+            .input_tokens(parameters)
+            .await
+            .unwrap();
+        assert_eq!(tokens.input_tokens, 7);
+
+        let request = &server.received_requests()[0];
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.path, "/responses/input_tokens");
+        assert_eq!(
+            request.json().unwrap(),
+            json!({"model": "gpt-4o", "input": "Hello"})
+        );
+    }
+
+    fn response_json(status: &str) -> serde_json::Value {
+        json!({
+            "id": "resp_1",
+            "object": "response",
+            "created_at": 1,
+            "model": "o3",
+            "status": status,
+            "output": [],
+            "tools": []
+        })
+    }
+
+    #[tokio::test]
+    async fn test_wait_polls_until_terminal_status() {
+        let server = MockServer::start().await;
+        server
+            .mock(
+                Mock::get("/responses/resp_1")
+                    .respond_with(MockResponse::json(response_json("queued")))
+                    .times(1),
+            )
+            .mock(
+                Mock::get("/responses/resp_1")
+                    .respond_with(MockResponse::json(response_json("in_progress")))
+                    .times(1),
+            )
+            .mock(
+                Mock::get("/responses/resp_1")
+                    .respond_with(MockResponse::json(response_json("completed"))),
+            );
+
+        let response = server
+            .client()
+            .responses()
+            .wait("resp_1", Duration::from_millis(1), Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(response.status, ReasoningStatus::Completed);
+
+        let requests = server.received_requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests
+            .iter()
+            .all(|request| request.method == Method::GET && request.path == "/responses/resp_1"));
+    }
+
+    #[tokio::test]
+    async fn test_wait_times_out() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::get("/responses/resp_1")
+                .respond_with(MockResponse::json(response_json("in_progress"))),
+        );
+
+        let result = server
+            .client()
+            .responses()
+            .wait(
+                "resp_1",
+                Duration::from_millis(10),
+                Duration::from_millis(50),
+            )
+            .await;
+        assert!(matches!(result, Err(APIError::WaitTimeoutError(_))));
+
+        // The last poll happens at the deadline
+        assert!(server.received_requests().len() > 1);
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_resume_stream_skips_seen_events() {
+        use crate::v1::endpoints::responses::AccumulatingStream;
+        use crate::v1::resources::response::accumulator::ResponseAccumulator;
+        use crate::v1::resources::response::response::ResponseStreamEvent;
+        use futures::StreamExt;
+
+        let events = json!([
+            {"type": "response.created", "sequence_number": 0, "response": response_json("in_progress")},
+            {"type": "response.output_item.added", "sequence_number": 1, "output_index": 0, "item": {"type": "message", "id": "msg_1", "role": "assistant", "status": "in_progress", "content": []}},
+            {"type": "response.content_part.added", "sequence_number": 2, "item_id": "msg_1", "output_index": 0, "content_index": 0, "part": {"type": "output_text", "text": "", "annotations": []}},
+            {"type": "response.output_text.delta", "sequence_number": 3, "item_id": "msg_1", "output_index": 0, "content_index": 0, "delta": "Hello"},
+            {"type": "response.output_text.delta", "sequence_number": 4, "item_id": "msg_1", "output_index": 0, "content_index": 0, "delta": " world"},
+            {"type": "response.completed", "sequence_number": 5, "response": response_json("completed")},
+        ]);
+
+        // The server replays events that were already received, which are skipped
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::get("/responses/resp_1")
+                .respond_with(MockResponse::events(events.as_array().unwrap().clone())),
+        );
+
+        let mut accumulator = ResponseAccumulator::new();
+        let seen: Vec<ResponseStreamEvent> = serde_json::from_value(events).unwrap();
+        for event in &seen[..4] {
+            accumulator.push(event).unwrap();
+        }
+
+        let client = server.client();
+        let stream = client
+            .responses()
+            .resume_stream("resp_1", accumulator.sequence_number())
+            .await
+            .unwrap();
+        let mut stream = AccumulatingStream::with_accumulator(stream, accumulator);
+
+        let mut received = vec![];
+        while let Some(event) = stream.next().await {
+            received.push(event.unwrap().sequence_number());
+        }
+        assert_eq!(received, vec![4, 5]);
+        assert_eq!(stream.output_text(), "Hello world");
+
+        let received: Vec<u32> = client
+            .responses()
+            .resume_stream("resp_1", None)
+            .await
+            .unwrap()
+            .map(|event| event.unwrap().sequence_number())
+            .collect()
+            .await;
+        assert_eq!(received, vec![0, 1, 2, 3, 4, 5]);
+
+        let requests = server.received_requests();
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(requests[0].path, "/responses/resp_1");
+        assert_eq!(
+            requests[0].query.as_deref(),
+            Some("stream=true&starting_after=3")
+        );
+        assert_eq!(requests[1].query.as_deref(), Some("stream=true"));
     }
 }
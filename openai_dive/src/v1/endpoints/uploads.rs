@@ -4,8 +4,11 @@ use crate::v1::{
     api::Client,
     error::APIError,
     helpers::format_response,
-    resources::upload::{
-        AddPartParameters, CompleteUploadParameters, CreateUploadParameters, Upload, UploadPart,
+    resources::{
+        shared::MultipartForm,
+        upload::{
+            AddPartParameters, CompleteUploadParameters, CreateUploadParameters, Upload, UploadPart,
+        },
     },
 };
 
@@ -36,10 +39,7 @@ impl Uploads<'_> {
         id: &str,
         parameters: AddPartParameters,
     ) -> Result<UploadPart, APIError> {
-        let mut form = reqwest::multipart::Form::new();
-
-        let file_part = parameters.data.into_part().await?;
-        form = form.part("data", file_part);
+        let form = MultipartForm::new().file("data", parameters.data);
 
         let response = self
             .client
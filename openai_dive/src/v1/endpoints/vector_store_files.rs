@@ -2,6 +2,7 @@ use crate::v1::{
     api::Client,
     error::APIError,
     helpers::format_response,
+    pagination::paginate_method,
     resources::{
         shared::{DeletedObject, ListParameters, ListResponse},
         vector_store_file::{CreateVectorStoreFileParameters, VectorStoreFile},
@@ -19,7 +20,7 @@ impl Client {
     }
 }
 
-impl VectorStoreFiles<'_> {
+impl<'a> VectorStoreFiles<'a> {
     /// Create a vector store file by attaching a File to a vector store.
     pub async fn create(
         &self,
@@ -56,6 +57,12 @@ impl VectorStoreFiles<'_> {
         Ok(response)
     }
 
+    paginate_method!(
+        paginate => list(vector_store_id, ListParameters) -> VectorStoreFile,
+        client: self.client,
+        endpoint: vector_store_files()
+    );
+
     /// Retrieves a vector store.
     pub async fn retrieve(
         &self,
@@ -2,6 +2,7 @@ use crate::v1::{
     api::Client,
     error::APIError,
     helpers::format_response,
+    pagination::paginate_method,
     resources::{
         shared::{DeletedObject, ListParameters, ListResponse},
         vector_store::{
@@ -22,7 +23,7 @@ impl Client {
     }
 }
 
-impl VectorStores<'_> {
+impl<'a> VectorStores<'a> {
     /// Create a vector store.
     pub async fn create(
         &self,
@@ -50,6 +51,12 @@ impl VectorStores<'_> {
         Ok(response)
     }
 
+    paginate_method!(
+        paginate => list(ListParameters) -> VectorStore,
+        client: self.client,
+        endpoint: vector_stores()
+    );
+
     /// Retrieves a vector store.
     pub async fn retrieve(&self, vector_store_id: &str) -> Result<VectorStore, APIError> {
         let response = self
@@ -1,6 +1,7 @@
 use crate::v1::api::Client;
 use crate::v1::error::APIError;
 use crate::v1::helpers::format_response;
+use crate::v1::pagination::paginate_method;
 use crate::v1::resources::shared::{DeletedObject, ListResponse, SimpleListParameters};
 use crate::v1::resources::video::{CreateVideoParameters, CreateVideoRemixParameters, VideoJob};
 
@@ -15,7 +16,7 @@ impl Client {
     }
 }
 
-impl Videos<'_> {
+impl<'a> Videos<'a> {
     /// Create a video
     pub async fn create(&self, parameters: CreateVideoParameters) -> Result<VideoJob, APIError> {
         let response = self.client.post("/videos", &parameters, None).await?;
@@ -62,6 +63,12 @@ impl Videos<'_> {
         Ok(response)
     }
 
+    paginate_method!(
+        paginate => list(SimpleListParameters) -> VideoJob,
+        client: self.client,
+        endpoint: videos()
+    );
+
     /// Delete a video
     pub async fn delete(&self, id: &str) -> Result<DeletedObject, APIError> {
         let response = self.client.delete(&format!("/videos/{id}")).await?;
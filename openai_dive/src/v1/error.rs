@@ -1,67 +1,300 @@
-use serde::{Deserialize, Serialize};
+use crate::v1::resources::shared::Headers;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 use std::fmt::{Display, Formatter, Result};
+use std::time::Duration;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum APIError {
-    AuthenticationError(String),
-    BadRequestError(String),
-    PermissionError(String),
-    NotFoundError(String),
-    GoneError(String),
+    AuthenticationError(Box<ErrorResponse>),
+    BadRequestError(Box<ErrorResponse>),
+    PermissionError(Box<ErrorResponse>),
+    NotFoundError(Box<ErrorResponse>),
+    GoneError(Box<ErrorResponse>),
     ServerError(String),
-    InvalidRequestError(String),
-    RateLimitError(String),
+    /// An error object returned in the body of a successful response or as a stream event.
+    InvalidRequestError(Box<ErrorResponse>),
+    RateLimitError(Box<ErrorResponse>),
     ParseError(String),
     FileError(String),
     StreamError(String),
     WebSocketError(String),
-    UnknownError(u16, String),
+    /// The request parameters were rejected before sending the request.
+    ValidationError(String),
+    /// A tool run was stopped, for example because it reached its iteration limit.
+    ToolError(String),
+    /// A background response did not reach a terminal status before the timeout.
+    WaitTimeoutError(String),
+    /// A request could not be replayed from or recorded to a cassette.
+    CassetteError(String),
+    /// The server encountered an internal error (500 and other unmapped 5xx status codes).
+    InternalServerError(Box<ErrorResponse>),
+    /// The server received an invalid response from an upstream server (502).
+    BadGatewayError(Box<ErrorResponse>),
+    /// The server is overloaded or down for maintenance (503, 529).
+    ServiceUnavailableError(Box<ErrorResponse>),
+    /// The server did not respond in time (504).
+    TimeoutError(Box<ErrorResponse>),
+    UnknownError(Box<ErrorResponse>),
+}
+
+/// The details of an error response returned by the API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ErrorResponse {
+    /// The HTTP status code of the response.
+    pub status: u16,
+    /// The parsed `error` object, if the body contained one.
+    pub error: Option<ErrorObject>,
+    /// The raw response body.
+    pub body: String,
+    /// The value of the `x-request-id` header, needed when contacting OpenAI support.
+    pub request_id: Option<String>,
+    /// The rate limit headers of the response.
+    pub headers: Headers,
+    /// The delay requested by the server through the `retry-after` headers.
+    pub retry_after: Option<Duration>,
+    /// Whether the server asked to retry the request through the `x-should-retry` header.
+    #[serde(default)]
+    pub should_retry: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ErrorObject {
+    /// A human-readable error message.
+    pub message: String,
+    /// The error type, e.g. `invalid_request_error`.
+    #[serde(default)]
+    pub r#type: Option<String>,
+    /// The parameter related to the error, if any.
+    #[serde(default)]
+    pub param: Option<String>,
+    /// A machine-readable error code, e.g. `context_length_exceeded` or `rate_limit_exceeded`.
+    #[serde(default, deserialize_with = "deserialize_code")]
+    pub code: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ErrorEnvelope {
+    error: ErrorObject,
+}
+
+impl ErrorObject {
+    /// Parses an error object from either `{"error": {...}}` or a bare `{"message": ...}` body.
+    pub fn from_body(body: &str) -> Option<Self> {
+        serde_json::from_str::<ErrorEnvelope>(body)
+            .map(|envelope| envelope.error)
+            .or_else(|_| serde_json::from_str::<ErrorObject>(body))
+            .ok()
+    }
+}
+
+/// The error object of an error response, replaced by `ErrorObject` in version 2.0.
+#[deprecated(
+    since = "2.0.0",
+    note = "use `ErrorObject`, which is returned by `APIError::response`"
+)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InvalidRequestError {
+    pub code: String,
+    pub message: String,
+    pub param: Option<String>,
+    pub r#type: String,
+}
+
+#[allow(deprecated)]
+impl From<ErrorObject> for InvalidRequestError {
+    fn from(error: ErrorObject) -> Self {
+        Self {
+            code: error.code.unwrap_or_default(),
+            message: error.message,
+            param: error.param,
+            r#type: error.r#type.unwrap_or_default(),
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl From<InvalidRequestError> for ErrorObject {
+    fn from(error: InvalidRequestError) -> Self {
+        Self {
+            message: error.message,
+            r#type: Some(error.r#type),
+            param: error.param,
+            code: Some(error.code),
+        }
+    }
+}
+
+impl ErrorResponse {
+    /// Creates an error response from a status code and body, without any headers.
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        let body = body.into();
+
+        Self {
+            status,
+            error: ErrorObject::from_body(&body),
+            body,
+            request_id: None,
+            headers: Headers::default(),
+            retry_after: None,
+            should_retry: None,
+        }
+    }
+
+    /// Returns the error message, falling back to the raw body.
+    pub fn message(&self) -> &str {
+        match &self.error {
+            Some(error) => &error.message,
+            None => &self.body,
+        }
+    }
+
+    /// Returns the error code, e.g. `context_length_exceeded`.
+    pub fn code(&self) -> Option<&str> {
+        self.error.as_ref().and_then(|error| error.code.as_deref())
+    }
 }
 
 impl APIError {
+    /// Returns the error response, if the error was returned by the API.
+    pub fn response(&self) -> Option<&ErrorResponse> {
+        match self {
+            APIError::AuthenticationError(response)
+            | APIError::BadRequestError(response)
+            | APIError::PermissionError(response)
+            | APIError::NotFoundError(response)
+            | APIError::GoneError(response)
+            | APIError::InvalidRequestError(response)
+            | APIError::RateLimitError(response)
+            | APIError::InternalServerError(response)
+            | APIError::BadGatewayError(response)
+            | APIError::ServiceUnavailableError(response)
+            | APIError::TimeoutError(response)
+            | APIError::UnknownError(response) => Some(response),
+            APIError::ServerError(_)
+            | APIError::ParseError(_)
+            | APIError::FileError(_)
+            | APIError::StreamError(_)
+            | APIError::WebSocketError(_)
+            | APIError::ValidationError(_)
+            | APIError::ToolError(_)
+            | APIError::WaitTimeoutError(_)
+            | APIError::CassetteError(_) => None,
+        }
+    }
+
+    /// Returns the HTTP status code, if the error was returned by the API.
+    pub fn status_code(&self) -> Option<u16> {
+        self.response().map(|response| response.status)
+    }
+
+    /// Returns the `x-request-id` of the failed request.
+    pub fn request_id(&self) -> Option<&str> {
+        self.response()
+            .and_then(|response| response.request_id.as_deref())
+    }
+
+    /// Returns the error code returned by the API, e.g. `insufficient_quota`.
+    pub fn code(&self) -> Option<&str> {
+        self.response().and_then(|response| response.code())
+    }
+
+    /// Returns the delay requested by the server before retrying.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.response().and_then(|response| response.retry_after)
+    }
+
+    /// Returns whether the request was rejected because of a rate limit.
+    pub fn is_rate_limit(&self) -> bool {
+        matches!(self, APIError::RateLimitError(_))
+    }
+
+    /// Returns whether the input exceeded the context window of the model.
+    pub fn is_context_length_exceeded(&self) -> bool {
+        self.code() == Some("context_length_exceeded")
+    }
+
+    /// Returns whether sending the same request again might succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            APIError::ServerError(_)
+            | APIError::InternalServerError(_)
+            | APIError::BadGatewayError(_)
+            | APIError::ServiceUnavailableError(_)
+            | APIError::TimeoutError(_) => true,
+            // An exhausted quota will not recover by waiting
+            APIError::RateLimitError(_) => self.code() != Some("insufficient_quota"),
+            APIError::UnknownError(response) => matches!(response.status, 408 | 409),
+            _ => false,
+        }
+    }
+
     fn message(&self) -> String {
         match self {
-            APIError::AuthenticationError(message)
-            | APIError::BadRequestError(message)
-            | APIError::PermissionError(message)
-            | APIError::NotFoundError(message)
-            | APIError::GoneError(message)
-            | APIError::ServerError(message)
-            | APIError::InvalidRequestError(message)
-            | APIError::RateLimitError(message)
+            APIError::ServerError(message)
             | APIError::ParseError(message)
             | APIError::FileError(message)
             | APIError::StreamError(message)
-            | APIError::WebSocketError(message) => message.to_string(),
-            APIError::UnknownError(status_code, message) => {
-                format!("{status_code}: {message}")
-            }
+            | APIError::WebSocketError(message)
+            | APIError::ValidationError(message)
+            | APIError::ToolError(message)
+            | APIError::WaitTimeoutError(message)
+            | APIError::CassetteError(message) => message.to_string(),
+            APIError::AuthenticationError(response)
+            | APIError::BadRequestError(response)
+            | APIError::PermissionError(response)
+            | APIError::NotFoundError(response)
+            | APIError::GoneError(response)
+            | APIError::InvalidRequestError(response)
+            | APIError::RateLimitError(response)
+            | APIError::InternalServerError(response)
+            | APIError::BadGatewayError(response)
+            | APIError::ServiceUnavailableError(response)
+            | APIError::TimeoutError(response)
+            | APIError::UnknownError(response) => response.to_string(),
         }
     }
 }
 
 impl std::error::Error for APIError {}
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-pub struct InvalidRequestError {
-    pub code: String,
-    pub message: String,
-    pub param: Option<String>,
-    pub r#type: String,
-}
-
 impl Display for APIError {
     fn fmt(&self, f: &mut Formatter) -> Result {
         write!(f, "{}", self.message())
     }
 }
 
+impl Display for ErrorResponse {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "{}: {}", self.status, self.message())?;
+
+        if let Some(request_id) = &self.request_id {
+            write!(f, " (request id: {request_id})")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[allow(deprecated)]
 impl Display for InvalidRequestError {
     fn fmt(&self, f: &mut Formatter) -> Result {
         write!(f, "{} {}", self.code, self.message)
     }
 }
 
+fn deserialize_code<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // Some endpoints return the error code as a number
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::String(code)) => Some(code),
+        Some(Value::Number(code)) => Some(code.to_string()),
+        _ => None,
+    })
+}
+
 #[cfg(feature = "realtime")]
 impl From<reqwest_websocket::Error> for APIError {
     fn from(error: reqwest_websocket::Error) -> Self {
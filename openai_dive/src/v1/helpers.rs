@@ -1,50 +1,54 @@
-use crate::v1::error::APIError;
-use reqwest::{Response, StatusCode};
+use crate::v1::error::{APIError, ErrorResponse};
+use crate::v1::retry::retry_after;
+use crate::v1::transport::HttpResponse;
+use reqwest::StatusCode;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 #[cfg(feature = "download")]
 use std::time::{SystemTime, UNIX_EPOCH};
 
-pub(crate) async fn check_status_code(
-    result: reqwest::Result<Response>,
-) -> Result<Response, APIError> {
-    match result {
-        Ok(response) => {
-            if response.status().is_client_error() {
-                let status = response.status();
-                let text = response
-                    .text()
-                    .await
-                    .map_err(|error| APIError::ParseError(error.to_string()))?;
-
-                match status {
-                    StatusCode::BAD_REQUEST => {
-                        return Err(APIError::BadRequestError(text));
-                    }
-                    StatusCode::UNAUTHORIZED => {
-                        return Err(APIError::AuthenticationError(text));
-                    }
-                    StatusCode::FORBIDDEN => {
-                        return Err(APIError::PermissionError(text));
-                    }
-                    StatusCode::NOT_FOUND => {
-                        return Err(APIError::NotFoundError(text));
-                    }
-                    StatusCode::GONE => {
-                        return Err(APIError::GoneError(text));
-                    }
-                    StatusCode::TOO_MANY_REQUESTS => {
-                        return Err(APIError::RateLimitError(text));
-                    }
-                    _ => {
-                        return Err(APIError::UnknownError(status.as_u16(), text));
-                    }
-                }
-            }
-
-            Ok(response)
-        }
-        Err(error) => Err(APIError::ServerError(error.to_string())),
+pub(crate) fn check_status_code(response: HttpResponse) -> Result<HttpResponse, APIError> {
+    if response.status.is_client_error() || response.status.is_server_error() {
+        return Err(error_from_response(response));
+    }
+
+    Ok(response)
+}
+
+pub(crate) fn error_from_response(response: HttpResponse) -> APIError {
+    let status = response.status;
+    let headers = response.headers;
+    let body = String::from_utf8_lossy(&response.body).into_owned();
+
+    let error_response = Box::new(ErrorResponse {
+        request_id: headers
+            .get("x-request-id")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string()),
+        retry_after: retry_after(&headers),
+        should_retry: match headers.get("x-should-retry").map(|value| value.as_bytes()) {
+            Some(b"true") => Some(true),
+            Some(b"false") => Some(false),
+            _ => None,
+        },
+        headers: headers.into(),
+        ..ErrorResponse::new(status.as_u16(), body)
+    });
+
+    match status {
+        StatusCode::BAD_REQUEST => APIError::BadRequestError(error_response),
+        StatusCode::UNAUTHORIZED => APIError::AuthenticationError(error_response),
+        StatusCode::FORBIDDEN => APIError::PermissionError(error_response),
+        StatusCode::NOT_FOUND => APIError::NotFoundError(error_response),
+        StatusCode::GONE => APIError::GoneError(error_response),
+        StatusCode::TOO_MANY_REQUESTS => APIError::RateLimitError(error_response),
+        StatusCode::BAD_GATEWAY => APIError::BadGatewayError(error_response),
+        StatusCode::SERVICE_UNAVAILABLE => APIError::ServiceUnavailableError(error_response),
+        // 529 is used by some providers to signal that the service is overloaded
+        _ if status.as_u16() == 529 => APIError::ServiceUnavailableError(error_response),
+        StatusCode::GATEWAY_TIMEOUT => APIError::TimeoutError(error_response),
+        _ if status.is_server_error() => APIError::InternalServerError(error_response),
+        _ => APIError::UnknownError(error_response),
     }
 }
 
@@ -54,7 +58,10 @@ pub(crate) fn validate_response(response: String) -> Result<Value, APIError> {
 
     if let Some(object) = value.as_object() {
         if object.len() == 1 && object.contains_key("error") {
-            return Err(APIError::InvalidRequestError(value["error"].to_string()));
+            return Err(APIError::InvalidRequestError(Box::new(ErrorResponse::new(
+                StatusCode::OK.as_u16(),
+                response,
+            ))));
         }
     }
 
@@ -90,3 +97,112 @@ pub fn generate_file_name(path: &str, length: u32, file_type: &str) -> String {
 
     format!("{path}/{random_str}.{file_type}")
 }
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::v1::error::APIError;
+    use crate::v1::testing::{Mock, MockResponse, MockServer};
+    use std::time::Duration;
+
+    async fn get_model_with_status(status: u16) -> Result<(), APIError> {
+        let server = MockServer::start().await;
+
+        server.mock(
+            Mock::get("/models/gpt-4o")
+                .respond_with(MockResponse::new(status).body("<html>oops</html>")),
+        );
+
+        server.client().models().get("gpt-4o").await.map(|_| ())
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_mapped_to_error_variants() {
+        assert!(matches!(
+            get_model_with_status(500).await,
+            Err(APIError::InternalServerError(response)) if response.body == "<html>oops</html>"
+        ));
+        assert!(matches!(
+            get_model_with_status(501).await,
+            Err(APIError::InternalServerError(response)) if response.status == 501
+        ));
+        assert!(matches!(
+            get_model_with_status(502).await,
+            Err(APIError::BadGatewayError(response)) if response.status == 502
+        ));
+        assert!(matches!(
+            get_model_with_status(503).await,
+            Err(APIError::ServiceUnavailableError(response)) if response.status == 503
+        ));
+        assert!(matches!(
+            get_model_with_status(529).await,
+            Err(APIError::ServiceUnavailableError(response)) if response.status == 529
+        ));
+        assert!(matches!(
+            get_model_with_status(504).await,
+            Err(APIError::TimeoutError(response)) if response.status == 504
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_error_response_is_parsed() {
+        let server = MockServer::start().await;
+
+        server.mock(
+            Mock::get("/models/gpt-4o").respond_with(
+                MockResponse::new(400)
+                    .header("x-request-id", "req_123")
+                    .body(
+                        serde_json::json!({
+                            "error": {
+                                "message": "This model's maximum context length is 128000 tokens.",
+                                "type": "invalid_request_error",
+                                "param": "messages",
+                                "code": "context_length_exceeded"
+                            }
+                        })
+                        .to_string(),
+                    ),
+            ),
+        );
+
+        let error = server.client().models().get("gpt-4o").await.unwrap_err();
+
+        assert!(matches!(error, APIError::BadRequestError(_)));
+        assert!(error.is_context_length_exceeded());
+        assert!(!error.is_retryable());
+        assert_eq!(error.status_code(), Some(400));
+        assert_eq!(error.request_id(), Some("req_123"));
+        assert_eq!(
+            error.response().unwrap().error.as_ref().unwrap().param,
+            Some("messages".to_string())
+        );
+        assert_eq!(
+            error.to_string(),
+            "400: This model's maximum context length is 128000 tokens. (request id: req_123)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_error_carries_retry_after() {
+        let server = MockServer::start().await;
+
+        server.mock(
+            Mock::get("/models/gpt-4o").respond_with(
+                MockResponse::error(
+                    429,
+                    "You exceeded your current quota.",
+                    "insufficient_quota",
+                    Some("insufficient_quota"),
+                )
+                .header("retry-after", "20"),
+            ),
+        );
+
+        let error = server.client().models().get("gpt-4o").await.unwrap_err();
+
+        assert!(error.is_rate_limit());
+        assert!(!error.is_retryable());
+        assert_eq!(error.code(), Some("insufficient_quota"));
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(20)));
+    }
+}
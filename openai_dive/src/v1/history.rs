@@ -0,0 +1,606 @@
+use crate::v1::api::Client;
+use crate::v1::error::APIError;
+use crate::v1::resources::chat::{
+    ChatCompletionParameters, ChatCompletionResponse, ChatMessage, ChatMessageContent,
+};
+#[cfg(feature = "tokenizer")]
+use crate::v1::tokenizer::Tokenizer;
+use serde::{Deserialize, Serialize};
+
+const SUMMARY_PROMPT: &str = "Summarize the following conversation between a user and an assistant. Keep every fact, decision and open question that is needed to continue the conversation.";
+
+/// How older messages are removed when the conversation exceeds its token budget.
+///
+/// System and developer messages at the start of the conversation are always kept, and an assistant message
+/// with tool calls is always kept or removed together with its tool messages.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TruncationStrategy {
+    /// Removes the oldest messages until the conversation fits the budget.
+    #[default]
+    DropOldest,
+    /// Keeps only the last `messages` messages, then removes the oldest of those until the conversation fits the budget.
+    KeepLast { messages: usize },
+    /// Replaces all but the last `keep_last` messages with a summary created by `model`, then removes the oldest
+    /// messages until the conversation fits the budget.
+    Summarize { model: String, keep_last: usize },
+}
+
+/// The messages of a chat conversation, trimmed to a token budget before every chat completion.
+///
+/// A conversation can be serialized to persist it between sessions.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Conversation {
+    messages: Vec<ChatMessage>,
+    /// A summary of the messages removed by `TruncationStrategy::Summarize`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token_budget: Option<usize>,
+    #[serde(default)]
+    strategy: TruncationStrategy,
+}
+
+impl Conversation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_messages(messages: Vec<ChatMessage>) -> Self {
+        Self {
+            messages,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the maximum number of prompt tokens of the messages and tools sent in a chat completion.
+    ///
+    /// Without the `tokenizer` feature, tokens are estimated from the length of the serialized JSON, which counts
+    /// the JSON syntax as well. The estimate is higher than the actual count, so messages are removed earlier than
+    /// needed; enable the `tokenizer` feature for exact budgets.
+    pub fn set_token_budget(&mut self, token_budget: usize) -> &mut Self {
+        self.token_budget = Some(token_budget);
+
+        self
+    }
+
+    pub fn set_strategy(&mut self, strategy: TruncationStrategy) -> &mut Self {
+        self.strategy = strategy;
+
+        self
+    }
+
+    /// The messages of the conversation, without the summary of removed messages.
+    pub fn messages(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    /// The summary of the messages removed by `TruncationStrategy::Summarize`.
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    pub fn push(&mut self, message: ChatMessage) -> &mut Self {
+        self.messages.push(message);
+
+        self
+    }
+
+    /// Appends the assistant message of the first choice of a response.
+    pub fn push_response(&mut self, response: &ChatCompletionResponse) -> &mut Self {
+        if let Some(choice) = response.choices.first() {
+            self.messages.push(choice.message.clone());
+        }
+
+        self
+    }
+
+    /// Appends the output of a tool call.
+    pub fn push_tool_output(&mut self, tool_call_id: &str, output: &str) -> &mut Self {
+        self.messages.push(ChatMessage::Tool {
+            content: ChatMessageContent::Text(output.to_string()),
+            tool_call_id: tool_call_id.to_string(),
+        });
+
+        self
+    }
+
+    /// Returns the messages to send, with the summary of removed messages after the leading system and developer messages.
+    pub fn request_messages(&self) -> Vec<ChatMessage> {
+        let mut messages = self.messages.clone();
+
+        if let Some(summary) = &self.summary {
+            messages.insert(
+                self.leading_system_messages(),
+                ChatMessage::System {
+                    content: ChatMessageContent::Text(format!(
+                        "Summary of the earlier conversation:\n{summary}"
+                    )),
+                    name: None,
+                },
+            );
+        }
+
+        messages
+    }
+
+    /// Appends the messages in `parameters` to the conversation, trims it to the token budget and creates a chat
+    /// completion with the conversation. The assistant message of the response is appended to the conversation.
+    ///
+    /// `parameters.messages` should only contain the new messages of this turn. Leading system and developer
+    /// messages that are already at the start of the conversation are skipped, so the same system prompt can be
+    /// passed with every turn.
+    ///
+    /// The conversation is only changed when the request succeeds, so a failed turn can be retried with the same
+    /// parameters.
+    pub async fn create(
+        &mut self,
+        client: &Client,
+        parameters: ChatCompletionParameters,
+    ) -> Result<ChatCompletionResponse, APIError> {
+        let mut conversation = self.clone();
+
+        let leading = conversation.leading_system_messages();
+        let messages: Vec<ChatMessage> = parameters
+            .messages
+            .iter()
+            .skip_while(|message| {
+                is_system_message(message) && self.messages[..leading].contains(message)
+            })
+            .cloned()
+            .collect();
+        conversation.messages.extend(messages);
+
+        conversation.trim(client, &parameters).await?;
+
+        let response = client
+            .chat()
+            .create(ChatCompletionParameters {
+                messages: conversation.request_messages(),
+                ..parameters
+            })
+            .await?;
+
+        conversation.push_response(&response);
+        *self = conversation;
+
+        Ok(response)
+    }
+
+    /// Removes older messages according to the strategy until the request fits the token budget. The model and
+    /// tools of `parameters` are used to count the tokens.
+    pub async fn trim(
+        &mut self,
+        client: &Client,
+        parameters: &ChatCompletionParameters,
+    ) -> Result<(), APIError> {
+        if self.fits(parameters) {
+            return Ok(());
+        }
+
+        match self.strategy.clone() {
+            TruncationStrategy::DropOldest => {}
+            TruncationStrategy::KeepLast { messages } => self.keep_last(messages),
+            TruncationStrategy::Summarize { model, keep_last } => {
+                self.summarize(client, &model, keep_last).await?;
+            }
+        }
+
+        self.drop_oldest(parameters);
+
+        Ok(())
+    }
+
+    /// Returns the estimated number of prompt tokens of the conversation sent with `parameters`.
+    ///
+    /// Tokens are counted with the `Tokenizer` when the `tokenizer` feature is enabled, and estimated at four
+    /// characters of serialized JSON per token otherwise.
+    pub fn count_tokens(&self, parameters: &ChatCompletionParameters) -> usize {
+        let parameters = ChatCompletionParameters {
+            messages: self.request_messages(),
+            ..parameters.clone()
+        };
+
+        #[cfg(feature = "tokenizer")]
+        {
+            Tokenizer::for_model(&parameters.model).count_parameters(&parameters)
+        }
+
+        #[cfg(not(feature = "tokenizer"))]
+        {
+            let characters = serde_json::to_string(&parameters.messages)
+                .map(|json| json.len())
+                .unwrap_or_default()
+                + serde_json::to_string(&parameters.tools)
+                    .map(|json| json.len())
+                    .unwrap_or_default();
+
+            characters.div_ceil(4)
+        }
+    }
+
+    fn fits(&self, parameters: &ChatCompletionParameters) -> bool {
+        self.token_budget
+            .is_none_or(|token_budget| self.count_tokens(parameters) <= token_budget)
+    }
+
+    fn leading_system_messages(&self) -> usize {
+        self.messages
+            .iter()
+            .take_while(|message| is_system_message(message))
+            .count()
+    }
+
+    /// Returns the number of messages from `index` that must be removed together, so an assistant message with
+    /// tool calls is not separated from its tool messages.
+    fn group_length(&self, index: usize) -> usize {
+        1 + self.messages[index + 1..]
+            .iter()
+            .take_while(|message| matches!(message, ChatMessage::Tool { .. }))
+            .count()
+    }
+
+    /// Returns the index where the last `count` messages start, moved back to the start of a tool call group.
+    fn tail_start(&self, count: usize) -> usize {
+        let start = self.leading_system_messages();
+        let mut index = self.messages.len().saturating_sub(count).max(start);
+
+        while index > start && matches!(self.messages[index], ChatMessage::Tool { .. }) {
+            index -= 1;
+        }
+
+        index
+    }
+
+    fn keep_last(&mut self, count: usize) {
+        let start = self.leading_system_messages();
+        let end = self.tail_start(count);
+
+        self.messages.drain(start..end);
+    }
+
+    /// Removes the oldest messages, but never the last message, until the request fits the budget.
+    fn drop_oldest(&mut self, parameters: &ChatCompletionParameters) {
+        let start = self.leading_system_messages();
+
+        while !self.fits(parameters) {
+            let length = match self.messages.get(start) {
+                Some(_) => self.group_length(start),
+                None => break,
+            };
+
+            if start + length >= self.messages.len() {
+                break;
+            }
+
+            self.messages.drain(start..start + length);
+        }
+    }
+
+    async fn summarize(
+        &mut self,
+        client: &Client,
+        model: &str,
+        keep_last: usize,
+    ) -> Result<(), APIError> {
+        let start = self.leading_system_messages();
+        let end = self.tail_start(keep_last);
+
+        if start == end {
+            return Ok(());
+        }
+
+        let mut transcript = self
+            .summary
+            .iter()
+            .map(|summary| format!("summary of the earlier conversation: {summary}"))
+            .collect::<Vec<String>>();
+        transcript.extend(self.messages[start..end].iter().map(transcript_line));
+
+        let response = client
+            .chat()
+            .create(ChatCompletionParameters {
+                model: model.to_string(),
+                messages: vec![
+                    ChatMessage::System {
+                        content: ChatMessageContent::Text(SUMMARY_PROMPT.to_string()),
+                        name: None,
+                    },
+                    ChatMessage::User {
+                        content: ChatMessageContent::Text(transcript.join("\n")),
+                        name: None,
+                    },
+                ],
+                ..Default::default()
+            })
+            .await?;
+
+        let summary = response
+            .choices
+            .first()
+            .and_then(|choice| choice.message.text())
+            .ok_or_else(|| {
+                APIError::ParseError("the summary response contains no text".to_string())
+            })?;
+
+        self.summary = Some(summary.to_string());
+        self.messages.drain(start..end);
+
+        Ok(())
+    }
+}
+
+fn transcript_line(message: &ChatMessage) -> String {
+    let role = match message {
+        ChatMessage::Developer { .. } => "developer",
+        ChatMessage::System { .. } => "system",
+        ChatMessage::User { .. } => "user",
+        ChatMessage::Assistant { .. } => "assistant",
+        ChatMessage::Tool { .. } => "tool",
+    };
+
+    let mut line = format!("{role}: {}", message.text().unwrap_or_default());
+
+    if let ChatMessage::Assistant {
+        tool_calls: Some(tool_calls),
+        ..
+    } = message
+    {
+        for tool_call in tool_calls {
+            line.push_str(&format!(
+                " [called {}({})]",
+                tool_call.function.name, tool_call.function.arguments
+            ));
+        }
+    }
+
+    line
+}
+
+fn is_system_message(message: &ChatMessage) -> bool {
+    matches!(
+        message,
+        ChatMessage::System { .. } | ChatMessage::Developer { .. }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::history::{Conversation, TruncationStrategy};
+    use crate::v1::resources::chat::{
+        ChatCompletionParameters, ChatMessage, ChatMessageContent, Function, ToolCall,
+    };
+
+    fn text(role: &str, text: &str) -> ChatMessage {
+        let content = ChatMessageContent::Text(text.to_string());
+
+        match role {
+            "system" => ChatMessage::System {
+                content,
+                name: None,
+            },
+            "user" => ChatMessage::User {
+                content,
+                name: None,
+            },
+            _ => ChatMessage::Assistant {
+                content: Some(content),
+                reasoning: None,
+                reasoning_content: None,
+                refusal: None,
+                name: None,
+                audio: None,
+                tool_calls: None,
+            },
+        }
+    }
+
+    fn tool_call_message() -> ChatMessage {
+        ChatMessage::Assistant {
+            content: None,
+            reasoning: None,
+            reasoning_content: None,
+            refusal: None,
+            name: None,
+            audio: None,
+            tool_calls: Some(vec![ToolCall {
+                id: "call_1".to_string(),
+                r#type: "function".to_string(),
+                function: Function {
+                    name: "get_weather".to_string(),
+                    arguments: "{\"city\": \"Paris\"}".to_string(),
+                },
+            }]),
+        }
+    }
+
+    fn weather_conversation() -> Conversation {
+        let mut conversation = Conversation::with_messages(vec![
+            text("system", "You are a helpful assistant."),
+            text("user", "What is the weather in Paris?"),
+            tool_call_message(),
+        ]);
+        conversation
+            .push_tool_output("call_1", "Sunny and 24 degrees")
+            .push(text("assistant", "It is sunny and 24 degrees in Paris."))
+            .push(text("user", "And tomorrow?"));
+
+        conversation
+    }
+
+    #[test]
+    fn test_keep_last_does_not_split_tool_calls() {
+        let mut conversation = weather_conversation();
+        conversation.keep_last(3);
+
+        // The last 3 messages start with a tool message, so its assistant message is kept as well.
+        assert_eq!(conversation.messages().len(), 5);
+        assert_eq!(conversation.messages()[1], tool_call_message());
+
+        let mut conversation = weather_conversation();
+        conversation.set_strategy(TruncationStrategy::DropOldest);
+        let parameters = ChatCompletionParameters {
+            model: "gpt-4o".to_string(),
+            ..Default::default()
+        };
+        let budget = conversation.count_tokens(&parameters) - 1;
+        conversation.set_token_budget(budget);
+        conversation.drop_oldest(&parameters);
+
+        // Dropping the first user message fits the budget, the tool call group is kept.
+        assert_eq!(conversation.messages().len(), 5);
+        assert_eq!(conversation.messages()[1], tool_call_message());
+
+        let json = serde_json::to_string(&conversation).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Conversation>(&json).unwrap(),
+            conversation
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_create_summarizes_older_messages() {
+        use crate::v1::resources::chat::ChatCompletionParametersBuilder;
+        use crate::v1::testing::{Mock, MockResponse, MockServer};
+        use serde_json::json;
+
+        let response = |text: &str| {
+            json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion",
+                "created": 1,
+                "model": "gpt-4o",
+                "choices": [{
+                    "index": 0,
+                    "message": {"role": "assistant", "content": text},
+                    "finish_reason": "stop"
+                }]
+            })
+        };
+
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/chat/completions")
+                .respond_with(MockResponse::json(response("The user asked about Paris.")))
+                .times(1),
+        );
+        server.mock(
+            Mock::post("/chat/completions").respond_with(MockResponse::json(response("Rain."))),
+        );
+
+        let mut conversation = weather_conversation();
+        conversation
+            .set_token_budget(1)
+            .set_strategy(TruncationStrategy::Summarize {
+                model: "gpt-4o-mini".to_string(),
+                keep_last: 1,
+            });
+
+        let parameters = ChatCompletionParametersBuilder::default()
+            .model("gpt-4o")
+            .build()
+            .unwrap();
+        let response = conversation
+            .create(&server.client(), parameters)
+            .await
+            .unwrap();
+
+        assert_eq!(response.choices[0].message.text(), Some("Rain."));
+        assert_eq!(conversation.summary(), Some("The user asked about Paris."));
+        assert_eq!(conversation.messages().len(), 3);
+
+        let requests = server.received_requests();
+        let summary_request = requests[0].json().unwrap();
+        assert_eq!(summary_request["model"], "gpt-4o-mini");
+        assert!(summary_request["messages"][1]["content"]
+            .as_str()
+            .unwrap()
+            .contains("[called get_weather({\"city\": \"Paris\"})]"));
+
+        let request = requests[1].json().unwrap();
+        assert_eq!(request["messages"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            request["messages"][1]["content"],
+            "Summary of the earlier conversation:\nThe user asked about Paris."
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_create_skips_repeated_system_prompt() {
+        use crate::v1::resources::chat::ChatCompletionParametersBuilder;
+        use crate::v1::testing::MockServer;
+
+        let server = MockServer::start().await;
+        let client = server.client();
+        let mut conversation = Conversation::new();
+
+        for question in ["Hello!", "How are you?"] {
+            let parameters = ChatCompletionParametersBuilder::default()
+                .model("gpt-4o")
+                .messages(vec![
+                    text("system", "You are a helpful assistant."),
+                    text("user", question),
+                ])
+                .build()
+                .unwrap();
+
+            conversation.create(&client, parameters).await.unwrap();
+        }
+
+        let roles: Vec<&str> = conversation
+            .messages()
+            .iter()
+            .map(|message| match message {
+                ChatMessage::System { .. } => "system",
+                ChatMessage::User { .. } => "user",
+                _ => "assistant",
+            })
+            .collect();
+        assert_eq!(roles, ["system", "user", "assistant", "user", "assistant"]);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_create_keeps_conversation_on_failure() {
+        use crate::v1::error::APIError;
+        use crate::v1::resources::chat::ChatCompletionParametersBuilder;
+        use crate::v1::testing::{Mock, MockResponse, MockServer};
+
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/chat/completions")
+                .respond_with(MockResponse::error(
+                    500,
+                    "The server had an error",
+                    "server_error",
+                    None,
+                ))
+                .times(1),
+        );
+
+        let client = server.client();
+        let mut conversation = weather_conversation();
+        let before = conversation.clone();
+
+        let parameters = ChatCompletionParametersBuilder::default()
+            .model("gpt-4o")
+            .messages(vec![text("user", "And the day after?")])
+            .build()
+            .unwrap();
+
+        let result = conversation.create(&client, parameters.clone()).await;
+        assert!(matches!(result, Err(APIError::InternalServerError(_))));
+        assert_eq!(conversation, before);
+
+        // Retrying the turn sends the user message once
+        conversation.create(&client, parameters).await.unwrap();
+
+        let request = server.received_requests()[1].json().unwrap();
+        let messages = request["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), before.messages().len() + 1);
+        assert_eq!(messages.last().unwrap()["content"], "And the day after?");
+        assert_eq!(conversation.messages().len(), before.messages().len() + 2);
+    }
+}
@@ -0,0 +1,366 @@
+use crate::v1::error::APIError;
+#[cfg(feature = "stream")]
+use crate::v1::transport::{ByteStream, StreamingResponse};
+use crate::v1::transport::{HttpRequest, HttpResponse, Transport};
+use async_trait::async_trait;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Intercepts requests and responses between the client and the transport.
+///
+/// Middleware runs in the order it was added to the client. Each middleware receives the request and the
+/// rest of the chain (`next`), so it can mutate the request, short-circuit the call or mutate the response.
+/// The chain runs for every attempt made by the retry policy, and status codes are checked after the chain,
+/// so middleware can also translate error responses.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait Middleware: Debug + Send + Sync {
+    /// Handles JSON, multipart and raw bytes requests.
+    async fn handle(&self, request: HttpRequest, next: Next<'_>) -> Result<HttpResponse, APIError> {
+        next.run(request).await
+    }
+
+    /// Handles streaming (SSE and raw bytes stream) requests. The response body is passed on unread.
+    ///
+    /// The default implementation runs `handle` with the rest of the chain, so request changes and response hooks
+    /// also apply to streaming calls. `handle` receives the real status code and headers. The body of an error
+    /// response is read, so it can be inspected and translated, but the body of a successful response is an empty
+    /// placeholder: it is streamed on unless `handle` replaces it. Override this method to inspect streamed bodies.
+    #[cfg(feature = "stream")]
+    async fn handle_stream(
+        &self,
+        request: HttpRequest,
+        next: Next<'_>,
+    ) -> Result<StreamingResponse, APIError> {
+        let transport = StreamTransport {
+            next,
+            body: std::sync::Mutex::new(None),
+        };
+        let response = self.handle(request, Next::new(&transport, &[])).await?;
+
+        let body = transport.body.lock().unwrap().take();
+        match body {
+            Some(body) if response.body.is_empty() => Ok(StreamingResponse {
+                status: response.status,
+                headers: response.headers,
+                body,
+            }),
+            // The middleware answered without calling the rest of the chain, replaced the body or received an error
+            _ => Ok(StreamingResponse {
+                status: response.status,
+                headers: response.headers,
+                body: Box::pin(futures::stream::once(async move { Ok(response.body) })),
+            }),
+        }
+    }
+}
+
+/// Sends the request passed on by `Middleware::handle` as a streaming request, keeping the body of a successful
+/// response to pass it on unread.
+#[cfg(feature = "stream")]
+struct StreamTransport<'a> {
+    next: Next<'a>,
+    body: std::sync::Mutex<Option<ByteStream>>,
+}
+
+#[cfg(feature = "stream")]
+impl Debug for StreamTransport<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamTransport")
+            .field("next", &self.next)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "stream")]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Transport for StreamTransport<'_> {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+        let response = self.next.run_stream(request).await?;
+
+        if response.status.is_client_error() || response.status.is_server_error() {
+            return response.collect().await;
+        }
+
+        *self.body.lock().unwrap() = Some(response.body);
+
+        Ok(HttpResponse {
+            status: response.status,
+            headers: response.headers,
+            body: bytes::Bytes::new(),
+        })
+    }
+}
+
+/// The remaining middleware and the transport that sends the request.
+#[derive(Clone, Copy, Debug)]
+pub struct Next<'a> {
+    transport: &'a dyn Transport,
+    middlewares: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub fn new(transport: &'a dyn Transport, middlewares: &'a [Arc<dyn Middleware>]) -> Self {
+        Self {
+            transport,
+            middlewares,
+        }
+    }
+
+    /// Passes the request to the next middleware, or to the transport at the end of the chain.
+    pub async fn run(self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+        match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => {
+                middleware
+                    .handle(request, Next::new(self.transport, middlewares))
+                    .await
+            }
+            None => self.transport.send(request).await,
+        }
+    }
+
+    /// Passes the streaming request to the next middleware, or to the transport at the end of the chain.
+    #[cfg(feature = "stream")]
+    pub async fn run_stream(self, request: HttpRequest) -> Result<StreamingResponse, APIError> {
+        match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => {
+                middleware
+                    .handle_stream(request, Next::new(self.transport, middlewares))
+                    .await
+            }
+            None => self.transport.send_stream(request).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::api::Client;
+    use crate::v1::error::APIError;
+    use crate::v1::middleware::{Middleware, Next};
+    use crate::v1::transport::{HttpRequest, HttpResponse, Transport};
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use reqwest::StatusCode;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug)]
+    struct EchoHeadersTransport;
+
+    #[async_trait]
+    impl Transport for EchoHeadersTransport {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+            let trace = request.headers["x-trace"].to_str().unwrap().to_string();
+
+            Ok(HttpResponse {
+                status: StatusCode::NOT_FOUND,
+                headers: HeaderMap::new(),
+                body: Bytes::from(trace),
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    struct TraceMiddleware {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Middleware for TraceMiddleware {
+        async fn handle(
+            &self,
+            mut request: HttpRequest,
+            next: Next<'_>,
+        ) -> Result<HttpResponse, APIError> {
+            let trace = match request.headers.get("x-trace") {
+                Some(trace) => format!("{},{}", trace.to_str().unwrap(), self.name),
+                None => self.name.to_string(),
+            };
+            request
+                .headers
+                .insert("x-trace", HeaderValue::from_str(&trace).unwrap());
+
+            let response = next.run(request).await?;
+
+            self.log.lock().unwrap().push(format!(
+                "{} {}",
+                self.name,
+                String::from_utf8_lossy(&response.body)
+            ));
+
+            Ok(response)
+        }
+    }
+
+    #[derive(Debug)]
+    struct NotFoundAsEmptyList;
+
+    #[async_trait]
+    impl Middleware for NotFoundAsEmptyList {
+        async fn handle(
+            &self,
+            request: HttpRequest,
+            next: Next<'_>,
+        ) -> Result<HttpResponse, APIError> {
+            let mut response = next.run(request).await?;
+
+            if response.status == StatusCode::NOT_FOUND {
+                response.status = StatusCode::OK;
+                response.body = Bytes::from_static(br#"{"object": "list", "data": []}"#);
+            }
+
+            Ok(response)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_runs_in_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let mut client = Client::new("sk-test".to_string());
+        client
+            .set_transport(EchoHeadersTransport)
+            .add_middleware(NotFoundAsEmptyList)
+            .add_middleware(TraceMiddleware {
+                name: "first",
+                log: log.clone(),
+            })
+            .add_middleware(TraceMiddleware {
+                name: "second",
+                log: log.clone(),
+            });
+
+        let models = client.models().list().await.unwrap();
+
+        assert!(models.data.is_empty());
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["second first,second", "first first,second"]
+        );
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_handle_middleware_applies_to_streams() {
+        use crate::v1::resources::chat::{
+            ChatCompletionParametersBuilder, ChatMessage, ChatMessageContent,
+        };
+        use futures::StreamExt;
+
+        #[derive(Debug)]
+        struct RecordingTransport {
+            traces: Arc<Mutex<Vec<String>>>,
+        }
+
+        #[async_trait]
+        impl Transport for RecordingTransport {
+            async fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+                let trace = request.headers["x-trace"].to_str().unwrap().to_string();
+                self.traces.lock().unwrap().push(trace);
+
+                Ok(HttpResponse {
+                    status: StatusCode::OK,
+                    headers: HeaderMap::new(),
+                    body: Bytes::from_static(b"data: [DONE]\n\n"),
+                })
+            }
+        }
+
+        let traces = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let mut client = Client::new("sk-test".to_string());
+        client
+            .set_transport(RecordingTransport {
+                traces: traces.clone(),
+            })
+            .add_middleware(TraceMiddleware {
+                name: "first",
+                log: log.clone(),
+            })
+            .add_middleware(TraceMiddleware {
+                name: "second",
+                log: log.clone(),
+            });
+
+        let parameters = ChatCompletionParametersBuilder::default()
+            .model("gpt-4o")
+            .messages(vec![ChatMessage::User {
+                content: ChatMessageContent::Text("Hello".to_string()),
+                name: None,
+            }])
+            .build()
+            .unwrap();
+
+        let chunks: Vec<_> = client
+            .chat()
+            .create_stream(parameters)
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert!(chunks.is_empty());
+        assert_eq!(*traces.lock().unwrap(), vec!["first,second"]);
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_handle_middleware_sees_stream_error_status() {
+        use crate::v1::resources::chat::{
+            ChatCompletionParametersBuilder, ChatMessage, ChatMessageContent,
+        };
+
+        #[derive(Debug)]
+        struct StatusLog {
+            log: Arc<Mutex<Vec<String>>>,
+        }
+
+        #[async_trait]
+        impl Middleware for StatusLog {
+            async fn handle(
+                &self,
+                request: HttpRequest,
+                next: Next<'_>,
+            ) -> Result<HttpResponse, APIError> {
+                let response = next.run(request).await?;
+
+                self.log.lock().unwrap().push(format!(
+                    "{} {}",
+                    response.status.as_u16(),
+                    String::from_utf8_lossy(&response.body)
+                ));
+
+                Ok(response)
+            }
+        }
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let mut client = Client::new("sk-test".to_string());
+        client
+            .set_transport(EchoHeadersTransport)
+            .add_middleware(StatusLog { log: log.clone() })
+            .add_middleware(TraceMiddleware {
+                name: "first",
+                log: Arc::new(Mutex::new(Vec::new())),
+            });
+
+        let parameters = ChatCompletionParametersBuilder::default()
+            .model("gpt-4o")
+            .messages(vec![ChatMessage::User {
+                content: ChatMessageContent::Text("Hello".to_string()),
+                name: None,
+            }])
+            .build()
+            .unwrap();
+
+        let result = client.chat().create_stream(parameters).await;
+
+        assert!(result.is_err());
+        assert_eq!(*log.lock().unwrap(), vec!["404 first"]);
+    }
+}
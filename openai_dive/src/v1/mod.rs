@@ -1,9 +1,28 @@
 #[cfg(feature = "reqwest")]
 pub mod api;
+#[cfg(feature = "cassette")]
+pub mod cassette;
 #[cfg(feature = "reqwest")]
 pub mod endpoints;
 pub mod error;
 #[cfg(feature = "reqwest")]
 pub mod helpers;
+#[cfg(feature = "reqwest")]
+pub mod history;
+#[cfg(feature = "reqwest")]
+pub mod middleware;
 pub mod models;
+pub mod pagination;
+pub mod partial_json;
 pub mod resources;
+#[cfg(feature = "reqwest")]
+pub mod retry;
+#[cfg(feature = "schema")]
+pub mod schema;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tokenizer")]
+pub mod tokenizer;
+#[cfg(feature = "tools")]
+pub mod tools;
+pub mod transport;
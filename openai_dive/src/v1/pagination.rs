@@ -0,0 +1,555 @@
+use crate::v1::error::APIError;
+use crate::v1::resources::administration::audit_log::AuditLogParameters;
+use crate::v1::resources::chat::ListChatCompletionsParameters;
+use crate::v1::resources::conversation::ListConversationItemsParameters;
+use crate::v1::resources::file::ListFilesParameters;
+use crate::v1::resources::response::request::ListInputItemsParameters;
+use crate::v1::resources::shared::{ListParameters, ListResponse, SimpleListParameters};
+use crate::v1::resources::usage::UsageParameters;
+#[cfg(feature = "stream")]
+use futures::{stream, Stream, StreamExt};
+use std::future::Future;
+use std::pin::Pin;
+
+/// List parameters that can be moved to the next page of a list response.
+pub trait PaginationParameters: Clone {
+    /// Returns the parameters for the page after `response`, or `None` when it was the last page.
+    ///
+    /// Fails when the response has more pages but no cursor to continue with.
+    fn next_page_parameters<T>(&self, response: &ListResponse<T>)
+        -> Result<Option<Self>, APIError>;
+}
+
+/// Returns the cursor to continue with, unless there are no more pages or the cursor did not move.
+fn next_cursor(
+    current: &Option<String>,
+    next: &Option<String>,
+    has_more: bool,
+) -> Result<Option<String>, APIError> {
+    match next {
+        _ if !has_more => Ok(None),
+        Some(next) if current.as_ref() != Some(next) => Ok(Some(next.clone())),
+        Some(_) => Ok(None),
+        None => Err(APIError::ParseError(
+            "the list response has more pages but no cursor to continue with".to_string(),
+        )),
+    }
+}
+
+/// Implements `PaginationParameters` for list parameters with an `after` cursor, and optionally a `before`
+/// cursor to page backward with when it is set.
+macro_rules! impl_pagination_parameters {
+    (after: $($parameters:ty),+ $(,)?) => {
+        $(
+            impl PaginationParameters for $parameters {
+                fn next_page_parameters<T>(
+                    &self,
+                    response: &ListResponse<T>,
+                ) -> Result<Option<Self>, APIError> {
+                    Ok(
+                        next_cursor(&self.after, &response.last_id, response.has_more)?
+                            .map(|after| Self {
+                                after: Some(after),
+                                ..self.clone()
+                            }),
+                    )
+                }
+            }
+        )+
+    };
+    (before_after: $($parameters:ty),+ $(,)?) => {
+        $(
+            impl PaginationParameters for $parameters {
+                fn next_page_parameters<T>(
+                    &self,
+                    response: &ListResponse<T>,
+                ) -> Result<Option<Self>, APIError> {
+                    if self.before.is_some() {
+                        return Ok(
+                            next_cursor(&self.before, &response.first_id, response.has_more)?
+                                .map(|before| Self {
+                                    before: Some(before),
+                                    ..self.clone()
+                                }),
+                        );
+                    }
+
+                    Ok(
+                        next_cursor(&self.after, &response.last_id, response.has_more)?
+                            .map(|after| Self {
+                                after: Some(after),
+                                ..self.clone()
+                            }),
+                    )
+                }
+            }
+        )+
+    };
+}
+
+impl_pagination_parameters!(
+    after: SimpleListParameters,
+    ListFilesParameters,
+    ListChatCompletionsParameters,
+    ListInputItemsParameters,
+    ListConversationItemsParameters,
+);
+
+impl_pagination_parameters!(before_after: ListParameters, AuditLogParameters);
+
+impl PaginationParameters for UsageParameters {
+    fn next_page_parameters<T>(
+        &self,
+        response: &ListResponse<T>,
+    ) -> Result<Option<Self>, APIError> {
+        Ok(
+            next_cursor(&self.page, &response.next_page, response.has_more)?.map(|page| Self {
+                page: Some(page),
+                ..self.clone()
+            }),
+        )
+    }
+}
+
+/// The future of a page fetched by a `Paginator` returned from an endpoint, e.g. `client.files().paginate(..)`.
+#[cfg(not(target_arch = "wasm32"))]
+pub type PageFuture<'a, T> =
+    Pin<Box<dyn Future<Output = Result<ListResponse<T>, APIError>> + Send + 'a>>;
+
+/// The future of a page fetched by a `Paginator` returned from an endpoint, e.g. `client.files().paginate(..)`.
+#[cfg(target_arch = "wasm32")]
+pub type PageFuture<'a, T> = Pin<Box<dyn Future<Output = Result<ListResponse<T>, APIError>> + 'a>>;
+
+/// Fetches the pages of a list call, following the cursors of each page until the last one.
+///
+/// Every list endpoint has a `paginate` helper that returns a paginator, e.g. `client.files().paginate(..)`. Use
+/// `Paginator::new` for other list calls, like the usage endpoints.
+///
+/// ```no_run
+/// # async fn example(client: openai_dive::v1::api::Client) -> Result<(), openai_dive::v1::error::APIError> {
+/// use openai_dive::v1::pagination::Paginator;
+/// use openai_dive::v1::resources::usage::UsageParameters;
+///
+/// let batches = client
+///     .batches()
+///     .paginate(Default::default())
+///     .collect_all(1000)
+///     .await?;
+///
+/// let parameters = UsageParameters {
+///     start_time: 1730419200,
+///     ..Default::default()
+/// };
+///
+/// let buckets = Paginator::new(parameters, |query| {
+///     let client = &client;
+///     async move { client.usage().completions(query).await }
+/// })
+/// .collect_all(100)
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Paginator<P, F, T> {
+    parameters: P,
+    fetch: F,
+    item_cursor: Option<ItemCursor<T>>,
+}
+
+type ItemCursor<T> = Box<dyn Fn(&T) -> String + Send + Sync>;
+
+#[cfg(feature = "stream")]
+enum PageState<P, F, T> {
+    Next(Paginator<P, F, T>),
+    Failed(APIError),
+}
+
+impl<P, F, Fut, T> Paginator<P, F, T>
+where
+    P: PaginationParameters,
+    F: FnMut(P) -> Fut,
+    Fut: Future<Output = Result<ListResponse<T>, APIError>>,
+{
+    /// Creates a paginator starting at `parameters`, fetching each page with `fetch`.
+    pub fn new(parameters: P, fetch: F) -> Self {
+        Self {
+            parameters,
+            fetch,
+            item_cursor: None,
+        }
+    }
+
+    /// Sets the function that returns the ID of an item, used as cursor when a page has no `first_id`/`last_id`.
+    ///
+    /// The fine-tuning endpoints, for example, don't return these fields.
+    pub fn with_item_cursor(
+        mut self,
+        item_cursor: impl Fn(&T) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.item_cursor = Some(Box::new(item_cursor));
+
+        self
+    }
+
+    /// Fetches the page of the current parameters.
+    ///
+    /// Missing `first_id` and `last_id` fields of the page are filled in with the item cursor.
+    async fn fetch_page(&mut self) -> Result<ListResponse<T>, APIError> {
+        let mut page = (self.fetch)(self.parameters.clone()).await?;
+
+        if let Some(item_cursor) = &self.item_cursor {
+            if page.first_id.is_none() {
+                page.first_id = page.data.first().map(item_cursor);
+            }
+            if page.last_id.is_none() {
+                page.last_id = page.data.last().map(item_cursor);
+            }
+        }
+
+        Ok(page)
+    }
+
+    /// Returns a stream of pages. The stream ends after the last page or the first error.
+    ///
+    /// Missing `first_id` and `last_id` fields of a page are filled in with the item cursor.
+    #[cfg(feature = "stream")]
+    pub fn pages(self) -> impl Stream<Item = Result<ListResponse<T>, APIError>> {
+        stream::unfold(Some(PageState::Next(self)), |state| async move {
+            let mut paginator = match state? {
+                PageState::Next(paginator) => paginator,
+                PageState::Failed(error) => return Some((Err(error), None)),
+            };
+
+            let page = match paginator.fetch_page().await {
+                Ok(page) => page,
+                Err(error) => return Some((Err(error), None)),
+            };
+
+            let next = match paginator.parameters.next_page_parameters(&page) {
+                Ok(Some(parameters)) => {
+                    paginator.parameters = parameters;
+                    Some(PageState::Next(paginator))
+                }
+                Ok(None) => None,
+                Err(error) => Some(PageState::Failed(error)),
+            };
+
+            Some((Ok(page), next))
+        })
+    }
+
+    /// Returns a stream of the items of all pages.
+    #[cfg(feature = "stream")]
+    pub fn into_stream(self) -> impl Stream<Item = Result<T, APIError>> {
+        self.pages().flat_map(|page| {
+            stream::iter(match page {
+                Ok(page) => page.data.into_iter().map(Ok).collect(),
+                Err(error) => vec![Err(error)],
+            })
+        })
+    }
+
+    /// Collects the items of all pages, stopping once `max_items` items have been collected.
+    pub async fn collect_all(mut self, max_items: usize) -> Result<Vec<T>, APIError> {
+        let mut items = Vec::new();
+
+        while items.len() < max_items {
+            let page = self.fetch_page().await?;
+            let next = self.parameters.next_page_parameters(&page)?;
+            items.extend(page.data.into_iter().take(max_items - items.len()));
+
+            match next {
+                Some(parameters) => self.parameters = parameters,
+                None => break,
+            }
+        }
+
+        Ok(items)
+    }
+}
+
+/// Creates a paginator for an endpoint, boxing the future of `fetch` so the paginator type can be named.
+#[cfg(all(feature = "reqwest", not(target_arch = "wasm32")))]
+pub(crate) fn paginate<'a, P, T, F, Fut>(
+    parameters: P,
+    fetch: F,
+) -> Paginator<P, impl FnMut(P) -> PageFuture<'a, T> + 'a, T>
+where
+    P: PaginationParameters,
+    F: Fn(P) -> Fut + 'a,
+    Fut: Future<Output = Result<ListResponse<T>, APIError>> + Send + 'a,
+{
+    Paginator::new(parameters, move |query| -> PageFuture<'a, T> {
+        Box::pin(fetch(query))
+    })
+}
+
+/// Creates a paginator for an endpoint, boxing the future of `fetch` so the paginator type can be named.
+#[cfg(all(feature = "reqwest", target_arch = "wasm32"))]
+pub(crate) fn paginate<'a, P, T, F, Fut>(
+    parameters: P,
+    fetch: F,
+) -> Paginator<P, impl FnMut(P) -> PageFuture<'a, T> + 'a, T>
+where
+    P: PaginationParameters,
+    F: Fn(P) -> Fut + 'a,
+    Fut: Future<Output = Result<ListResponse<T>, APIError>> + 'a,
+{
+    Paginator::new(parameters, move |query| -> PageFuture<'a, T> {
+        Box::pin(fetch(query))
+    })
+}
+
+/// Implements a method that returns a `Paginator` over all pages of a list method of an endpoint.
+///
+/// Every page is fetched with a new endpoint created from the client, given by the path of the client field and
+/// the endpoint accessors, so the paginator does not borrow the endpoint. A list method with an ID parameter
+/// gets the same parameter. The optional `item_cursor` is passed to `Paginator::with_item_cursor`.
+#[cfg(feature = "reqwest")]
+macro_rules! paginate_method {
+    (
+        $(#[$attr:meta])*
+        $name:ident => $list:ident($parameters:ty) -> $item:ty,
+        client: self.$($field:ident).+,
+        endpoint: $($endpoint:ident()).+
+        $(, item_cursor: $item_cursor:expr)?
+    ) => {
+        #[doc = concat!("Returns a paginator over all pages of `", stringify!($list), "`, starting at `query`.")]
+        $(#[$attr])*
+        pub fn $name(
+            &self,
+            query: $parameters,
+        ) -> $crate::v1::pagination::Paginator<
+            $parameters,
+            impl FnMut($parameters) -> $crate::v1::pagination::PageFuture<'a, $item> + 'a,
+            $item,
+        > {
+            let client = self.$($field).+;
+
+            $crate::v1::pagination::paginate(query, move |query| async move {
+                client.$($endpoint()).+.$list(Some(query)).await
+            })
+            $(.with_item_cursor($item_cursor))?
+        }
+    };
+    (
+        $(#[$attr:meta])*
+        $name:ident => $list:ident($id:ident, $parameters:ty) -> $item:ty,
+        client: self.$($field:ident).+,
+        endpoint: $($endpoint:ident()).+
+        $(, item_cursor: $item_cursor:expr)?
+    ) => {
+        #[doc = concat!("Returns a paginator over all pages of `", stringify!($list), "`, starting at `query`.")]
+        $(#[$attr])*
+        pub fn $name(
+            &self,
+            $id: &str,
+            query: $parameters,
+        ) -> $crate::v1::pagination::Paginator<
+            $parameters,
+            impl FnMut($parameters) -> $crate::v1::pagination::PageFuture<'a, $item> + 'a,
+            $item,
+        > {
+            let client = self.$($field).+;
+            let $id = $id.to_string();
+
+            $crate::v1::pagination::paginate(query, move |query| {
+                let $id = $id.clone();
+                async move { client.$($endpoint()).+.$list(&$id, Some(query)).await }
+            })
+            $(.with_item_cursor($item_cursor))?
+        }
+    };
+}
+
+#[cfg(feature = "reqwest")]
+pub(crate) use paginate_method;
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::error::APIError;
+    use crate::v1::pagination::Paginator;
+    use crate::v1::resources::shared::{ListParameters, ListResponse, SimpleListParameters};
+    #[cfg(feature = "stream")]
+    use crate::v1::resources::usage::UsageParameters;
+    #[cfg(feature = "stream")]
+    use futures::StreamExt;
+    use std::sync::{Arc, Mutex};
+
+    fn page(ids: &[u32], has_more: bool, next_page: Option<&str>) -> ListResponse<u32> {
+        ListResponse {
+            object: "list".to_string(),
+            data: ids.to_vec(),
+            first_id: ids.first().map(|id| id.to_string()),
+            last_id: ids.last().map(|id| id.to_string()),
+            has_more,
+            next_page: next_page.map(|page| page.to_string()),
+        }
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_pages_are_fetched_with_after_cursor() {
+        let cursors = Arc::new(Mutex::new(Vec::new()));
+
+        let items: Vec<u32> = Paginator::new(SimpleListParameters::default(), |query| {
+            cursors.lock().unwrap().push(query.after.clone());
+
+            async move {
+                Ok(match query.after.as_deref() {
+                    None => page(&[1, 2], true, None),
+                    Some("2") => page(&[3, 4], true, None),
+                    _ => page(&[5], false, None),
+                })
+            }
+        })
+        .into_stream()
+        .map(|item| item.unwrap())
+        .collect()
+        .await;
+
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+        assert_eq!(
+            *cursors.lock().unwrap(),
+            vec![None, Some("2".to_string()), Some("4".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pages_are_fetched_with_before_cursor() {
+        let parameters = ListParameters {
+            before: Some("10".to_string()),
+            ..Default::default()
+        };
+
+        let items = Paginator::new(parameters, |query| async move {
+            Ok(match query.before.as_deref() {
+                Some("10") => page(&[8, 9], true, None),
+                _ => page(&[7], false, None),
+            })
+        })
+        .collect_all(usize::MAX)
+        .await
+        .unwrap();
+
+        assert_eq!(items, vec![8, 9, 7]);
+    }
+
+    #[tokio::test]
+    async fn test_collect_all_stops_at_cap() {
+        let fetches = Arc::new(Mutex::new(0));
+
+        let items = Paginator::new(SimpleListParameters::default(), |query| {
+            *fetches.lock().unwrap() += 1;
+
+            async move {
+                let start = query.after.map_or(0, |after| after.parse().unwrap());
+                Ok(page(&[start + 1, start + 2], true, None))
+            }
+        })
+        .collect_all(3)
+        .await
+        .unwrap();
+
+        assert_eq!(items, vec![1, 2, 3]);
+        assert_eq!(*fetches.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pages_without_last_id_use_item_cursor() {
+        let fetch = |query: SimpleListParameters| async move {
+            let mut page = match query.after.as_deref() {
+                None => page(&[1, 2], true, None),
+                _ => page(&[3], false, None),
+            };
+            page.first_id = None;
+            page.last_id = None;
+
+            Ok(page)
+        };
+
+        let result = Paginator::new(SimpleListParameters::default(), fetch)
+            .collect_all(usize::MAX)
+            .await;
+        assert!(matches!(result, Err(APIError::ParseError(_))));
+
+        let items = Paginator::new(SimpleListParameters::default(), fetch)
+            .with_item_cursor(|item| item.to_string())
+            .collect_all(usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(items, vec![1, 2, 3]);
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_usage_pages_follow_next_page() {
+        let parameters = UsageParameters {
+            start_time: 1730419200,
+            ..Default::default()
+        };
+
+        let pages: Vec<_> = Paginator::new(parameters, |query| async move {
+            match query.page.as_deref() {
+                None => Ok(page(&[1], true, Some("page_2"))),
+                Some("page_2") => Ok(page(&[2], false, None)),
+                _ => Err(APIError::ServerError("unexpected page".to_string())),
+            }
+        })
+        .pages()
+        .collect()
+        .await;
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[1].as_ref().unwrap().data, vec![2]);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_endpoint_paginate_follows_cursor() {
+        use crate::v1::resources::file::ListFilesParameters;
+        use crate::v1::testing::{Mock, MockResponse, MockServer};
+        use serde_json::json;
+
+        fn file(id: &str) -> serde_json::Value {
+            json!({"id": id, "object": "file", "bytes": 1, "created_at": 1, "filename": "a.jsonl", "purpose": "batch"})
+        }
+
+        let server = MockServer::start().await;
+        server
+            .mock(
+                Mock::get("/files")
+                    .respond_with(MockResponse::json(json!({
+                        "object": "list",
+                        "data": [file("file-1"), file("file-2")],
+                        "first_id": "file-1",
+                        "last_id": "file-2",
+                        "has_more": true
+                    })))
+                    .times(1),
+            )
+            .mock(Mock::get("/files").respond_with(MockResponse::json(json!({
+                "object": "list",
+                "data": [file("file-3")],
+                "first_id": "file-3",
+                "last_id": "file-3",
+                "has_more": false
+            }))));
+
+        let client = server.client();
+        let paginator = client.files().paginate(ListFilesParameters::default());
+        let collect = paginator.collect_all(usize::MAX);
+
+        fn assert_send<T: Send>(_: &T) {}
+        assert_send(&collect);
+
+        let files = collect.await.unwrap();
+
+        let ids: Vec<&str> = files.iter().map(|file| file.id.as_str()).collect();
+        assert_eq!(ids, ["file-1", "file-2", "file-3"]);
+
+        let requests = server.received_requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].query.as_deref(), Some("after=file-2"));
+    }
+}
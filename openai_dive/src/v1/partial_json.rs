@@ -0,0 +1,335 @@
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
+
+/// Parses the JSON received so far, for example the streamed content of a structured output or the streamed
+/// arguments of a tool call.
+///
+/// Open strings, arrays and objects are closed, and a trailing key without a value or an incomplete literal is
+/// left out. Returns `None` when the input contains no value yet or is not the beginning of a JSON value.
+pub fn parse_partial_json(input: &str) -> Option<Value> {
+    let mut parser = Parser {
+        input: input.as_bytes(),
+        position: 0,
+    };
+
+    let value = parser.parse_value().ok()??;
+
+    parser.skip_whitespace();
+    if parser.position < parser.input.len() {
+        return None;
+    }
+
+    Some(value)
+}
+
+/// Accumulates streamed JSON and parses the text received so far.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PartialJson {
+    text: String,
+}
+
+impl PartialJson {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a delta and returns the value parsed from all text received so far.
+    pub fn push(&mut self, delta: &str) -> Option<Value> {
+        self.text.push_str(delta);
+
+        self.value()
+    }
+
+    /// The text received so far.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The value parsed from the text received so far.
+    pub fn value(&self) -> Option<Value> {
+        parse_partial_json(&self.text)
+    }
+
+    /// Deserializes the value parsed so far into `T`.
+    ///
+    /// Fields that have not been received yet are missing, so `T` should make every field optional
+    /// (an `Option` or `#[serde(default)]`).
+    pub fn parse<T: DeserializeOwned>(&self) -> Option<T> {
+        parse_partial(&self.text)
+    }
+}
+
+/// Parses the JSON received so far into `T`, see `parse_partial_json` and `PartialJson::parse`.
+pub fn parse_partial<T: DeserializeOwned>(input: &str) -> Option<T> {
+    serde_json::from_value(parse_partial_json(input)?).ok()
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+/// The input is not the beginning of a JSON value.
+struct Invalid;
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\n' | b'\r' | b'\t')) {
+            self.position += 1;
+        }
+    }
+
+    /// Parses a value, returning `None` when the input ends before anything of the value can be used.
+    fn parse_value(&mut self) -> Result<Option<Value>, Invalid> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            None => Ok(None),
+            Some(b'{') => self.parse_object().map(Some),
+            Some(b'[') => self.parse_array().map(Some),
+            Some(b'"') => Ok(Some(Value::String(self.parse_string()?.0))),
+            Some(b't') => self.parse_literal("true", Value::Bool(true)),
+            Some(b'f') => self.parse_literal("false", Value::Bool(false)),
+            Some(b'n') => self.parse_literal("null", Value::Null),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(_) => Err(Invalid),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value, Invalid> {
+        self.position += 1;
+        let mut object = Map::new();
+
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                None => return Ok(Value::Object(object)),
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Value::Object(object));
+                }
+                Some(b'"') => {}
+                Some(_) => return Err(Invalid),
+            }
+
+            let (key, complete) = self.parse_string()?;
+            if !complete {
+                return Ok(Value::Object(object));
+            }
+
+            self.skip_whitespace();
+            match self.peek() {
+                None => return Ok(Value::Object(object)),
+                Some(b':') => self.position += 1,
+                Some(_) => return Err(Invalid),
+            }
+
+            match self.parse_value()? {
+                Some(value) => {
+                    object.insert(key, value);
+                }
+                None => return Ok(Value::Object(object)),
+            }
+
+            self.skip_whitespace();
+            match self.peek() {
+                None => return Ok(Value::Object(object)),
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Value::Object(object));
+                }
+                Some(_) => return Err(Invalid),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value, Invalid> {
+        self.position += 1;
+        let mut array = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+
+            if self.peek() == Some(b']') {
+                self.position += 1;
+                return Ok(Value::Array(array));
+            }
+
+            match self.parse_value()? {
+                Some(value) => array.push(value),
+                None => return Ok(Value::Array(array)),
+            }
+
+            self.skip_whitespace();
+            match self.peek() {
+                None => return Ok(Value::Array(array)),
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Value::Array(array));
+                }
+                Some(_) => return Err(Invalid),
+            }
+        }
+    }
+
+    /// Parses a string, returning whether its closing quote was received. An incomplete escape sequence at
+    /// the end of the input is left out.
+    fn parse_string(&mut self) -> Result<(String, bool), Invalid> {
+        let start = self.position;
+        self.position += 1;
+
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'"' => {
+                    self.position += 1;
+                    let text = std::str::from_utf8(&self.input[start..self.position])
+                        .map_err(|_| Invalid)?;
+
+                    return serde_json::from_str(text)
+                        .map(|string| (string, true))
+                        .map_err(|_| Invalid);
+                }
+                b'\\' => self.position += 2,
+                _ => self.position += 1,
+            }
+        }
+        self.position = self.input.len();
+
+        let mut text = String::from_utf8_lossy(&self.input[start..]).into_owned();
+
+        // Drop an incomplete escape sequence, e.g. `\` or `\u00`.
+        if let Some(index) = text.rfind('\\') {
+            let escape = &text[index..];
+            let backslashes = text[..index]
+                .chars()
+                .rev()
+                .take_while(|char| *char == '\\')
+                .count();
+            let incomplete = escape.len() == 1 || (escape.starts_with("\\u") && escape.len() < 6);
+
+            if backslashes % 2 == 0 && incomplete {
+                text.truncate(index);
+            }
+        }
+        text.push('"');
+
+        serde_json::from_str(&text)
+            .map(|string| (string, false))
+            .map_err(|_| Invalid)
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: Value) -> Result<Option<Value>, Invalid> {
+        let rest = &self.input[self.position..];
+        let length = rest.len().min(literal.len());
+
+        if rest[..length] != literal.as_bytes()[..length] {
+            return Err(Invalid);
+        }
+        self.position += length;
+
+        Ok((length == literal.len()).then_some(value))
+    }
+
+    fn parse_number(&mut self) -> Result<Option<Value>, Invalid> {
+        let start = self.position;
+
+        while matches!(
+            self.peek(),
+            Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+        ) {
+            self.position += 1;
+        }
+
+        let text = std::str::from_utf8(&self.input[start..self.position]).map_err(|_| Invalid)?;
+
+        if let Ok(number) = serde_json::from_str::<Number>(text) {
+            return Ok(Some(Value::Number(number)));
+        }
+
+        if self.position < self.input.len() {
+            return Err(Invalid);
+        }
+
+        // The number was cut off, e.g. `1.` or `-`, so use the longest prefix that is a number.
+        let number = (1..text.len())
+            .rev()
+            .find_map(|length| serde_json::from_str::<Number>(&text[..length]).ok());
+
+        Ok(number.map(Value::Number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::partial_json::{parse_partial_json, PartialJson};
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_partial_json() {
+        let cases = [
+            ("", None),
+            ("{", Some(json!({}))),
+            ("{\"na", Some(json!({}))),
+            ("{\"name\"", Some(json!({}))),
+            ("{\"name\": ", Some(json!({}))),
+            ("{\"name\": \"Par", Some(json!({"name": "Par"}))),
+            ("{\"name\": \"a\\", Some(json!({"name": "a"}))),
+            ("{\"name\": \"a\\u00", Some(json!({"name": "a"}))),
+            ("{\"name\": \"a\\n", Some(json!({"name": "a\n"}))),
+            ("{\"ok\": tr", Some(json!({}))),
+            (
+                "{\"ok\": true, \"n\": 1.",
+                Some(json!({"ok": true, "n": 1})),
+            ),
+            ("{\"n\": -", Some(json!({}))),
+            ("[1, 2, {\"a\": [3", Some(json!([1, 2, {"a": [3]}]))),
+            ("{\"a\": 1,", Some(json!({"a": 1}))),
+            ("{\"a\": 1} ", Some(json!({"a": 1}))),
+            ("{\"a\": 1}}", None),
+            ("{\"a\" 1", None),
+            ("hello", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(parse_partial_json(input), expected, "input: {input}");
+        }
+    }
+
+    #[test]
+    fn test_partial_json_deserializes_received_fields() {
+        #[derive(Debug, Default, PartialEq, Deserialize)]
+        #[serde(default)]
+        struct Weather {
+            city: Option<String>,
+            temperatures: Vec<f32>,
+        }
+
+        let mut partial = PartialJson::new();
+
+        partial.push("{\"city\": \"Ams");
+        assert_eq!(
+            partial.parse::<Weather>(),
+            Some(Weather {
+                city: Some("Ams".to_string()),
+                temperatures: vec![],
+            })
+        );
+
+        partial.push("terdam\", \"temperatures\": [12.5, 1");
+        assert_eq!(
+            partial.parse::<Weather>(),
+            Some(Weather {
+                city: Some("Amsterdam".to_string()),
+                temperatures: vec![12.5, 1.0],
+            })
+        );
+    }
+}
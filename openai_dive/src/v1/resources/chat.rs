@@ -1,9 +1,11 @@
 use super::shared::{ReasoningEffort, WebSearchContextSize};
 use crate::v1::resources::shared::StopToken;
-use crate::v1::resources::shared::{FinishReason, Usage};
+use crate::v1::resources::shared::{FinishReason, SortOrder, Usage};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+#[cfg(feature = "stream")]
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::fmt::Display;
 
@@ -29,6 +31,89 @@ pub struct ChatCompletionResponse {
     /// Usage statistics for the completion request.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage: Option<Usage>,
+    /// The metadata of a stored chat completion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Builder, Clone, PartialEq)]
+#[builder(name = "ListChatCompletionsParametersBuilder")]
+#[builder(setter(into, strip_option), default)]
+pub struct ListChatCompletionsParameters {
+    /// The model used to generate the chat completions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Only return chat completions with all of these metadata key-value pairs, sent as `metadata[key]=value`.
+    #[serde(
+        flatten,
+        serialize_with = "serialize_metadata_filter",
+        deserialize_with = "deserialize_metadata_filter"
+    )]
+    pub metadata: Option<HashMap<String, String>>,
+    /// Identifier for the last chat completion from the previous pagination request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    /// Number of chat completions to retrieve.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// Sort order by the created timestamp.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<SortOrder>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Builder, Clone, PartialEq)]
+#[builder(name = "UpdateChatCompletionParametersBuilder")]
+#[builder(setter(into, strip_option), default)]
+pub struct UpdateChatCompletionParameters {
+    /// Set of 16 key-value pairs that replace the metadata of the stored chat completion, or `None` to remove it.
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// A message of a stored chat completion.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChatCompletionStoredMessage {
+    /// The identifier of the message.
+    pub id: String,
+    #[serde(flatten)]
+    pub message: ChatMessage,
+}
+
+fn serialize_metadata_filter<S>(
+    metadata: &Option<HashMap<String, String>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_map(
+        metadata
+            .iter()
+            .flatten()
+            .map(|(key, value)| (format!("metadata[{key}]"), value)),
+    )
+}
+
+fn deserialize_metadata_filter<'de, D>(
+    deserializer: D,
+) -> Result<Option<HashMap<String, String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let fields = HashMap::<String, Value>::deserialize(deserializer)?;
+
+    let metadata = fields
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let key = key
+                .strip_prefix("metadata[")?
+                .strip_suffix(']')?
+                .to_string();
+
+            Some((key, value.as_str()?.to_string()))
+        })
+        .collect::<HashMap<String, String>>();
+
+    Ok((!metadata.is_empty()).then_some(metadata))
 }
 
 #[cfg(feature = "stream")]
@@ -43,6 +128,9 @@ pub struct ChatCompletionChunkResponse {
     pub created: u32,
     /// The model to generate the completion.
     pub model: String,
+    /// The service tier used for processing the request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_tier: Option<String>,
     /// This fingerprint represents the backend configuration that the model runs with.
     /// Can be used in conjunction with the seed request parameter to understand when backend changes have been made that might impact determinism.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -203,6 +291,9 @@ pub struct ChatCompletionFunction {
     pub description: Option<String>,
     /// The parameters the function takes. The model will generate JSON inputs for these parameters.
     pub parameters: serde_json::Value,
+    /// Whether to enable strict schema adherence when generating the function call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -728,6 +819,264 @@ impl Default for ChatMessageContent {
     }
 }
 
+/// Rebuilds a `ChatCompletionResponse` from the chunks of a streamed chat completion.
+#[cfg(feature = "stream")]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ChatCompletionAccumulator {
+    id: Option<String>,
+    created: u32,
+    model: String,
+    service_tier: Option<String>,
+    system_fingerprint: Option<String>,
+    usage: Option<Usage>,
+    choices: BTreeMap<u32, AccumulatedChoice>,
+}
+
+#[cfg(feature = "stream")]
+#[derive(Debug, Default, Clone, PartialEq)]
+struct AccumulatedChoice {
+    content: Option<String>,
+    reasoning: Option<String>,
+    reasoning_content: Option<String>,
+    refusal: Option<String>,
+    name: Option<String>,
+    tool_calls: BTreeMap<u32, AccumulatedToolCall>,
+    finish_reason: Option<FinishReason>,
+    logprobs: Option<LogProps>,
+}
+
+#[cfg(feature = "stream")]
+#[derive(Debug, Clone, PartialEq)]
+struct AccumulatedToolCall {
+    id: Option<String>,
+    r#type: Option<String>,
+    function: DeltaFunction,
+}
+
+#[cfg(feature = "stream")]
+impl ChatCompletionAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merges a chunk into the accumulated response.
+    pub fn push(&mut self, chunk: &ChatCompletionChunkResponse) {
+        if self.id.is_none() {
+            self.id.clone_from(&chunk.id);
+        }
+        if self.model.is_empty() {
+            self.model.clone_from(&chunk.model);
+        }
+        if self.created == 0 {
+            self.created = chunk.created;
+        }
+        if chunk.service_tier.is_some() {
+            self.service_tier.clone_from(&chunk.service_tier);
+        }
+        if chunk.system_fingerprint.is_some() {
+            self.system_fingerprint
+                .clone_from(&chunk.system_fingerprint);
+        }
+        if chunk.usage.is_some() {
+            self.usage.clone_from(&chunk.usage);
+        }
+
+        for (position, choice) in chunk.choices.iter().enumerate() {
+            let index = choice.index.unwrap_or(position as u32);
+            self.choices.entry(index).or_default().push(choice);
+        }
+    }
+
+    /// Returns the response accumulated so far.
+    pub fn response(&self) -> ChatCompletionResponse {
+        ChatCompletionResponse {
+            id: self.id.clone(),
+            choices: self
+                .choices
+                .iter()
+                .map(|(index, choice)| choice.to_choice(*index))
+                .collect(),
+            created: self.created,
+            model: self.model.clone(),
+            service_tier: self.service_tier.clone(),
+            system_fingerprint: self.system_fingerprint.clone(),
+            object: "chat.completion".to_string(),
+            usage: self.usage.clone(),
+            metadata: None,
+        }
+    }
+
+    /// Returns the content of a choice received so far.
+    pub fn content(&self, choice_index: u32) -> Option<&str> {
+        self.choices.get(&choice_index)?.content.as_deref()
+    }
+
+    /// Returns the arguments of a tool call of a choice received so far.
+    pub fn tool_call_arguments(&self, choice_index: u32, tool_call_index: u32) -> Option<&str> {
+        self.choices
+            .get(&choice_index)?
+            .tool_calls
+            .get(&tool_call_index)?
+            .function
+            .arguments
+            .as_deref()
+    }
+}
+
+#[cfg(feature = "stream")]
+impl AccumulatedChoice {
+    fn push(&mut self, choice: &ChatCompletionChunkChoice) {
+        match &choice.delta {
+            DeltaChatMessage::Assistant {
+                content,
+                reasoning,
+                reasoning_content,
+                refusal,
+                name,
+                tool_calls,
+            }
+            | DeltaChatMessage::Untagged {
+                content,
+                reasoning,
+                reasoning_content,
+                refusal,
+                name,
+                tool_calls,
+                ..
+            } => {
+                if let Some(content) = content {
+                    append(&mut self.content, &content_text(content));
+                }
+                if let Some(reasoning) = reasoning {
+                    append(&mut self.reasoning, reasoning);
+                }
+                if let Some(reasoning_content) = reasoning_content {
+                    append(&mut self.reasoning_content, reasoning_content);
+                }
+                if let Some(refusal) = refusal {
+                    append(&mut self.refusal, refusal);
+                }
+                if self.name.is_none() {
+                    self.name.clone_from(name);
+                }
+                for tool_call in tool_calls.iter().flatten() {
+                    self.push_tool_call(tool_call);
+                }
+            }
+            _ => {}
+        }
+
+        if choice.finish_reason.is_some() {
+            self.finish_reason.clone_from(&choice.finish_reason);
+        }
+
+        if let Some(logprobs) = &choice.logprobs {
+            let accumulated = self.logprobs.get_or_insert(LogProps {
+                content: None,
+                refusal: None,
+            });
+
+            if let Some(content) = &logprobs.content {
+                accumulated
+                    .content
+                    .get_or_insert_with(Vec::new)
+                    .extend(content.iter().cloned());
+            }
+            if let Some(refusal) = &logprobs.refusal {
+                accumulated
+                    .refusal
+                    .get_or_insert_with(Vec::new)
+                    .extend(refusal.iter().cloned());
+            }
+        }
+    }
+
+    fn push_tool_call(&mut self, tool_call: &DeltaToolCall) {
+        // Without an index, a delta with an ID starts a new tool call and any other delta continues the last one.
+        let index = match tool_call.index {
+            Some(index) => index,
+            None => match self.tool_calls.keys().next_back() {
+                Some(last) if tool_call.id.is_none() => *last,
+                Some(last) => last + 1,
+                None => 0,
+            },
+        };
+
+        let accumulated = self
+            .tool_calls
+            .entry(index)
+            .or_insert_with(|| AccumulatedToolCall {
+                id: None,
+                r#type: None,
+                function: DeltaFunction {
+                    name: None,
+                    arguments: None,
+                },
+            });
+
+        if accumulated.id.is_none() {
+            accumulated.id.clone_from(&tool_call.id);
+        }
+        if accumulated.r#type.is_none() {
+            accumulated.r#type.clone_from(&tool_call.r#type);
+        }
+        accumulated.function.merge(&tool_call.function);
+    }
+
+    fn to_choice(&self, index: u32) -> ChatCompletionChoice {
+        let tool_calls: Vec<ToolCall> = self
+            .tool_calls
+            .values()
+            .map(|tool_call| ToolCall {
+                id: tool_call.id.clone().unwrap_or_default(),
+                r#type: tool_call
+                    .r#type
+                    .clone()
+                    .unwrap_or_else(|| "function".to_string()),
+                function: Function {
+                    name: tool_call.function.name.clone().unwrap_or_default(),
+                    arguments: tool_call.function.arguments.clone().unwrap_or_default(),
+                },
+            })
+            .collect();
+
+        ChatCompletionChoice {
+            index,
+            message: ChatMessage::Assistant {
+                content: self.content.clone().map(ChatMessageContent::Text),
+                reasoning: self.reasoning.clone(),
+                reasoning_content: self.reasoning_content.clone(),
+                refusal: self.refusal.clone(),
+                name: self.name.clone(),
+                audio: None,
+                tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+            },
+            finish_reason: self.finish_reason.clone(),
+            logprobs: self.logprobs.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "stream")]
+fn append(accumulated: &mut Option<String>, text: &str) {
+    accumulated.get_or_insert_with(String::new).push_str(text);
+}
+
+#[cfg(feature = "stream")]
+fn content_text(content: &ChatMessageContent) -> String {
+    match content {
+        ChatMessageContent::Text(text) => text.clone(),
+        ChatMessageContent::ContentPart(parts) => parts
+            .iter()
+            .filter_map(|part| match part {
+                ChatMessageContentPart::Text(part) => Some(part.text.as_str()),
+                _ => None,
+            })
+            .collect(),
+        ChatMessageContent::None => String::new(),
+    }
+}
+
 impl DeltaFunction {
     pub fn merge(&mut self, other: &Self) {
         if self.name.is_none() && other.name.is_some() {
@@ -754,8 +1103,38 @@ mod tests {
         ChatCompletionResponseFormat, ChatCompletionToolChoice, ChatCompletionToolChoiceFunction,
         ChatCompletionToolChoiceFunctionName, ChatCompletionToolType, ChatMessage,
         ChatMessageContent, ChatMessageContentPart, ChatMessageTextContentPart, JsonSchemaBuilder,
+        ListChatCompletionsParameters, ListChatCompletionsParametersBuilder,
     };
     use serde_json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_list_chat_completions_parameters_metadata_filter() {
+        let parameters = ListChatCompletionsParametersBuilder::default()
+            .model("gpt-4o")
+            .metadata(HashMap::from([(
+                "pipeline".to_string(),
+                "distillation".to_string(),
+            )]))
+            .limit(10u32)
+            .build()
+            .unwrap();
+
+        let query = serde_html_form::to_string(&parameters).unwrap();
+        assert_eq!(
+            query,
+            "model=gpt-4o&metadata%5Bpipeline%5D=distillation&limit=10"
+        );
+
+        let deserialized: ListChatCompletionsParameters =
+            serde_json::from_value(serde_json::json!({
+                "model": "gpt-4o",
+                "metadata[pipeline]": "distillation",
+                "limit": 10
+            }))
+            .unwrap();
+        assert_eq!(deserialized, parameters);
+    }
 
     #[test]
     fn test_chat_completion_response_format_serialization_deserialization() {
@@ -863,4 +1242,62 @@ mod tests {
         let deserialized: ChatMessage = serde_json::from_str(serialized.as_str()).unwrap();
         assert_eq!(deserialized, tool_message)
     }
+
+    #[cfg(feature = "stream")]
+    #[test]
+    fn test_chat_completion_accumulator_rebuilds_response() {
+        use crate::v1::resources::chat::{
+            ChatCompletionAccumulator, ChatCompletionChunkResponse, Function, ToolCall,
+        };
+        use crate::v1::resources::shared::FinishReason;
+
+        let chunks = [
+            r#"{"id":"chatcmpl-1","object":"chat.completion.chunk","created":1,"model":"gpt-4o","choices":[{"index":0,"delta":{"role":"assistant","content":"Hel"},"finish_reason":null},{"index":1,"delta":{"role":"assistant","content":null,"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"get_weather","arguments":""}}]},"finish_reason":null}]}"#,
+            r#"{"id":"chatcmpl-1","object":"chat.completion.chunk","created":1,"model":"gpt-4o","choices":[{"index":0,"delta":{"content":"lo"},"finish_reason":null},{"index":1,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"city\":"}}]},"finish_reason":null}]}"#,
+            r#"{"id":"chatcmpl-1","object":"chat.completion.chunk","created":1,"model":"gpt-4o","choices":[{"index":0,"delta":{},"finish_reason":"stop"},{"index":1,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"Paris\"}"}}]},"finish_reason":"tool_calls"}]}"#,
+            r#"{"id":"chatcmpl-1","object":"chat.completion.chunk","created":1,"model":"gpt-4o","service_tier":"default","system_fingerprint":"fp_1","choices":[],"usage":{"prompt_tokens":10,"completion_tokens":5,"total_tokens":15}}"#,
+        ];
+
+        let mut accumulator = ChatCompletionAccumulator::new();
+        for chunk in chunks {
+            let chunk: ChatCompletionChunkResponse = serde_json::from_str(chunk).unwrap();
+            accumulator.push(&chunk);
+        }
+
+        let response = accumulator.response();
+
+        assert_eq!(response.id.as_deref(), Some("chatcmpl-1"));
+        assert_eq!(response.service_tier.as_deref(), Some("default"));
+        assert_eq!(response.system_fingerprint.as_deref(), Some("fp_1"));
+        assert_eq!(response.usage.unwrap().total_tokens, 15);
+        assert_eq!(response.choices.len(), 2);
+        assert_eq!(response.choices[0].message.text(), Some("Hello"));
+        assert_eq!(
+            response.choices[0].finish_reason,
+            Some(FinishReason::StopSequenceReached)
+        );
+
+        let ChatMessage::Assistant {
+            tool_calls: Some(tool_calls),
+            ..
+        } = &response.choices[1].message
+        else {
+            panic!("expected an assistant message with tool calls");
+        };
+        assert_eq!(
+            tool_calls[0],
+            ToolCall {
+                id: "call_1".to_string(),
+                r#type: "function".to_string(),
+                function: Function {
+                    name: "get_weather".to_string(),
+                    arguments: "{\"city\":\"Paris\"}".to_string(),
+                },
+            }
+        );
+        assert_eq!(
+            response.choices[1].finish_reason,
+            Some(FinishReason::ToolCalls)
+        );
+    }
 }
@@ -0,0 +1,118 @@
+use crate::v1::resources::response::request::{ResponseInclude, ResponseInputItem};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConversationObject {
+    /// The unique ID of the conversation.
+    pub id: String,
+    /// The object type, which is always "conversation".
+    pub object: String,
+    /// The Unix timestamp (in seconds) for when the conversation was created.
+    pub created_at: u32,
+    /// Set of 16 key-value pairs that can be attached to an object.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Builder, Clone, PartialEq)]
+#[builder(name = "CreateConversationParametersBuilder")]
+#[builder(setter(into, strip_option), default)]
+pub struct CreateConversationParameters {
+    /// Initial items to include in the conversation context. You may add up to 20 items at a time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<ResponseInputItem>>,
+    /// Set of 16 key-value pairs that can be attached to an object.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Builder, Clone, PartialEq)]
+#[builder(name = "UpdateConversationParametersBuilder")]
+#[builder(setter(into, strip_option), default)]
+pub struct UpdateConversationParameters {
+    /// Set of 16 key-value pairs that replace the metadata of the conversation.
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Builder, Clone, PartialEq)]
+#[builder(name = "CreateConversationItemsParametersBuilder")]
+#[builder(setter(into, strip_option), default)]
+pub struct CreateConversationItemsParameters {
+    /// The items to add to the conversation. You may add up to 20 items at a time.
+    pub items: Vec<ResponseInputItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Builder, Clone, PartialEq)]
+#[builder(name = "ListConversationItemsParametersBuilder")]
+#[builder(setter(into, strip_option), default)]
+pub struct ListConversationItemsParameters {
+    /// An item ID to list items after, used in pagination.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    /// A limit on the number of objects to be returned. Limit can range between 1 and 100, and the default is 20.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// The order to return the items in, asc or desc. Default is desc.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+    /// Additional fields to include in the response.
+    #[serde(rename = "include[]", skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<ResponseInclude>>,
+}
+
+/// The `include[]` query parameters of the conversation item endpoints.
+#[cfg(feature = "reqwest")]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub(crate) struct IncludeParameters {
+    #[serde(rename = "include[]", skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<ResponseInclude>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::resources::conversation::ConversationObject;
+    use crate::v1::resources::response::request::{ResponseInputItem, ResponseParametersBuilder};
+    use crate::v1::resources::response::shared::ResponseConversation;
+    use crate::v1::resources::shared::ListResponse;
+    use serde_json::json;
+
+    #[test]
+    fn test_conversation_items_deserialization() {
+        let conversation: ConversationObject = serde_json::from_value(json!({
+            "id": "conv_123",
+            "object": "conversation",
+            "created_at": 1741900000,
+            "metadata": {"topic": "demo"}
+        }))
+        .unwrap();
+        assert_eq!(conversation.metadata.unwrap()["topic"], "demo");
+
+        let items: ListResponse<ResponseInputItem> = serde_json::from_value(json!({
+            "object": "list",
+            "data": [{
+                "type": "message",
+                "id": "msg_abc",
+                "status": "completed",
+                "role": "user",
+                "content": [{"type": "input_text", "text": "Hello!"}]
+            }],
+            "first_id": "msg_abc",
+            "last_id": "msg_abc",
+            "has_more": false
+        }))
+        .unwrap();
+        assert!(matches!(items.data[0], ResponseInputItem::Message(_)));
+
+        let parameters = ResponseParametersBuilder::default()
+            .model("gpt-4o")
+            .conversation(ResponseConversation::Id("conv_123".to_string()))
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&parameters).unwrap()["conversation"],
+            "conv_123"
+        );
+    }
+}
@@ -19,10 +19,19 @@ pub struct File {
     pub purpose: FilePurpose,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct ListFilesParameters {
     /// Only return files with the given purpose.
     pub purpose: Option<FilePurpose>,
+    /// A limit on the number of objects to be returned. Limit can range between 1 and 10,000, and the default is 10,000.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// Sort order by the created_at timestamp of the objects. asc for ascending order and desc for descending order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+    /// A cursor for use in pagination. after is an object ID that defines your place in the list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Builder, Clone, PartialEq)]
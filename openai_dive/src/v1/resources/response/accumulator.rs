@@ -0,0 +1,576 @@
+use super::items::{ReasoningContent, ReasoningSummary, ReasoningSummaryPart};
+use super::response::{
+    OutputContent, ResponseError, ResponseObject, ResponseOutput, ResponseStreamEvent,
+};
+use crate::v1::error::APIError;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+/// Rebuilds a `ResponseObject` from the events of a streamed response.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ResponseAccumulator {
+    response: Option<ResponseObject>,
+    output: BTreeMap<usize, ResponseOutput>,
+    error: Option<ResponseError>,
+    sequence_number: Option<u32>,
+    missed_events: Vec<RangeInclusive<u32>>,
+    completed: bool,
+}
+
+impl ResponseAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies an event to the accumulated response.
+    ///
+    /// Returns a `StreamError` without applying the event when its sequence number does not follow the previous event.
+    /// When events were missed, the gap is recorded in `missed_events` and a `StreamError` is returned, but the event
+    /// is applied, so the events after it are accumulated again.
+    pub fn push(&mut self, event: &ResponseStreamEvent) -> Result<(), APIError> {
+        let sequence_number = event.sequence_number();
+
+        let mut missed = None;
+        if let Some(previous) = self.sequence_number {
+            if sequence_number <= previous {
+                return Err(APIError::StreamError(format!(
+                    "received event {sequence_number} out of order after event {previous}"
+                )));
+            }
+            if sequence_number != previous + 1 {
+                missed = Some(previous + 1..=sequence_number - 1);
+            }
+        }
+        self.sequence_number = Some(sequence_number);
+
+        self.apply(event);
+
+        if let Some(missed) = missed {
+            let error = APIError::StreamError(format!(
+                "missed events {} to {} before event {sequence_number}",
+                missed.start(),
+                missed.end()
+            ));
+            self.missed_events.push(missed);
+
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the response accumulated so far, or `None` before the response was created.
+    pub fn response(&self) -> Option<ResponseObject> {
+        let mut response = self.response.clone()?;
+
+        if !self.output.is_empty() {
+            response.output = self.output.values().cloned().collect();
+        }
+        if response.error.is_none() {
+            response.error.clone_from(&self.error);
+        }
+
+        Some(response)
+    }
+
+    /// Returns the text of all output messages received so far.
+    pub fn output_text(&self) -> String {
+        self.output
+            .values()
+            .filter_map(|item| match item {
+                ResponseOutput::Message(message) => Some(&message.content),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|content| match content {
+                OutputContent::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the arguments of the function or MCP tool call at `output_index` received so far.
+    pub fn arguments(&self, output_index: usize) -> Option<&str> {
+        match self.output.get(&output_index)? {
+            ResponseOutput::FunctionToolCall(call) => Some(&call.arguments),
+            ResponseOutput::McpToolCall(call) => Some(&call.arguments),
+            _ => None,
+        }
+    }
+
+    /// Returns the sequence number of the last applied event.
+    pub fn sequence_number(&self) -> Option<u32> {
+        self.sequence_number
+    }
+
+    /// Returns the ranges of sequence numbers of the events that were missed.
+    pub fn missed_events(&self) -> &[RangeInclusive<u32>] {
+        &self.missed_events
+    }
+
+    /// Whether a `response.completed`, `response.failed` or `response.incomplete` event was received.
+    pub fn is_complete(&self) -> bool {
+        self.completed
+    }
+
+    fn apply(&mut self, event: &ResponseStreamEvent) {
+        match event {
+            ResponseStreamEvent::ResponseCreated { response, .. }
+            | ResponseStreamEvent::ResponseQueued { response, .. }
+            | ResponseStreamEvent::ResponseInProgress { response, .. } => {
+                self.set_response(response);
+            }
+            ResponseStreamEvent::ResponseCompleted { response, .. }
+            | ResponseStreamEvent::ResponseFailed { response, .. }
+            | ResponseStreamEvent::ResponseIncomplete { response, .. } => {
+                self.set_response(response);
+                self.completed = true;
+            }
+            ResponseStreamEvent::ResponseOutputItemAdded {
+                output_index, item, ..
+            }
+            | ResponseStreamEvent::ResponseOutputItemDone {
+                output_index, item, ..
+            } => {
+                self.output.insert(*output_index, item.clone());
+            }
+            ResponseStreamEvent::ResponseContentPartAdded {
+                output_index,
+                content_index,
+                part,
+                ..
+            }
+            | ResponseStreamEvent::ResponseContentPartDone {
+                output_index,
+                content_index,
+                part,
+                ..
+            } => {
+                if let Some(content) = self.message_content(*output_index, *content_index) {
+                    *content = part.clone();
+                }
+            }
+            ResponseStreamEvent::ResponseOutputTextDelta {
+                output_index,
+                content_index,
+                delta,
+                ..
+            } => {
+                if let Some(OutputContent::Text { text, .. }) =
+                    self.message_content(*output_index, *content_index)
+                {
+                    text.push_str(delta);
+                }
+            }
+            ResponseStreamEvent::ResponseOutputTextDone {
+                output_index,
+                content_index,
+                text: done,
+                ..
+            } => {
+                if let Some(OutputContent::Text { text, .. }) =
+                    self.message_content(*output_index, *content_index)
+                {
+                    text.clone_from(done);
+                }
+            }
+            ResponseStreamEvent::ResponseOutputTextAnnotationAdded {
+                output_index,
+                content_index,
+                annotation_index,
+                annotation,
+                ..
+            } => {
+                if let Some(OutputContent::Text { annotations, .. }) =
+                    self.message_content(*output_index, *content_index)
+                {
+                    match annotations.get_mut(*annotation_index) {
+                        Some(existing) => *existing = annotation.clone(),
+                        None => annotations.push(annotation.clone()),
+                    }
+                }
+            }
+            ResponseStreamEvent::ResponseRefusalDelta {
+                output_index,
+                content_index,
+                delta,
+                ..
+            } => {
+                if let Some(content) = self.message_content(*output_index, *content_index) {
+                    match content {
+                        OutputContent::Refusal { refusal } => refusal.push_str(delta),
+                        _ => {
+                            *content = OutputContent::Refusal {
+                                refusal: delta.clone(),
+                            }
+                        }
+                    }
+                }
+            }
+            ResponseStreamEvent::ResponseRefusalDone {
+                output_index,
+                content_index,
+                refusal,
+                ..
+            } => {
+                if let Some(content) = self.message_content(*output_index, *content_index) {
+                    *content = OutputContent::Refusal {
+                        refusal: refusal.clone(),
+                    };
+                }
+            }
+            ResponseStreamEvent::ResponseFunctionCallArgumentsDelta {
+                output_index,
+                delta,
+                ..
+            } => {
+                if let Some(ResponseOutput::FunctionToolCall(call)) =
+                    self.output.get_mut(output_index)
+                {
+                    call.arguments.push_str(delta);
+                }
+            }
+            ResponseStreamEvent::ResponseFunctionCallArgumentsDone {
+                output_index,
+                name,
+                arguments,
+                ..
+            } => {
+                if let Some(ResponseOutput::FunctionToolCall(call)) =
+                    self.output.get_mut(output_index)
+                {
+                    call.name.clone_from(name);
+                    call.arguments.clone_from(arguments);
+                }
+            }
+            ResponseStreamEvent::ResponseReasoningPartAdded {
+                output_index,
+                content_index,
+                part,
+                ..
+            }
+            | ResponseStreamEvent::ResponseReasoningPartDone {
+                output_index,
+                content_index,
+                part,
+                ..
+            } => {
+                if let (Some(text), OutputContent::ReasoningText { text: part }) =
+                    (self.reasoning_text(*output_index, *content_index), part)
+                {
+                    text.clone_from(part);
+                }
+            }
+            ResponseStreamEvent::ResponseReasoningTextDelta {
+                output_index,
+                content_index,
+                delta,
+                ..
+            } => {
+                if let Some(text) = self.reasoning_text(*output_index, *content_index) {
+                    text.push_str(delta);
+                }
+            }
+            ResponseStreamEvent::ResponseReasoningTextDone {
+                output_index,
+                content_index,
+                text: done,
+                ..
+            } => {
+                if let Some(text) = self.reasoning_text(*output_index, *content_index) {
+                    text.clone_from(done);
+                }
+            }
+            ResponseStreamEvent::ResponseReasoningSummaryPartAdded {
+                output_index,
+                summary_index,
+                part: ReasoningSummaryPart::SummaryText { text: part },
+                ..
+            }
+            | ResponseStreamEvent::ResponseReasoningSummaryPartDone {
+                output_index,
+                summary_index,
+                part: ReasoningSummaryPart::SummaryText { text: part },
+                ..
+            } => {
+                if let Some(text) = self.reasoning_summary(*output_index, *summary_index) {
+                    text.clone_from(part);
+                }
+            }
+            ResponseStreamEvent::ResponseReasoningSummaryTextDelta {
+                output_index,
+                summary_index,
+                delta,
+                ..
+            } => {
+                if let Some(text) = self.reasoning_summary(*output_index, *summary_index) {
+                    text.push_str(delta);
+                }
+            }
+            ResponseStreamEvent::ResponseReasoningSummaryTextDone {
+                output_index,
+                summary_index,
+                text: done,
+                ..
+            } => {
+                if let Some(text) = self.reasoning_summary(*output_index, *summary_index) {
+                    text.clone_from(done);
+                }
+            }
+            ResponseStreamEvent::ResponseImageGenerationCallPartialImage {
+                output_index,
+                partial_image_b64,
+                ..
+            } => {
+                if let Some(ResponseOutput::ImageGenerationCall(call)) =
+                    self.output.get_mut(output_index)
+                {
+                    call.result = Some(partial_image_b64.clone());
+                }
+            }
+            ResponseStreamEvent::ResponseMcpCallArgumentsDelta {
+                output_index,
+                delta,
+                ..
+            } => {
+                if let Some(ResponseOutput::McpToolCall(call)) = self.output.get_mut(output_index) {
+                    call.arguments.push_str(delta);
+                }
+            }
+            ResponseStreamEvent::ResponseMcpCallArgumentsDone {
+                output_index,
+                arguments,
+                ..
+            } => {
+                if let Some(ResponseOutput::McpToolCall(call)) = self.output.get_mut(output_index) {
+                    call.arguments.clone_from(arguments);
+                }
+            }
+            ResponseStreamEvent::ResponseCodeInterpreterCallCodeDelta {
+                output_index,
+                delta,
+                ..
+            } => {
+                if let Some(ResponseOutput::CodeInterpreterCall(call)) =
+                    self.output.get_mut(output_index)
+                {
+                    call.code.get_or_insert_with(String::new).push_str(delta);
+                }
+            }
+            ResponseStreamEvent::ResponseCodeInterpreterCallCodeDone {
+                output_index, code, ..
+            } => {
+                if let Some(ResponseOutput::CodeInterpreterCall(call)) =
+                    self.output.get_mut(output_index)
+                {
+                    call.code = Some(code.clone());
+                }
+            }
+            ResponseStreamEvent::ResponseCustomToolCallInputDelta {
+                output_index,
+                delta,
+                ..
+            } => {
+                if let Some(ResponseOutput::CustomToolCall(call)) =
+                    self.output.get_mut(output_index)
+                {
+                    call.input.push_str(delta);
+                }
+            }
+            ResponseStreamEvent::ResponseCustomToolCallInputDone {
+                output_index,
+                input,
+                ..
+            } => {
+                if let Some(ResponseOutput::CustomToolCall(call)) =
+                    self.output.get_mut(output_index)
+                {
+                    call.input.clone_from(input);
+                }
+            }
+            ResponseStreamEvent::Error { code, message, .. } => {
+                self.error = Some(ResponseError {
+                    code: code.clone(),
+                    message: message.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Replaces the response, keeping the output accumulated from the events when the response has none.
+    fn set_response(&mut self, response: &ResponseObject) {
+        if !response.output.is_empty() {
+            self.output = response.output.iter().cloned().enumerate().collect();
+        }
+
+        self.response = Some(response.clone());
+    }
+
+    fn message_content(
+        &mut self,
+        output_index: usize,
+        content_index: usize,
+    ) -> Option<&mut OutputContent> {
+        let Some(ResponseOutput::Message(message)) = self.output.get_mut(&output_index) else {
+            return None;
+        };
+
+        while message.content.len() <= content_index {
+            message.content.push(OutputContent::Text {
+                text: String::new(),
+                annotations: Vec::new(),
+            });
+        }
+
+        message.content.get_mut(content_index)
+    }
+
+    fn reasoning_text(&mut self, output_index: usize, content_index: usize) -> Option<&mut String> {
+        let Some(ResponseOutput::Reasoning(reasoning)) = self.output.get_mut(&output_index) else {
+            return None;
+        };
+
+        let content = reasoning.content.get_or_insert_with(Vec::new);
+        while content.len() <= content_index {
+            content.push(ReasoningContent::Text {
+                text: String::new(),
+            });
+        }
+
+        let ReasoningContent::Text { text } = &mut content[content_index];
+
+        Some(text)
+    }
+
+    fn reasoning_summary(
+        &mut self,
+        output_index: usize,
+        summary_index: usize,
+    ) -> Option<&mut String> {
+        let Some(ResponseOutput::Reasoning(reasoning)) = self.output.get_mut(&output_index) else {
+            return None;
+        };
+
+        let summary = reasoning.summary.get_or_insert_with(Vec::new);
+        while summary.len() <= summary_index {
+            summary.push(ReasoningSummary::Text {
+                text: String::new(),
+            });
+        }
+
+        let ReasoningSummary::Text { text } = &mut summary[summary_index];
+
+        Some(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::error::APIError;
+    use crate::v1::resources::response::accumulator::ResponseAccumulator;
+    use crate::v1::resources::response::response::{
+        OutputContent, ReasoningStatus, ResponseOutput, ResponseStreamEvent,
+    };
+    use serde_json::json;
+
+    fn response_json(status: &str, output: serde_json::Value) -> serde_json::Value {
+        json!({
+            "id": "resp_1",
+            "object": "response",
+            "created_at": 1,
+            "model": "gpt-4o",
+            "status": status,
+            "output": output,
+            "tools": []
+        })
+    }
+
+    fn parse_events(events: serde_json::Value) -> Vec<ResponseStreamEvent> {
+        serde_json::from_value(events).unwrap()
+    }
+
+    #[test]
+    fn test_response_accumulator_rebuilds_output() {
+        let events = parse_events(json!([
+            {"type": "response.created", "sequence_number": 0, "response": response_json("in_progress", json!([]))},
+            {"type": "response.output_item.added", "sequence_number": 1, "output_index": 0, "item": {"type": "message", "id": "msg_1", "role": "assistant", "status": "in_progress", "content": []}},
+            {"type": "response.content_part.added", "sequence_number": 2, "item_id": "msg_1", "output_index": 0, "content_index": 0, "part": {"type": "output_text", "text": "", "annotations": []}},
+            {"type": "response.output_text.delta", "sequence_number": 3, "item_id": "msg_1", "output_index": 0, "content_index": 0, "delta": "Hello"},
+            {"type": "response.output_text.delta", "sequence_number": 4, "item_id": "msg_1", "output_index": 0, "content_index": 0, "delta": " world"},
+            {"type": "response.output_text.annotation.added", "sequence_number": 5, "item_id": "msg_1", "output_index": 0, "content_index": 0, "annotation_index": 0, "annotation": {"type": "url_citation", "title": "Example", "url": "https://example.com", "start_index": 0, "end_index": 5}},
+            {"type": "response.output_item.added", "sequence_number": 6, "output_index": 1, "item": {"type": "function_call", "id": "fc_1", "call_id": "call_1", "name": "get_weather", "arguments": ""}},
+            {"type": "response.function_call_arguments.delta", "sequence_number": 7, "item_id": "fc_1", "output_index": 1, "delta": "{\"city\":"},
+            {"type": "response.function_call_arguments.delta", "sequence_number": 8, "item_id": "fc_1", "output_index": 1, "delta": "\"Paris\"}"},
+        ]));
+
+        let mut accumulator = ResponseAccumulator::new();
+        for event in &events {
+            accumulator.push(event).unwrap();
+        }
+
+        assert_eq!(accumulator.output_text(), "Hello world");
+        assert!(!accumulator.is_complete());
+
+        let response = accumulator.response().unwrap();
+        let ResponseOutput::Message(message) = &response.output[0] else {
+            panic!("expected a message");
+        };
+        let OutputContent::Text { annotations, .. } = &message.content[0] else {
+            panic!("expected output text");
+        };
+        assert_eq!(annotations.len(), 1);
+
+        let ResponseOutput::FunctionToolCall(call) = &response.output[1] else {
+            panic!("expected a function call");
+        };
+        assert_eq!(call.arguments, "{\"city\":\"Paris\"}");
+
+        let completed = parse_events(json!([
+            {"type": "response.completed", "sequence_number": 9, "response": response_json("completed", json!([]))},
+        ]));
+        accumulator.push(&completed[0]).unwrap();
+
+        let response = accumulator.response().unwrap();
+        assert!(accumulator.is_complete());
+        assert_eq!(response.status, ReasoningStatus::Completed);
+        assert_eq!(response.output.len(), 2);
+    }
+
+    #[test]
+    fn test_response_accumulator_detects_sequence_gaps() {
+        let events = parse_events(json!([
+            {"type": "response.created", "sequence_number": 0, "response": response_json("in_progress", json!([]))},
+            {"type": "keepalive", "sequence_number": 1},
+            {"type": "keepalive", "sequence_number": 1},
+            {"type": "response.output_item.added", "sequence_number": 4, "output_index": 0, "item": {"type": "message", "id": "msg_1", "role": "assistant", "status": "in_progress", "content": []}},
+            {"type": "response.content_part.added", "sequence_number": 5, "item_id": "msg_1", "output_index": 0, "content_index": 0, "part": {"type": "output_text", "text": "", "annotations": []}},
+            {"type": "response.output_text.delta", "sequence_number": 6, "item_id": "msg_1", "output_index": 0, "content_index": 0, "delta": "Hello"},
+            {"type": "keepalive", "sequence_number": 5},
+        ]));
+
+        let mut accumulator = ResponseAccumulator::new();
+        accumulator.push(&events[0]).unwrap();
+        accumulator.push(&events[1]).unwrap();
+        assert!(matches!(
+            accumulator.push(&events[2]),
+            Err(APIError::StreamError(_))
+        ));
+
+        // The events after a gap are accumulated again
+        assert!(matches!(
+            accumulator.push(&events[3]),
+            Err(APIError::StreamError(message)) if message == "missed events 2 to 3 before event 4"
+        ));
+        accumulator.push(&events[4]).unwrap();
+        accumulator.push(&events[5]).unwrap();
+        assert!(matches!(
+            accumulator.push(&events[6]),
+            Err(APIError::StreamError(_))
+        ));
+
+        assert_eq!(accumulator.sequence_number(), Some(6));
+        assert_eq!(accumulator.missed_events(), [2..=3]);
+        assert_eq!(accumulator.output_text(), "Hello");
+    }
+}
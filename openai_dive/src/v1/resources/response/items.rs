@@ -156,6 +156,106 @@ pub enum LocalShellAction {
     },
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LocalShellCallOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub call_id: String,
+    pub output: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<InputItemStatus>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShellCall {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub call_id: String,
+    pub action: ShellAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<InputItemStatus>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShellAction {
+    /// The shell commands to run, in order.
+    pub commands: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_length: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShellCallOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub call_id: String,
+    /// The output of each command, in the order of the commands.
+    pub output: Vec<ShellCommandOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_length: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShellCommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub outcome: ShellOutcome,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ShellOutcome {
+    Exit { exit_code: i32 },
+    Timeout,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApplyPatchCall {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub call_id: String,
+    pub operation: ApplyPatchOperation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<InputItemStatus>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApplyPatchOperation {
+    /// Creates a file, the diff contains its lines prefixed with `+`.
+    CreateFile {
+        path: String,
+        diff: String,
+    },
+    /// Updates a file with a V4A diff.
+    UpdateFile {
+        path: String,
+        diff: String,
+    },
+    DeleteFile {
+        path: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApplyPatchCallOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub call_id: String,
+    pub status: ApplyPatchStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplyPatchStatus {
+    Completed,
+    Failed,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct McpToolCall {
     pub id: String,
@@ -209,6 +309,17 @@ pub struct McpApprovalRequest {
     pub arguments: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct McpApprovalResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The ID of the approval request being answered.
+    pub approval_request_id: String,
+    pub approve: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct CustomToolCall {
     pub id: String,
@@ -225,13 +336,14 @@ pub struct ComputerToolCall {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub call_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub pending_safety_checks: Option<Vec<String>>,
+    pub pending_safety_checks: Option<Vec<PendingSafetyCheck>>,
     pub status: InputItemStatus,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ComputerToolCallOutput {
-    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     pub call_id: String,
     pub output: ComputerToolCallOutputOutput,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -239,6 +351,25 @@ pub struct ComputerToolCallOutput {
     pub status: InputItemStatus,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PendingSafetyCheck {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl From<PendingSafetyCheck> for AcknowledgedSafetyCheck {
+    fn from(check: PendingSafetyCheck) -> Self {
+        Self {
+            id: check.id,
+            code: check.code,
+            message: check.message,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct AcknowledgedSafetyCheck {
     pub id: String,
@@ -249,6 +380,7 @@ pub struct AcknowledgedSafetyCheck {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename = "computer_screenshot")]
 pub struct ComputerToolCallOutputOutput {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file_id: Option<String>,
@@ -1,3 +1,5 @@
+#[cfg(feature = "stream")]
+pub mod accumulator;
 pub mod items;
 pub mod request;
 #[allow(clippy::module_inception)]
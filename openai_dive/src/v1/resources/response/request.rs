@@ -7,11 +7,16 @@ use std::collections::HashMap;
 
 use super::{
     items::{
-        ComputerToolCall, FileSearchToolCall, FunctionToolCall, FunctionToolCallOutput, Reasoning,
-        WebSearchToolCall,
+        ApplyPatchCall, ApplyPatchCallOutput, ComputerToolCall, FileSearchToolCall,
+        FunctionToolCall, FunctionToolCallOutput, LocalShellCall, LocalShellCallOutput,
+        McpApprovalRequest, McpApprovalResponse, McpListTools, McpToolCall, Reasoning, ShellCall,
+        ShellCallOutput, WebSearchToolCall,
     },
     response::{ResponseReasoning, ResponseText, Role},
-    shared::{ResponseTool, ResponseToolChoice, TruncationStrategy, WebSearchUserLocation},
+    shared::{
+        ResponseConversation, ResponseTool, ResponseToolChoice, TruncationStrategy,
+        WebSearchUserLocation,
+    },
 };
 
 #[derive(Serialize, Deserialize, Debug, Default, Builder, Clone, PartialEq)]
@@ -25,6 +30,10 @@ pub struct ResponseParameters {
     /// Whether to run the model response in the background.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub background: Option<bool>,
+    /// The conversation that this response belongs to. Its items are prepended to the input, and the input and
+    /// output of this response are added to it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversation: Option<ResponseConversation>,
     /// Reference to a prompt template and its variables.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prompt: Option<Prompt>,
@@ -84,6 +93,82 @@ pub struct ResponseParameters {
     pub service_tier: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Default, Builder, Clone, PartialEq)]
+#[builder(name = "ListInputItemsParametersBuilder")]
+#[builder(setter(into, strip_option), default)]
+pub struct ListInputItemsParameters {
+    /// An item ID to list items after, used in pagination.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    /// A limit on the number of objects to be returned. Limit can range between 1 and 100, and the default is 20.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// The order to return the input items in, asc or desc. Default is desc.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+    /// Additional fields to include in the response.
+    #[serde(rename = "include[]", skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<ResponseInclude>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Builder, Clone, PartialEq)]
+#[builder(name = "InputTokensParametersBuilder")]
+#[builder(setter(into, strip_option), default)]
+pub struct InputTokensParameters {
+    /// Model ID used to generate the response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Text, image, or file inputs to the model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<ResponseInput>,
+    /// Inserts a system (or developer) message as the first item in the model's context.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    /// The unique ID of the previous response to the model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_response_id: Option<String>,
+    /// The conversation whose items are prepended to the input.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversation: Option<ResponseConversation>,
+    /// Configuration options for reasoning models.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<ResponseReasoning>,
+    /// Configuration options for a text response from the model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<ResponseText>,
+    /// How the model should select which tool (or tools) to use when generating a response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ResponseToolChoice>,
+    /// An array of tools the model may call while generating a response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<ResponseTool>>,
+    /// Whether to allow the model to run tool calls in parallel.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+    /// The truncation strategy to use for the model response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncation: Option<TruncationStrategy>,
+}
+
+/// Counts the input tokens of the request that would be sent with these parameters.
+impl From<ResponseParameters> for InputTokensParameters {
+    fn from(parameters: ResponseParameters) -> Self {
+        Self {
+            model: Some(parameters.model),
+            input: Some(parameters.input),
+            instructions: parameters.instructions,
+            previous_response_id: parameters.previous_response_id,
+            conversation: parameters.conversation,
+            reasoning: parameters.reasoning,
+            text: parameters.text,
+            tool_choice: parameters.tool_choice,
+            tools: parameters.tools,
+            parallel_tool_calls: parameters.parallel_tool_calls,
+            truncation: parameters.truncation,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct InputMessage {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -174,6 +259,26 @@ pub enum ResponseInputItem {
     FunctionToolCallOutput(FunctionToolCallOutput),
     #[serde(rename = "reasoning")]
     Reasoning(Reasoning),
+    #[serde(rename = "local_shell_call")]
+    LocalShellCall(LocalShellCall),
+    #[serde(rename = "local_shell_call_output")]
+    LocalShellCallOutput(LocalShellCallOutput),
+    #[serde(rename = "shell_call")]
+    ShellCall(ShellCall),
+    #[serde(rename = "shell_call_output")]
+    ShellCallOutput(ShellCallOutput),
+    #[serde(rename = "apply_patch_call")]
+    ApplyPatchCall(ApplyPatchCall),
+    #[serde(rename = "apply_patch_call_output")]
+    ApplyPatchCallOutput(ApplyPatchCallOutput),
+    #[serde(rename = "mcp_list_tools")]
+    McpListTools(McpListTools),
+    #[serde(rename = "mcp_call")]
+    McpToolCall(McpToolCall),
+    #[serde(rename = "mcp_approval_request")]
+    McpApprovalRequest(McpApprovalRequest),
+    #[serde(rename = "mcp_approval_response")]
+    McpApprovalResponse(McpApprovalResponse),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -192,6 +297,8 @@ pub enum ResponseInclude {
     ComputerCallOutputOutputImageUrls,
     #[serde(rename = "message.output_text.logprobs")]
     MessageOutputTextLogprobs,
+    #[serde(rename = "reasoning.encrypted_content")]
+    ReasoningEncryptedContent,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -201,3 +308,27 @@ pub enum ImageDetailLevel {
     Low,
     Auto,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::resources::response::request::{
+        ListInputItemsParametersBuilder, ResponseInclude,
+    };
+
+    #[test]
+    fn test_list_input_items_parameters_query() {
+        let parameters = ListInputItemsParametersBuilder::default()
+            .limit(50u32)
+            .include(vec![
+                ResponseInclude::FileSearchCallResults,
+                ResponseInclude::MessageInputImageUrls,
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_html_form::to_string(&parameters).unwrap(),
+            "limit=50&include%5B%5D=file_search_call.results&include%5B%5D=message.input_image.image_url"
+        );
+    }
+}
@@ -2,13 +2,13 @@
 use super::items::{LogProbs, ReasoningSummaryPart};
 use super::{
     items::{
-        CodeInterpreterCall, ComputerToolCall, CustomToolCall, FileSearchToolCall,
+        ApplyPatchCall, CodeInterpreterCall, ComputerToolCall, CustomToolCall, FileSearchToolCall,
         FunctionToolCall, ImageGenerationCall, LocalShellCall, McpApprovalRequest, McpListTools,
-        McpToolCall, Reasoning, WebSearchToolCall,
+        McpToolCall, Reasoning, ShellCall, WebSearchToolCall,
     },
     shared::{
-        Annotation, ResponseFormat, ResponseTool, ResponseToolChoice, ResponseUsage,
-        TruncationStrategy,
+        Annotation, ResponseConversation, ResponseFormat, ResponseTool, ResponseToolChoice,
+        ResponseUsage, TruncationStrategy,
     },
 };
 use crate::v1::resources::shared::ReasoningEffort;
@@ -45,6 +45,9 @@ pub struct ResponseObject {
     /// Whether to allow the model to run tool calls in parallel.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parallel_tool_calls: Option<bool>,
+    /// The conversation that this response belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation: Option<ResponseConversation>,
     /// The unique ID of the previous response to the model.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub previous_response_id: Option<String>,
@@ -81,6 +84,14 @@ pub struct ResponseObject {
     pub user: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InputTokens {
+    /// The object type, which is always response.input_tokens.
+    pub object: String,
+    /// The number of input tokens.
+    pub input_tokens: u32,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ResponseError {
     /// The error code for the response.
@@ -114,6 +125,10 @@ pub enum ResponseOutput {
     CodeInterpreterCall(CodeInterpreterCall),
     #[serde(rename = "local_shell_call")]
     LocalShellCall(LocalShellCall),
+    #[serde(rename = "shell_call")]
+    ShellCall(ShellCall),
+    #[serde(rename = "apply_patch_call")]
+    ApplyPatchCall(ApplyPatchCall),
     #[serde(rename = "mcp_call")]
     McpToolCall(McpToolCall),
     #[serde(rename = "mcp_list_tools")]
@@ -162,6 +177,33 @@ pub enum ReasoningStatus {
     Failed,
     InProgress,
     Incomplete,
+    Queued,
+    Cancelled,
+}
+
+impl ResponseObject {
+    /// The tools listed by the MCP servers in the output, see `McpToolCache` to keep them between requests.
+    pub fn mcp_list_tools(&self) -> impl Iterator<Item = &McpListTools> {
+        self.output.iter().filter_map(|item| match item {
+            ResponseOutput::McpListTools(list) => Some(list),
+            _ => None,
+        })
+    }
+
+    /// The MCP tool calls in the output that wait for approval.
+    pub fn mcp_approval_requests(&self) -> impl Iterator<Item = &McpApprovalRequest> {
+        self.output.iter().filter_map(|item| match item {
+            ResponseOutput::McpApprovalRequest(request) => Some(request),
+            _ => None,
+        })
+    }
+}
+
+impl ReasoningStatus {
+    /// Whether the response has finished and its status will not change anymore.
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, ReasoningStatus::InProgress | ReasoningStatus::Queued)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -726,4 +768,166 @@ impl ResponseStreamEvent {
             Self::Error { .. } => "error",
         }
     }
+
+    /// Get the sequence number of this event
+    pub fn sequence_number(&self) -> u32 {
+        match self {
+            Self::ResponseCreated {
+                sequence_number, ..
+            }
+            | Self::ResponseQueued {
+                sequence_number, ..
+            }
+            | Self::ResponseInProgress {
+                sequence_number, ..
+            }
+            | Self::ResponseCompleted {
+                sequence_number, ..
+            }
+            | Self::ResponseFailed {
+                sequence_number, ..
+            }
+            | Self::ResponseIncomplete {
+                sequence_number, ..
+            }
+            | Self::ResponseOutputItemAdded {
+                sequence_number, ..
+            }
+            | Self::ResponseOutputItemDone {
+                sequence_number, ..
+            }
+            | Self::ResponseContentPartAdded {
+                sequence_number, ..
+            }
+            | Self::ResponseContentPartDone {
+                sequence_number, ..
+            }
+            | Self::ResponseReasoningPartAdded {
+                sequence_number, ..
+            }
+            | Self::ResponseReasoningPartDone {
+                sequence_number, ..
+            }
+            | Self::ResponseOutputTextDelta {
+                sequence_number, ..
+            }
+            | Self::ResponseOutputTextDone {
+                sequence_number, ..
+            }
+            | Self::ResponseOutputTextAnnotationAdded {
+                sequence_number, ..
+            }
+            | Self::ResponseRefusalDelta {
+                sequence_number, ..
+            }
+            | Self::ResponseRefusalDone {
+                sequence_number, ..
+            }
+            | Self::ResponseFunctionCallArgumentsDelta {
+                sequence_number, ..
+            }
+            | Self::ResponseFunctionCallArgumentsDone {
+                sequence_number, ..
+            }
+            | Self::ResponseFileSearchCallInProgress {
+                sequence_number, ..
+            }
+            | Self::ResponseFileSearchCallSearching {
+                sequence_number, ..
+            }
+            | Self::ResponseFileSearchCallCompleted {
+                sequence_number, ..
+            }
+            | Self::ResponseWebSearchCallInProgress {
+                sequence_number, ..
+            }
+            | Self::ResponseWebSearchCallSearching {
+                sequence_number, ..
+            }
+            | Self::ResponseWebSearchCallCompleted {
+                sequence_number, ..
+            }
+            | Self::ResponseReasoningTextDelta {
+                sequence_number, ..
+            }
+            | Self::ResponseReasoningTextDone {
+                sequence_number, ..
+            }
+            | Self::ResponseReasoningSummaryPartAdded {
+                sequence_number, ..
+            }
+            | Self::ResponseReasoningSummaryPartDone {
+                sequence_number, ..
+            }
+            | Self::ResponseReasoningSummaryTextDelta {
+                sequence_number, ..
+            }
+            | Self::ResponseReasoningSummaryTextDone {
+                sequence_number, ..
+            }
+            | Self::ResponseImageGenerationCallInProgress {
+                sequence_number, ..
+            }
+            | Self::ResponseImageGenerationCallGenerating {
+                sequence_number, ..
+            }
+            | Self::ResponseImageGenerationCallPartialImage {
+                sequence_number, ..
+            }
+            | Self::ResponseImageGenerationCallCompleted {
+                sequence_number, ..
+            }
+            | Self::ResponseMcpCallInProgress {
+                sequence_number, ..
+            }
+            | Self::ResponseMcpCallCompleted {
+                sequence_number, ..
+            }
+            | Self::ResponseMcpCallFailed {
+                sequence_number, ..
+            }
+            | Self::ResponseMcpCallArgumentsDelta {
+                sequence_number, ..
+            }
+            | Self::ResponseMcpCallArgumentsDone {
+                sequence_number, ..
+            }
+            | Self::ResponseMcpListToolsInProgress {
+                sequence_number, ..
+            }
+            | Self::ResponseMcpListToolsCompleted {
+                sequence_number, ..
+            }
+            | Self::ResponseMcpListToolsFailed {
+                sequence_number, ..
+            }
+            | Self::ResponseCodeInterpreterCallInProgress {
+                sequence_number, ..
+            }
+            | Self::ResponseCodeInterpreterCallInterpreting {
+                sequence_number, ..
+            }
+            | Self::ResponseCodeInterpreterCallCompleted {
+                sequence_number, ..
+            }
+            | Self::ResponseCodeInterpreterCallCodeDelta {
+                sequence_number, ..
+            }
+            | Self::ResponseCodeInterpreterCallCodeDone {
+                sequence_number, ..
+            }
+            | Self::ResponseCustomToolCallInputDelta {
+                sequence_number, ..
+            }
+            | Self::ResponseCustomToolCallInputDone {
+                sequence_number, ..
+            }
+            | Self::Keepalive {
+                sequence_number, ..
+            }
+            | Self::Error {
+                sequence_number, ..
+            } => *sequence_number,
+        }
+    }
 }
@@ -127,6 +127,14 @@ pub enum ResponseTool {
     },
 }
 
+/// The conversation a response belongs to, either its ID or an object with its ID.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ResponseConversation {
+    Id(String),
+    Object { id: String },
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct InputImageMask {
     pub image_url: Option<String>,
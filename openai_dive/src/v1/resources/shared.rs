@@ -1,8 +1,10 @@
-#[cfg(feature = "reqwest")]
 use crate::v1::error::APIError;
 use bytes::Bytes;
 #[cfg(feature = "reqwest")]
-use reqwest::{header::HeaderMap, multipart::Part};
+use reqwest::{
+    header::HeaderMap,
+    multipart::{Form, Part},
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -65,7 +67,7 @@ pub struct ResponseWrapper<T> {
     pub headers: Headers,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct Headers {
     /// The maximum number of requests that are permitted before exhausting the rate limit.
     #[serde(rename = "x-ratelimit-limit-requests")]
@@ -87,7 +89,7 @@ pub struct Headers {
     pub x_ratelimit_reset_tokens: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct SimpleListParameters {
     /// Identifier for the last object from the previous pagination request.
     pub after: Option<String>,
@@ -95,7 +97,7 @@ pub struct SimpleListParameters {
     pub limit: Option<u32>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct ListParameters {
     /// A limit on the number of objects to be returned. Limit can range between 1 and 100, and the default is 20.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -111,6 +113,14 @@ pub struct ListParameters {
     pub before: Option<String>,
 }
 
+/// The sort order of a list by the creation timestamp of the objects.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ListResponse<T> {
     // The object type, which is always "list".
@@ -123,6 +133,9 @@ pub struct ListResponse<T> {
     pub last_id: Option<String>,
     /// Indicates whether there are more objects to retrieve.
     pub has_more: bool,
+    /// The cursor for the next page, used by the usage endpoints instead of `last_id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_page: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -300,8 +313,8 @@ impl FileUploadBytes {
     #[cfg(feature = "reqwest")]
     pub(crate) fn into_part(self) -> Result<Part, APIError> {
         reqwest::multipart::Part::bytes(self.bytes.to_vec())
-            .file_name(self.filename.clone())
-            .mime_str("application/octet-stream")
+            .file_name(encode_line_breaks(&self.filename))
+            .mime_str(DEFAULT_FILE_MIME_TYPE)
             .map_err(|error| APIError::FileError(error.to_string()))
     }
 }
@@ -335,11 +348,10 @@ impl FileUpload {
                 let stream = FramedRead::new(file, BytesCodec::new());
                 let file_body = reqwest::Body::wrap_stream(stream);
 
-                let file_part = reqwest::multipart::Part::stream(file_body).file_name(path);
-                // .mime_str("application/octet-stream")
-                // .unwrap();
-
-                Ok(file_part)
+                reqwest::multipart::Part::stream(file_body)
+                    .file_name(encode_line_breaks(&path))
+                    .mime_str(DEFAULT_FILE_MIME_TYPE)
+                    .map_err(|error| APIError::FileError(error.to_string()))
             }
             #[cfg(all(feature = "tokio", feature = "tokio-util"))]
             FileUpload::FileArray(_) => {
@@ -347,63 +359,217 @@ impl FileUpload {
             }
         }
     }
+}
+impl Default for FileUpload {
+    fn default() -> Self {
+        Self::Bytes(FileUploadBytes::new(Bytes::new(), ""))
+    }
+}
 
-    #[cfg(feature = "reqwest")]
-    pub(crate) async fn into_parts(self) -> Result<Vec<Part>, APIError> {
-        match self {
-            FileUpload::Bytes(bytes) => bytes.into_part().map(|part| vec![part]),
-            FileUpload::BytesArray(bytes) => bytes
-                .into_iter()
-                .map(|bytes| bytes.into_part())
-                .collect::<Result<Vec<Part>, APIError>>(),
-            #[cfg(all(feature = "tokio", feature = "tokio-util"))]
-            FileUpload::File(path) => {
-                use tokio::fs::File;
-                use tokio_util::codec::{BytesCodec, FramedRead};
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MultipartForm {
+    /// The fields of the form, in the order they were added.
+    pub fields: Vec<MultipartField>,
+}
 
-                let file = File::open(&path)
-                    .await
-                    .map_err(|error| APIError::FileError(error.to_string()))?;
+#[derive(Clone, Debug, PartialEq)]
+pub enum MultipartField {
+    Text {
+        name: String,
+        value: String,
+    },
+    File {
+        name: String,
+        file: FileUpload,
+        /// Overrides the MIME type of the file part.
+        mime_type: Option<String>,
+    },
+}
 
-                let stream = FramedRead::new(file, BytesCodec::new());
-                let file_body = reqwest::Body::wrap_stream(stream);
+impl MultipartForm {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-                let file_part = reqwest::multipart::Part::stream(file_body)
-                    .file_name(path)
-                    .mime_str("application/octet-stream")
-                    .unwrap();
+    /// Adds a text field to the form.
+    pub fn text(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push(MultipartField::Text {
+            name: name.into(),
+            value: value.into(),
+        });
 
-                Ok(vec![file_part])
-            }
+        self
+    }
+
+    /// Adds a file field to the form. Arrays are added as one field per file using the same name.
+    pub fn file(self, name: impl Into<String>, file: FileUpload) -> Self {
+        self.file_with_mime_type(name, file, None)
+    }
+
+    /// Adds a file field to the form with an explicit MIME type.
+    pub fn file_with_mime_type(
+        mut self,
+        name: impl Into<String>,
+        file: FileUpload,
+        mime_type: Option<String>,
+    ) -> Self {
+        let name = name.into();
+
+        let files = match file {
+            FileUpload::BytesArray(bytes) => bytes.into_iter().map(FileUpload::Bytes).collect(),
             #[cfg(all(feature = "tokio", feature = "tokio-util"))]
-            FileUpload::FileArray(paths) => {
-                use tokio::fs::File;
-                use tokio_util::codec::{BytesCodec, FramedRead};
+            FileUpload::FileArray(paths) => paths.into_iter().map(FileUpload::File).collect(),
+            file => vec![file],
+        };
+
+        for file in files {
+            self.fields.push(MultipartField::File {
+                name: name.clone(),
+                file,
+                mime_type: mime_type.clone(),
+            });
+        }
 
-                let mut file_parts = vec![];
-                for path in paths {
-                    let file = File::open(&path)
-                        .await
-                        .map_err(|error| APIError::FileError(error.to_string()))?;
+        self
+    }
 
-                    let stream = FramedRead::new(file, BytesCodec::new());
-                    let file_body = reqwest::Body::wrap_stream(stream);
+    /// Encodes the form as a `multipart/form-data` body and returns it along with its content type.
+    pub async fn encode(&self) -> Result<(String, Bytes), APIError> {
+        let boundary = format!("openai-dive-{:016x}", random_u64());
+        let mut body = Vec::new();
 
-                    let file_part = reqwest::multipart::Part::stream(file_body)
-                        .file_name(path)
-                        .mime_str("application/octet-stream")
-                        .unwrap();
+        for field in &self.fields {
+            body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
 
-                    file_parts.push(file_part);
+            match field {
+                MultipartField::Text { name, value } => {
+                    body.extend_from_slice(content_disposition(name, None).as_bytes());
+                    body.extend_from_slice(b"\r\n\r\n");
+                    body.extend_from_slice(value.as_bytes());
                 }
+                MultipartField::File {
+                    name,
+                    file,
+                    mime_type,
+                } => {
+                    let (filename, bytes) = match file {
+                        FileUpload::Bytes(file) => (file.filename.clone(), file.bytes.clone()),
+                        #[cfg(all(feature = "tokio", feature = "tokio-util"))]
+                        FileUpload::File(path) => {
+                            let bytes = tokio::fs::read(path)
+                                .await
+                                .map_err(|error| APIError::FileError(error.to_string()))?;
+
+                            (path.clone(), Bytes::from(bytes))
+                        }
+                        _ => {
+                            return Err(APIError::FileError(
+                                "file arrays must be added with MultipartForm::file".to_string(),
+                            ))
+                        }
+                    };
+
+                    let mime_type = mime_type.as_deref().unwrap_or(DEFAULT_FILE_MIME_TYPE);
+
+                    body.extend_from_slice(content_disposition(name, Some(&filename)).as_bytes());
+                    body.extend_from_slice(
+                        format!("\r\nContent-Type: {mime_type}\r\n\r\n").as_bytes(),
+                    );
+                    body.extend_from_slice(&bytes);
+                }
+            }
 
-                Ok(file_parts)
+            body.extend_from_slice(b"\r\n");
+        }
+
+        body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+
+        Ok((
+            format!("multipart/form-data; boundary={boundary}"),
+            body.into(),
+        ))
+    }
+
+    /// Builds the reqwest form. Files on disk are (re)opened every time the form is built.
+    #[cfg(feature = "reqwest")]
+    pub(crate) async fn to_form(&self) -> Result<Form, APIError> {
+        let mut form = Form::new();
+
+        for field in &self.fields {
+            match field {
+                MultipartField::Text { name, value } => {
+                    form = form.text(name.clone(), value.clone());
+                }
+                MultipartField::File {
+                    name,
+                    file,
+                    mime_type,
+                } => {
+                    let mut part = file.clone().into_part().await?;
+
+                    if let Some(mime_type) = mime_type {
+                        part = part
+                            .mime_str(mime_type)
+                            .map_err(|error| APIError::FileError(error.to_string()))?;
+                    }
+
+                    form = form.part(name.clone(), part);
+                }
             }
         }
+
+        Ok(form)
     }
 }
-impl Default for FileUpload {
-    fn default() -> Self {
-        Self::Bytes(FileUploadBytes::new(Bytes::new(), ""))
+
+/// The MIME type of file parts without an explicit MIME type.
+const DEFAULT_FILE_MIME_TYPE: &str = "application/octet-stream";
+
+/// Formats the `Content-Disposition` header of a form field the way reqwest does: a name with special characters
+/// is percent-encoded and quotes in the filename are escaped. Line breaks in the filename are percent-encoded, so
+/// they can't end the header.
+fn content_disposition(name: &str, filename: Option<&str>) -> String {
+    const ENCODED: &[u8] = b" \"<>`#?{}/%";
+    let needs_encoding =
+        |byte: u8| byte.is_ascii_control() || !byte.is_ascii() || ENCODED.contains(&byte);
+
+    let mut header = if name.bytes().any(needs_encoding) {
+        let name: String = name
+            .bytes()
+            .map(|byte| {
+                if needs_encoding(byte) {
+                    format!("%{byte:02X}")
+                } else {
+                    char::from(byte).to_string()
+                }
+            })
+            .collect();
+
+        format!("Content-Disposition: form-data; name*=utf-8''{name}")
+    } else {
+        format!("Content-Disposition: form-data; name=\"{name}\"")
+    };
+
+    if let Some(filename) = filename {
+        let filename = encode_line_breaks(filename)
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"");
+
+        header.push_str(&format!("; filename=\"{filename}\""));
     }
+
+    header
+}
+
+/// Percent-encodes the line breaks of a filename, which reqwest would otherwise write into the header as-is.
+fn encode_line_breaks(filename: &str) -> String {
+    filename.replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Returns a random number, seeded by the standard library's hasher randomness.
+pub(crate) fn random_u64() -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    RandomState::new().build_hasher().finish()
 }
@@ -0,0 +1,440 @@
+use crate::v1::error::APIError;
+use crate::v1::resources::shared::random_u64;
+use derive_builder::Builder;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Builder, Clone, PartialEq)]
+#[builder(name = "RetryPolicyBuilder")]
+#[builder(setter(into), default)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first request.
+    pub max_attempts: u32,
+    /// The delay before the first retry. The delay doubles on every subsequent retry.
+    pub base_delay: Duration,
+    /// The upper bound for a single delay, also applied to delays requested by the server.
+    pub max_delay: Duration,
+    /// The fraction (between 0.0 and 1.0) of each computed delay that is randomized.
+    pub jitter: f64,
+    /// The HTTP status codes that are retried.
+    pub retryable_status_codes: Vec<u16>,
+    /// Whether to retry when the request could not be sent or timed out (`APIError::ServerError`).
+    pub retry_on_connection_error: bool,
+    /// Decides which errors are retried, defaults to `APIError::is_retryable`.
+    #[builder(setter(custom))]
+    pub retryable_error: RetryPredicate,
+}
+
+/// A function that returns whether an error should be retried.
+#[derive(Clone)]
+pub struct RetryPredicate(Arc<dyn Fn(&APIError) -> bool + Send + Sync>);
+
+impl RetryPredicate {
+    pub fn new(predicate: impl Fn(&APIError) -> bool + Send + Sync + 'static) -> Self {
+        Self(Arc::new(predicate))
+    }
+
+    pub fn matches(&self, error: &APIError) -> bool {
+        (self.0)(error)
+    }
+}
+
+impl Default for RetryPredicate {
+    fn default() -> Self {
+        Self::new(APIError::is_retryable)
+    }
+}
+
+impl Debug for RetryPredicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RetryPredicate")
+    }
+}
+
+impl PartialEq for RetryPredicate {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl RetryPolicyBuilder {
+    /// Sets the function that decides which errors are retried, defaults to `APIError::is_retryable`.
+    pub fn retryable_error(
+        &mut self,
+        predicate: impl Fn(&APIError) -> bool + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.retryable_error = Some(RetryPredicate::new(predicate));
+
+        self
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+            jitter: 0.25,
+            retryable_status_codes: vec![408, 409, 429, 500, 502, 503, 504],
+            retry_on_connection_error: true,
+            retryable_error: RetryPredicate::default(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns whether the given HTTP status code should be retried.
+    pub fn is_retryable_status(&self, status: StatusCode) -> bool {
+        self.retryable_status_codes.contains(&status.as_u16())
+    }
+
+    /// Returns whether the error should be retried.
+    ///
+    /// An `x-should-retry` header of the response takes precedence. Otherwise the status code (or, for
+    /// connection errors, `retry_on_connection_error`) and the `retryable_error` predicate must both allow it.
+    pub fn is_retryable_error(&self, error: &APIError) -> bool {
+        if let Some(should_retry) = error.response().and_then(|response| response.should_retry) {
+            return should_retry;
+        }
+
+        let allowed = match error.response() {
+            Some(response) => self.retryable_status_codes.contains(&response.status),
+            None => self.retry_on_connection_error && matches!(error, APIError::ServerError(_)),
+        };
+
+        allowed && self.retryable_error.matches(error)
+    }
+
+    /// Returns the exponential backoff delay (before jitter) for the given retry, starting at 1.
+    pub fn backoff_delay(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(31);
+        let delay = self.base_delay.saturating_mul(1u32 << exponent);
+
+        delay.min(self.max_delay)
+    }
+
+    /// Returns the delay to wait before the next attempt, or `None` if the error should not be retried.
+    pub fn retry_delay(&self, attempt: u32, error: &APIError) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.is_retryable_error(error) {
+            return None;
+        }
+
+        match error.retry_after() {
+            Some(delay) => Some(delay.min(self.max_delay)),
+            None => Some(self.jittered(self.backoff_delay(attempt))),
+        }
+    }
+
+    fn jittered(&self, delay: Duration) -> Duration {
+        let jitter = self.jitter.clamp(0.0, 1.0);
+
+        if jitter == 0.0 {
+            return delay;
+        }
+
+        let random = random_u64() as f64 / u64::MAX as f64;
+
+        delay.mul_f64(1.0 - jitter * random)
+    }
+}
+
+/// Reads the delay requested by the server from the `retry-after-ms`, `retry-after` and `x-ratelimit-reset-*` headers.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    if let Some(milliseconds) = header("retry-after-ms").and_then(|value| value.parse::<f64>().ok())
+    {
+        return Some(Duration::from_secs_f64(milliseconds.max(0.0) / 1000.0));
+    }
+
+    if let Some(retry_after) = header("retry-after") {
+        if let Ok(seconds) = retry_after.parse::<f64>() {
+            return Some(Duration::from_secs_f64(seconds.max(0.0)));
+        }
+
+        if let Ok(date) = httpdate::parse_http_date(retry_after) {
+            return Some(
+                date.duration_since(SystemTime::now())
+                    .unwrap_or(Duration::ZERO),
+            );
+        }
+    }
+
+    let reset_requests = header("x-ratelimit-reset-requests").and_then(parse_reset_duration);
+    let reset_tokens = header("x-ratelimit-reset-tokens").and_then(parse_reset_duration);
+
+    match (reset_requests, reset_tokens) {
+        (Some(requests), Some(tokens)) => Some(requests.max(tokens)),
+        (requests, tokens) => requests.or(tokens),
+    }
+}
+
+/// Parses the duration format used by the `x-ratelimit-reset-*` headers, e.g. `1s`, `6m0s`, `20ms` or `1h30m`.
+pub fn parse_reset_duration(value: &str) -> Option<Duration> {
+    let mut total = Duration::ZERO;
+    let mut number = String::new();
+    let mut chars = value.trim().chars().peekable();
+    let mut parsed_any = false;
+
+    while let Some(char) = chars.next() {
+        if char.is_ascii_digit() || char == '.' {
+            number.push(char);
+            continue;
+        }
+
+        let amount: f64 = number.parse().ok()?;
+        number.clear();
+
+        let seconds = match char {
+            'h' => amount * 3600.0,
+            'm' if chars.peek() == Some(&'s') => {
+                chars.next();
+                amount / 1000.0
+            }
+            'm' => amount * 60.0,
+            's' => amount,
+            _ => return None,
+        };
+
+        total += Duration::from_secs_f64(seconds);
+        parsed_any = true;
+    }
+
+    if !number.is_empty() {
+        return None;
+    }
+
+    parsed_any.then_some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::retry::{parse_reset_duration, retry_after, RetryPolicyBuilder};
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use std::time::Duration;
+    #[cfg(feature = "testing")]
+    use {
+        crate::v1::api::Client,
+        crate::v1::error::{APIError, ErrorResponse},
+        crate::v1::resources::file::{FilePurpose, UploadFileParametersBuilder},
+        crate::v1::resources::shared::{FileUpload, FileUploadBytes},
+        crate::v1::testing::{Mock, MockResponse, MockServer},
+        serde_json::json,
+    };
+
+    #[cfg(feature = "testing")]
+    fn test_client(server: &MockServer) -> Client {
+        let mut client = server.client();
+
+        client.set_retry_policy(
+            RetryPolicyBuilder::default()
+                .max_attempts(3u32)
+                .base_delay(Duration::from_millis(1))
+                .build()
+                .unwrap(),
+        );
+
+        client
+    }
+
+    #[test]
+    fn test_parse_reset_duration() {
+        assert_eq!(parse_reset_duration("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_reset_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(
+            parse_reset_duration("20ms"),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(
+            parse_reset_duration("1h30m"),
+            Some(Duration::from_secs(5400))
+        );
+        assert_eq!(
+            parse_reset_duration("1.5s"),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(parse_reset_duration("soon"), None);
+        assert_eq!(parse_reset_duration("10"), None);
+    }
+
+    #[test]
+    fn test_retry_after_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-reset-requests", HeaderValue::from_static("2s"));
+        headers.insert("x-ratelimit-reset-tokens", HeaderValue::from_static("3s"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+
+        headers.insert("retry-after", HeaderValue::from_static("7"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+        headers.insert("retry-after-ms", HeaderValue::from_static("250"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_millis(250)));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "retry-after",
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_backoff_delay_is_capped() {
+        let policy = RetryPolicyBuilder::default()
+            .base_delay(Duration::from_millis(500))
+            .max_delay(Duration::from_secs(3))
+            .build()
+            .unwrap();
+
+        assert_eq!(policy.backoff_delay(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff_delay(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff_delay(3), Duration::from_secs(2));
+        assert_eq!(policy.backoff_delay(4), Duration::from_secs(3));
+        assert_eq!(policy.backoff_delay(40), Duration::from_secs(3));
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_retries_rate_limited_requests() {
+        let server = MockServer::start().await;
+
+        server
+            .mock(
+                Mock::get("/models/gpt-4o")
+                    .respond_with(MockResponse::new(429).header("retry-after-ms", "1"))
+                    .times(2),
+            )
+            .mock(
+                Mock::get("/models/gpt-4o").respond_with(MockResponse::json(json!({
+                    "id": "gpt-4o",
+                    "object": "model",
+                    "created": 1686935002,
+                    "owned_by": "openai"
+                }))),
+            );
+
+        let model = test_client(&server).models().get("gpt-4o").await.unwrap();
+
+        assert_eq!(model.id, "gpt-4o");
+        assert_eq!(server.received_requests().len(), 3);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let server = MockServer::start().await;
+
+        server.mock(
+            Mock::get("/models/gpt-4o").respond_with(MockResponse::new(429).body("slow down")),
+        );
+
+        let result = test_client(&server).models().get("gpt-4o").await;
+
+        assert!(matches!(result, Err(APIError::RateLimitError(_))));
+        assert_eq!(server.received_requests().len(), 3);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_does_not_retry_bad_requests() {
+        let server = MockServer::start().await;
+
+        server
+            .mock(Mock::get("/models/gpt-4o").respond_with(MockResponse::new(400).body("invalid")));
+
+        let result = test_client(&server).models().get("gpt-4o").await;
+
+        assert!(matches!(result, Err(APIError::BadRequestError(_))));
+        assert_eq!(server.received_requests().len(), 1);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_retry_decision_uses_error_and_should_retry_header() {
+        let server = MockServer::start().await;
+
+        server
+            .mock(Mock::get("/models/quota").respond_with(MockResponse::error(
+                429,
+                "quota exceeded",
+                "insufficient_quota",
+                Some("insufficient_quota"),
+            )))
+            .mock(
+                Mock::get("/models/flaky")
+                    .respond_with(MockResponse::new(400).header("x-should-retry", "true")),
+            );
+
+        let client = test_client(&server);
+
+        let result = client.models().get("quota").await;
+        assert!(matches!(result, Err(APIError::RateLimitError(_))));
+        assert_eq!(server.received_requests().len(), 1);
+
+        let result = client.models().get("flaky").await;
+        assert!(matches!(result, Err(APIError::BadRequestError(_))));
+        assert_eq!(server.received_requests().len(), 4);
+
+        let policy = RetryPolicyBuilder::default()
+            .retryable_error(|error| error.status_code() == Some(503))
+            .build()
+            .unwrap();
+        let unavailable = APIError::ServiceUnavailableError(Box::new(ErrorResponse::new(503, "")));
+        let internal = APIError::InternalServerError(Box::new(ErrorResponse::new(500, "")));
+
+        assert!(policy.is_retryable_error(&unavailable));
+        assert!(!policy.is_retryable_error(&internal));
+        assert!(!policy.is_retryable_error(&APIError::ServerError("reset".to_string())));
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_rebuilds_multipart_form_on_retry() {
+        let server = MockServer::start().await;
+
+        server
+            .mock(
+                Mock::post("/files")
+                    .respond_with(MockResponse::new(503))
+                    .times(1),
+            )
+            .mock(Mock::post("/files").respond_with(MockResponse::json(json!({
+                "id": "file-abc123",
+                "object": "file",
+                "bytes": 11,
+                "created_at": 1677610602,
+                "filename": "hello.jsonl",
+                "purpose": "batch"
+            }))));
+
+        let parameters = UploadFileParametersBuilder::default()
+            .file(FileUpload::Bytes(FileUploadBytes::new(
+                "hello world",
+                "hello.jsonl",
+            )))
+            .purpose(FilePurpose::Batch)
+            .build()
+            .unwrap();
+
+        let file = test_client(&server)
+            .files()
+            .upload(parameters)
+            .await
+            .unwrap();
+
+        assert_eq!(file.id, "file-abc123");
+
+        let requests = server.received_requests();
+        assert_eq!(requests.len(), 2);
+
+        for request in requests {
+            let body = String::from_utf8_lossy(&request.body);
+            assert!(body.contains("hello world"));
+            assert!(body.contains("name=\"purpose\""));
+        }
+    }
+}
@@ -0,0 +1,550 @@
+use crate::v1::error::APIError;
+use crate::v1::resources::chat::{
+    ChatCompletionFunction, ChatCompletionTool, ChatCompletionToolType, JsonSchema,
+    JsonSchemaBuilder,
+};
+use crate::v1::resources::response::shared::{ResponseFormat, ResponseTool};
+use schemars::generate::SchemaSettings;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// The keywords that are not supported in strict mode.
+const UNSUPPORTED_KEYWORDS: [&str; 11] = [
+    "allOf",
+    "oneOf",
+    "not",
+    "if",
+    "then",
+    "else",
+    "dependentRequired",
+    "dependentSchemas",
+    "patternProperties",
+    "unevaluatedProperties",
+    "propertyNames",
+];
+
+/// The string formats that are supported in strict mode.
+const SUPPORTED_FORMATS: [&str; 9] = [
+    "date-time",
+    "time",
+    "date",
+    "duration",
+    "email",
+    "hostname",
+    "ipv4",
+    "ipv6",
+    "uuid",
+];
+
+const MAX_NESTING_DEPTH: usize = 10;
+
+const MAX_PROPERTIES: usize = 5000;
+
+/// The output of a request with a structured output schema.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StructuredOutput<T> {
+    /// The output was deserialized into `T`.
+    Parsed(T),
+    /// The model refused to answer.
+    Refusal(String),
+    /// The output was cut off, for example because the token limit was reached.
+    Truncated { reason: String, text: String },
+    /// The output could not be deserialized into `T`.
+    Invalid { text: String, error: String },
+}
+
+/// A response together with its structured output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parsed<R, T> {
+    pub response: R,
+    pub output: StructuredOutput<T>,
+}
+
+impl<T> StructuredOutput<T> {
+    /// Returns the parsed value, if the output matched the schema.
+    pub fn parsed(self) -> Option<T> {
+        match self {
+            StructuredOutput::Parsed(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> StructuredOutput<T> {
+    /// Deserializes the text generated by the model.
+    pub fn parse(text: &str) -> Self {
+        match serde_json::from_str(text) {
+            Ok(value) => StructuredOutput::Parsed(value),
+            Err(error) => StructuredOutput::Invalid {
+                text: text.to_string(),
+                error: error.to_string(),
+            },
+        }
+    }
+}
+
+/// Generates a strict mode compatible JSON Schema for `T`.
+///
+/// Every object gets `additionalProperties: false` and lists all of its properties as required. `Option` fields
+/// are already nullable, fields with a serde default must be sent by the model. The schema is validated with
+/// `validate_strict_schema`.
+pub fn strict_schema_for<T: schemars::JsonSchema>() -> Result<Value, APIError> {
+    let mut schema = SchemaSettings::draft2020_12()
+        .into_generator()
+        .into_root_schema_for::<T>()
+        .to_value();
+
+    if let Some(object) = schema.as_object_mut() {
+        object.remove("$schema");
+    }
+    make_strict(&mut schema);
+
+    validate_strict_schema(&schema)?;
+
+    Ok(schema)
+}
+
+/// Checks that the schema only uses the JSON Schema subset supported by strict mode (structured outputs and strict function calling).
+pub fn validate_strict_schema(schema: &Value) -> Result<(), APIError> {
+    if schema.get("type") != Some(&Value::String("object".to_string())) {
+        return Err(APIError::ValidationError(
+            "the root of a strict schema must be an object".to_string(),
+        ));
+    }
+
+    let mut properties = 0;
+    validate(schema, "#", 0, &mut properties)?;
+
+    if let Some(Value::Object(definitions)) = schema.get("$defs") {
+        for (name, definition) in definitions {
+            validate(definition, &format!("#/$defs/{name}"), 0, &mut properties)?;
+        }
+    }
+
+    if properties > MAX_PROPERTIES {
+        return Err(APIError::ValidationError(format!(
+            "a strict schema may have at most {MAX_PROPERTIES} properties, found {properties}"
+        )));
+    }
+
+    Ok(())
+}
+
+impl JsonSchema {
+    /// Creates a strict response format schema for `T`, named after the type.
+    pub fn from_type<T: schemars::JsonSchema>() -> Result<Self, APIError> {
+        let schema = strict_schema_for::<T>()?;
+
+        let mut builder = JsonSchemaBuilder::default();
+        builder
+            .name(schema_name::<T>())
+            .schema(schema.clone())
+            .strict(true);
+
+        if let Some(Value::String(description)) = schema.get("description") {
+            builder.description(description.clone());
+        }
+
+        builder
+            .build()
+            .map_err(|error| APIError::ValidationError(error.to_string()))
+    }
+}
+
+impl ResponseFormat {
+    /// Creates a strict response format schema for `T`, named after the type.
+    pub fn from_type<T: schemars::JsonSchema>() -> Result<Self, APIError> {
+        let schema = strict_schema_for::<T>()?;
+
+        Ok(Self::JsonSchema {
+            name: schema_name::<T>(),
+            description: schema
+                .get("description")
+                .and_then(Value::as_str)
+                .map(|description| description.to_string()),
+            schema,
+            strict: Some(true),
+        })
+    }
+}
+
+impl ChatCompletionTool {
+    /// Creates a strict function tool with the parameters of `T`.
+    pub fn function<T: schemars::JsonSchema>(
+        name: &str,
+        description: &str,
+    ) -> Result<Self, APIError> {
+        Ok(Self {
+            r#type: ChatCompletionToolType::Function,
+            function: ChatCompletionFunction {
+                name: name.to_string(),
+                description: Some(description.to_string()),
+                parameters: strict_schema_for::<T>()?,
+                strict: Some(true),
+            },
+        })
+    }
+}
+
+impl ResponseTool {
+    /// Creates a strict function tool with the parameters of `T`.
+    pub fn function<T: schemars::JsonSchema>(
+        name: &str,
+        description: &str,
+    ) -> Result<Self, APIError> {
+        Ok(Self::Function {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            parameters: strict_schema_for::<T>()?,
+            strict: true,
+        })
+    }
+}
+
+/// The name of the type, limited to the characters and length allowed for response format names.
+fn schema_name<T: schemars::JsonSchema>() -> String {
+    T::schema_name()
+        .chars()
+        .map(|char| {
+            if char.is_ascii_alphanumeric() || char == '_' || char == '-' {
+                char
+            } else {
+                '_'
+            }
+        })
+        .take(64)
+        .collect()
+}
+
+fn make_strict(schema: &mut Value) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+
+    if let Some(one_of) = object.remove("oneOf") {
+        object.insert("anyOf".to_string(), one_of);
+    }
+
+    if matches!(object.get("format"), Some(Value::String(format)) if !SUPPORTED_FORMATS.contains(&format.as_str()))
+    {
+        object.remove("format");
+    }
+
+    object.remove("default");
+
+    if object.get("properties").is_some_and(Value::is_object) {
+        let Some(Value::Object(properties)) = object.get_mut("properties") else {
+            return;
+        };
+
+        properties.values_mut().for_each(make_strict);
+
+        let names = properties
+            .keys()
+            .map(|name| Value::String(name.clone()))
+            .collect();
+
+        object.insert("required".to_string(), Value::Array(names));
+        object.insert("additionalProperties".to_string(), Value::Bool(false));
+    } else if object.get("type") == Some(&Value::String("object".to_string()))
+        && !object.contains_key("additionalProperties")
+    {
+        object.insert("properties".to_string(), Value::Object(Map::new()));
+        object.insert("required".to_string(), Value::Array(Vec::new()));
+        object.insert("additionalProperties".to_string(), Value::Bool(false));
+    }
+
+    if let Some(items) = object.get_mut("items") {
+        make_strict(items);
+    }
+
+    if let Some(Value::Array(any_of)) = object.get_mut("anyOf") {
+        any_of.iter_mut().for_each(make_strict);
+    }
+
+    if let Some(Value::Object(definitions)) = object.get_mut("$defs") {
+        definitions.values_mut().for_each(make_strict);
+    }
+}
+
+fn validate(
+    schema: &Value,
+    path: &str,
+    depth: usize,
+    properties_count: &mut usize,
+) -> Result<(), APIError> {
+    let Value::Object(object) = schema else {
+        return Err(APIError::ValidationError(format!(
+            "{path}: a strict schema must be an object"
+        )));
+    };
+
+    if let Some(keyword) = UNSUPPORTED_KEYWORDS
+        .iter()
+        .find(|keyword| object.contains_key(**keyword))
+    {
+        return Err(APIError::ValidationError(format!(
+            "{path}: `{keyword}` is not supported in strict mode"
+        )));
+    }
+
+    if let Some(properties) = object.get("properties") {
+        if depth >= MAX_NESTING_DEPTH {
+            return Err(APIError::ValidationError(format!(
+                "{path}: a strict schema may have at most {MAX_NESTING_DEPTH} levels of nesting"
+            )));
+        }
+
+        if object.get("additionalProperties") != Some(&Value::Bool(false)) {
+            return Err(APIError::ValidationError(format!(
+                "{path}: `additionalProperties` must be false"
+            )));
+        }
+
+        let Value::Object(properties) = properties else {
+            return Err(APIError::ValidationError(format!(
+                "{path}: `properties` must be an object"
+            )));
+        };
+
+        let required = match object.get("required") {
+            Some(Value::Array(required)) => required.as_slice(),
+            _ => &[],
+        };
+
+        for (name, property) in properties {
+            if !required.contains(&Value::String(name.clone())) {
+                return Err(APIError::ValidationError(format!(
+                    "{path}: property `{name}` must be required, use a nullable type for optional properties"
+                )));
+            }
+
+            *properties_count += 1;
+            validate(
+                property,
+                &format!("{path}/properties/{name}"),
+                depth + 1,
+                properties_count,
+            )?;
+        }
+    } else if object.get("type") == Some(&Value::String("object".to_string())) {
+        return Err(APIError::ValidationError(format!(
+            "{path}: objects must list their properties, maps with arbitrary keys are not supported"
+        )));
+    }
+
+    if let Some(items) = object.get("items") {
+        validate(items, &format!("{path}/items"), depth, properties_count)?;
+    }
+
+    if let Some(Value::Array(any_of)) = object.get("anyOf") {
+        for (index, variant) in any_of.iter().enumerate() {
+            validate(
+                variant,
+                &format!("{path}/anyOf/{index}"),
+                depth,
+                properties_count,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::error::APIError;
+    use crate::v1::resources::chat::ChatCompletionTool;
+    use crate::v1::schema::{strict_schema_for, validate_strict_schema};
+    use schemars::JsonSchema;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct Location {
+        city: String,
+        country: Option<String>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    enum Unit {
+        Celsius,
+        Fahrenheit,
+    }
+
+    /// Get the current weather.
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct WeatherArguments {
+        location: Location,
+        unit: Option<Unit>,
+        days: u32,
+        #[serde(default)]
+        hourly: bool,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct Metadata {
+        tags: HashMap<String, String>,
+    }
+
+    #[test]
+    fn test_strict_schema_for_type() {
+        let schema = strict_schema_for::<WeatherArguments>().unwrap();
+
+        assert_eq!(schema["additionalProperties"], json!(false));
+        assert_eq!(
+            schema["required"],
+            json!(["days", "hourly", "location", "unit"])
+        );
+        assert_eq!(schema["properties"]["hourly"], json!({"type": "boolean"}));
+        assert_eq!(schema["properties"]["days"].get("format"), None);
+        assert_eq!(
+            schema["properties"]["unit"]["anyOf"][1],
+            json!({"type": "null"})
+        );
+
+        let location = &schema["$defs"]["Location"];
+        assert_eq!(location["required"], json!(["city", "country"]));
+        assert_eq!(location["additionalProperties"], json!(false));
+        assert_eq!(
+            location["properties"]["country"]["type"],
+            json!(["string", "null"])
+        );
+
+        let tool =
+            ChatCompletionTool::function::<WeatherArguments>("get_weather", "Get the weather")
+                .unwrap();
+        assert_eq!(tool.function.strict, Some(true));
+    }
+
+    #[test]
+    fn test_maps_are_rejected_in_strict_mode() {
+        assert!(matches!(
+            strict_schema_for::<Metadata>(),
+            Err(APIError::ValidationError(message)) if message.starts_with("#/properties/tags")
+        ));
+
+        assert!(validate_strict_schema(&json!({
+            "type": "object",
+            "properties": {"name": {"type": "string"}},
+            "required": []
+        }))
+        .is_err());
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_create_parsed() {
+        use crate::v1::resources::chat::ChatCompletionParametersBuilder;
+        use crate::v1::resources::response::request::{ResponseInput, ResponseParametersBuilder};
+        use crate::v1::schema::StructuredOutput;
+        use crate::v1::testing::{Mock, MockResponse, MockServer};
+        use serde::Deserialize;
+
+        #[derive(Debug, PartialEq, Deserialize, JsonSchema)]
+        struct Answer {
+            value: u32,
+        }
+
+        let chat_response = |message: serde_json::Value, finish_reason: &str| {
+            MockResponse::json(json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion",
+                "created": 1,
+                "model": "gpt-4o",
+                "choices": [{"index": 0, "message": message, "finish_reason": finish_reason}]
+            }))
+        };
+
+        let server = MockServer::start().await;
+        server
+            .mock(
+                Mock::post("/chat/completions")
+                    .respond_with(chat_response(
+                        json!({"role": "assistant", "content": "{\"value\": 42}"}),
+                        "stop",
+                    ))
+                    .times(1),
+            )
+            .mock(
+                Mock::post("/chat/completions")
+                    .respond_with(chat_response(
+                        json!({"role": "assistant", "content": null, "refusal": "I can't help with that."}),
+                        "stop",
+                    ))
+                    .times(1),
+            )
+            .mock(
+                Mock::post("/responses").respond_with(MockResponse::json(json!({
+                    "id": "resp_1",
+                    "object": "response",
+                    "created_at": 1,
+                    "model": "gpt-4o",
+                    "status": "incomplete",
+                    "incomplete_details": {"reason": "max_output_tokens"},
+                    "output": [{
+                        "type": "message",
+                        "id": "msg_1",
+                        "role": "assistant",
+                        "status": "incomplete",
+                        "content": [{"type": "output_text", "text": "{\"val", "annotations": []}]
+                    }],
+                    "tools": []
+                }))),
+            );
+
+        let client = server.client();
+        let parameters = ChatCompletionParametersBuilder::default()
+            .model("gpt-4o")
+            .messages(vec![])
+            .build()
+            .unwrap();
+
+        let parsed = client
+            .chat()
+            .create_parsed::<Answer>(parameters.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            parsed.output,
+            StructuredOutput::Parsed(Answer { value: 42 })
+        );
+
+        let request = server.received_requests()[0].json().unwrap();
+        assert_eq!(request["response_format"]["json_schema"]["name"], "Answer");
+        assert_eq!(request["response_format"]["json_schema"]["strict"], true);
+
+        let parsed = client
+            .chat()
+            .create_parsed::<Answer>(parameters)
+            .await
+            .unwrap();
+        assert_eq!(
+            parsed.output,
+            StructuredOutput::Refusal("I can't help with that.".to_string())
+        );
+
+        let parameters = ResponseParametersBuilder::default()
+            .model("gpt-4o")
+            .input(ResponseInput::Text("What is the answer?".to_string()))
+            .build()
+            .unwrap();
+
+        let parsed = client
+            .responses()
+            .create_parsed::<Answer>(parameters)
+            .await
+            .unwrap();
+        assert_eq!(
+            parsed.output,
+            StructuredOutput::Truncated {
+                reason: "max_output_tokens".to_string(),
+                text: "{\"val".to_string()
+            }
+        );
+    }
+}
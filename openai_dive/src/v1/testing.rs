@@ -0,0 +1,881 @@
+use crate::v1::api::Client;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response};
+use hyper_util::rt::TokioIo;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// An in-process HTTP server that mimics the OpenAI API for tests.
+///
+/// The most used routes have a canned default response: chat completions and responses (also streamed), embeddings,
+/// models, files, uploads, batches, vector stores and their files, audio speech, transcriptions and translations,
+/// image generations, edits and variations, and deleting any of these objects. Other routes answer with a 404
+/// error unless they are mocked. Mocks registered with `MockServer::mock` take precedence over the defaults, in the
+/// order they were registered. The server stops when it is dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    address: SocketAddr,
+    state: Arc<Mutex<MockServerState>>,
+    handle: JoinHandle<()>,
+}
+
+#[derive(Debug, Default)]
+struct MockServerState {
+    mocks: Vec<Mock>,
+    received_requests: Vec<ReceivedRequest>,
+}
+
+/// A programmed response for the requests matching a method and path.
+#[derive(Clone, Debug)]
+pub struct Mock {
+    method: Option<Method>,
+    path: String,
+    response: MockResponse,
+    times: Option<usize>,
+    delay: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Bytes,
+}
+
+/// A request received by the mock server.
+#[derive(Clone, Debug)]
+pub struct ReceivedRequest {
+    pub method: Method,
+    pub path: String,
+    pub query: Option<String>,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl MockServer {
+    /// Starts the server on a random local port.
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind the mock server");
+        let address = listener
+            .local_addr()
+            .expect("failed to read the mock server address");
+
+        let state = Arc::new(Mutex::new(MockServerState::default()));
+        let server_state = state.clone();
+
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = server_state.clone();
+
+                tokio::spawn(async move {
+                    let service = service_fn(move |request| handle(state.clone(), request));
+
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+
+        Self {
+            address,
+            state,
+            handle,
+        }
+    }
+
+    /// Returns the base URL of the server, to be used with `Client::set_base_url`.
+    pub fn uri(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Returns a client that sends its requests to this server.
+    pub fn client(&self) -> Client {
+        let mut client = Client::new("sk-test".to_string());
+        client.set_base_url(&self.uri());
+
+        client
+    }
+
+    /// Registers a mock. Mocks are matched in the order they were registered, before the default routes.
+    pub fn mock(&self, mock: Mock) -> &Self {
+        self.state.lock().unwrap().mocks.push(mock);
+
+        self
+    }
+
+    /// Returns the requests received so far, in the order they were received.
+    pub fn received_requests(&self) -> Vec<ReceivedRequest> {
+        self.state.lock().unwrap().received_requests.clone()
+    }
+
+    /// Removes all mocks and received requests.
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        state.mocks.clear();
+        state.received_requests.clear();
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+impl Mock {
+    /// Matches requests with the given method and path. A `*` path segment matches any single segment.
+    pub fn new(method: Method, path: &str) -> Self {
+        Self {
+            method: Some(method),
+            path: path.to_string(),
+            response: MockResponse::json(json!({})),
+            times: None,
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Matches requests with any method and the given path.
+    pub fn any(path: &str) -> Self {
+        Self {
+            method: None,
+            ..Self::new(Method::GET, path)
+        }
+    }
+
+    pub fn get(path: &str) -> Self {
+        Self::new(Method::GET, path)
+    }
+
+    pub fn post(path: &str) -> Self {
+        Self::new(Method::POST, path)
+    }
+
+    pub fn delete(path: &str) -> Self {
+        Self::new(Method::DELETE, path)
+    }
+
+    pub fn respond_with(mut self, response: MockResponse) -> Self {
+        self.response = response;
+
+        self
+    }
+
+    /// Only responds to the first `times` matching requests, after which later mocks or the defaults respond.
+    pub fn times(mut self, times: usize) -> Self {
+        self.times = Some(times);
+
+        self
+    }
+
+    /// Waits before responding, to simulate a slow server.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+
+        self
+    }
+
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        self.times != Some(0)
+            && self
+                .method
+                .as_ref()
+                .is_none_or(|expected| expected == method)
+            && path_matches(&self.path, path)
+    }
+}
+
+impl MockResponse {
+    /// Creates an empty response with the given status code.
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Bytes::new(),
+        }
+    }
+
+    /// Creates a successful JSON response.
+    pub fn json(body: Value) -> Self {
+        Self::new(200).json_body(body)
+    }
+
+    /// Creates an error response with an OpenAI error body.
+    pub fn error(status: u16, message: &str, r#type: &str, code: Option<&str>) -> Self {
+        Self::new(status).json_body(json!({
+            "error": {
+                "message": message,
+                "type": r#type,
+                "param": null,
+                "code": code
+            }
+        }))
+    }
+
+    /// Creates a server-sent events response from the given events, terminated by `[DONE]`.
+    pub fn events(events: impl IntoIterator<Item = Value>) -> Self {
+        let mut body = String::new();
+
+        for event in events {
+            body.push_str(&format!("data: {event}\n\n"));
+        }
+
+        body.push_str("data: [DONE]\n\n");
+
+        Self::new(200)
+            .header("content-type", "text/event-stream")
+            .body(body)
+    }
+
+    /// Creates a successful binary response.
+    pub fn bytes(body: impl Into<Bytes>, content_type: &str) -> Self {
+        Self::new(200)
+            .header("content-type", content_type)
+            .body(body)
+    }
+
+    pub fn header(mut self, key: &str, value: &str) -> Self {
+        self.headers.push((key.to_string(), value.to_string()));
+
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+
+        self
+    }
+
+    fn json_body(self, body: Value) -> Self {
+        self.header("content-type", "application/json")
+            .body(body.to_string())
+    }
+}
+
+impl ReceivedRequest {
+    /// Parses the body as JSON.
+    pub fn json(&self) -> Option<Value> {
+        serde_json::from_slice(&self.body).ok()
+    }
+}
+
+async fn handle(
+    state: Arc<Mutex<MockServerState>>,
+    request: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let (parts, body) = request.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map(|body| body.to_bytes())
+        .unwrap_or_default();
+
+    let received_request = ReceivedRequest {
+        method: parts.method,
+        path: parts.uri.path().to_string(),
+        query: parts.uri.query().map(|query| query.to_string()),
+        headers: parts.headers,
+        body,
+    };
+
+    let (response, delay) = {
+        let mut state = state.lock().unwrap();
+        state.received_requests.push(received_request.clone());
+
+        match state
+            .mocks
+            .iter_mut()
+            .find(|mock| mock.matches(&received_request.method, &received_request.path))
+        {
+            Some(mock) => {
+                if let Some(times) = mock.times.as_mut() {
+                    *times -= 1;
+                }
+
+                (mock.response.clone(), mock.delay)
+            }
+            None => (default_response(&received_request), Duration::ZERO),
+        }
+    };
+
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+
+    let mut builder = Response::builder()
+        .status(StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR));
+
+    for (key, value) in &response.headers {
+        builder = builder.header(key, value);
+    }
+
+    Ok(builder
+        .body(Full::new(response.body))
+        .unwrap_or_else(|_| Response::new(Full::new(Bytes::new()))))
+}
+
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.trim_end_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+
+    pattern.len() == path.len()
+        && pattern
+            .iter()
+            .zip(path.iter())
+            .all(|(expected, actual)| *expected == "*" || expected == actual)
+}
+
+fn default_response(request: &ReceivedRequest) -> MockResponse {
+    let body = request.json().unwrap_or(Value::Null);
+    let model = body["model"].as_str().unwrap_or("gpt-4o").to_string();
+    let stream = body["stream"].as_bool().unwrap_or(false);
+    let created = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    let segments: Vec<&str> = request
+        .path
+        .trim_matches('/')
+        .split('/')
+        .skip_while(|segment| *segment == "v1")
+        .collect();
+
+    const TEXT: &str = "Hello from the mock server!";
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("POST", ["chat", "completions"]) if stream => MockResponse::events([
+            json!({
+                "id": "chatcmpl-mock",
+                "object": "chat.completion.chunk",
+                "created": created,
+                "model": model,
+                "choices": [{ "index": 0, "delta": { "role": "assistant", "content": TEXT }, "finish_reason": null }]
+            }),
+            json!({
+                "id": "chatcmpl-mock",
+                "object": "chat.completion.chunk",
+                "created": created,
+                "model": model,
+                "choices": [{ "index": 0, "delta": { "role": "assistant", "content": "" }, "finish_reason": "stop" }]
+            }),
+        ]),
+        ("POST", ["chat", "completions"]) => MockResponse::json(json!({
+            "id": "chatcmpl-mock",
+            "object": "chat.completion",
+            "created": created,
+            "model": model,
+            "choices": [{ "index": 0, "message": { "role": "assistant", "content": TEXT }, "finish_reason": "stop" }],
+            "usage": { "prompt_tokens": 10, "completion_tokens": 6, "total_tokens": 16 }
+        })),
+        ("POST", ["responses"]) if stream => {
+            let response = response_object(&model, created, "in_progress", vec![]);
+            let completed =
+                response_object(&model, created, "completed", vec![output_message(TEXT)]);
+
+            MockResponse::events([
+                json!({ "type": "response.created", "sequence_number": 0, "response": response }),
+                json!({ "type": "response.output_text.delta", "sequence_number": 1, "item_id": "msg_mock", "output_index": 0, "content_index": 0, "delta": TEXT }),
+                json!({ "type": "response.completed", "sequence_number": 2, "response": completed }),
+            ])
+        }
+        ("POST", ["responses"]) | ("GET", ["responses", _]) => MockResponse::json(response_object(
+            &model,
+            created,
+            "completed",
+            vec![output_message(TEXT)],
+        )),
+        ("POST", ["embeddings"]) => {
+            let inputs = match &body["input"] {
+                Value::Array(inputs) if inputs.iter().all(|input| !input.is_number()) => {
+                    inputs.len()
+                }
+                _ => 1,
+            };
+
+            MockResponse::json(json!({
+                "object": "list",
+                "data": (0..inputs).map(|index| json!({
+                    "object": "embedding",
+                    "index": index,
+                    "embedding": [0.0, 0.1, 0.2, 0.3]
+                })).collect::<Vec<Value>>(),
+                "model": model,
+                "usage": { "prompt_tokens": 4, "total_tokens": 4 }
+            }))
+        }
+        ("GET", ["models"]) => MockResponse::json(json!({
+            "object": "list",
+            "data": [{ "id": model, "object": "model", "created": created, "owned_by": "openai" }]
+        })),
+        ("GET", ["models", id]) => MockResponse::json(
+            json!({ "id": id, "object": "model", "created": created, "owned_by": "openai" }),
+        ),
+        ("GET", ["files"]) => list(vec![file_object("file-mock", created)]),
+        ("POST", ["files"]) | ("GET", ["files", _]) => {
+            let id = segments.get(1).copied().unwrap_or("file-mock");
+
+            MockResponse::json(file_object(id, created))
+        }
+        ("GET", ["files", _, "content"]) => {
+            MockResponse::new(200).body("{\"custom_id\": \"request-1\"}\n")
+        }
+        ("DELETE", [object, id]) => MockResponse::json(json!({
+            "id": id,
+            "object": object.trim_end_matches('s'),
+            "deleted": true
+        })),
+        ("POST", ["uploads"]) => {
+            MockResponse::json(upload_object("upload-mock", &body, "pending", created))
+        }
+        ("POST", ["uploads", id, "parts"]) => MockResponse::json(json!({
+            "id": "part-mock",
+            "object": "upload.part",
+            "created_at": created,
+            "upload_id": id
+        })),
+        ("POST", ["uploads", id, "complete"]) => {
+            let mut upload = upload_object(id, &body, "completed", created);
+            upload["file"] = file_object("file-mock", created);
+
+            MockResponse::json(upload)
+        }
+        ("POST", ["uploads", id, "cancel"]) => {
+            MockResponse::json(upload_object(id, &body, "cancelled", created))
+        }
+        ("GET", ["batches"]) => list(vec![batch_object(
+            "batch-mock",
+            &body,
+            "validating",
+            created,
+        )]),
+        ("POST", ["batches"]) => {
+            MockResponse::json(batch_object("batch-mock", &body, "validating", created))
+        }
+        ("GET", ["batches", id]) => {
+            MockResponse::json(batch_object(id, &body, "in_progress", created))
+        }
+        ("POST", ["batches", id, "cancel"]) => {
+            MockResponse::json(batch_object(id, &body, "cancelling", created))
+        }
+        ("GET", ["vector_stores"]) => list(vec![vector_store_object("vs-mock", &body, created)]),
+        ("POST", ["vector_stores"]) => {
+            MockResponse::json(vector_store_object("vs-mock", &body, created))
+        }
+        ("GET", ["vector_stores", id]) | ("POST", ["vector_stores", id]) => {
+            MockResponse::json(vector_store_object(id, &body, created))
+        }
+        ("GET", ["vector_stores", vector_store_id, "files"]) => {
+            list(vec![vector_store_file_object(
+                "file-mock",
+                vector_store_id,
+                created,
+            )])
+        }
+        ("POST", ["vector_stores", vector_store_id, "files"]) => {
+            MockResponse::json(vector_store_file_object(
+                body["file_id"].as_str().unwrap_or("file-mock"),
+                vector_store_id,
+                created,
+            ))
+        }
+        ("GET", ["vector_stores", vector_store_id, "files", id]) => {
+            MockResponse::json(vector_store_file_object(id, vector_store_id, created))
+        }
+        ("DELETE", ["vector_stores", _, "files", id]) => MockResponse::json(json!({
+            "id": id,
+            "object": "vector_store.file.deleted",
+            "deleted": true
+        })),
+        ("POST", ["audio", "speech"]) => {
+            MockResponse::bytes(Bytes::from_static(&[0xff, 0xfb, 0x90, 0x00]), "audio/mpeg")
+        }
+        ("POST", ["audio", "transcriptions"]) | ("POST", ["audio", "translations"]) => {
+            MockResponse::json(json!({ "text": TEXT }))
+        }
+        ("POST", ["images", "generations"])
+        | ("POST", ["images", "edits"])
+        | ("POST", ["images", "variations"]) => MockResponse::json(json!({
+            "created": created,
+            "data": [{ "url": "https://example.com/mock.png" }]
+        })),
+        _ => MockResponse::error(
+            404,
+            &format!(
+                "The mock server has no route for {} {}",
+                request.method, request.path
+            ),
+            "invalid_request_error",
+            Some("unknown_url"),
+        ),
+    }
+}
+
+fn list(data: Vec<Value>) -> MockResponse {
+    let first_id = data.first().map(|item| item["id"].clone());
+    let last_id = data.last().map(|item| item["id"].clone());
+
+    MockResponse::json(json!({
+        "object": "list",
+        "data": data,
+        "first_id": first_id,
+        "last_id": last_id,
+        "has_more": false
+    }))
+}
+
+fn response_object(model: &str, created: u64, status: &str, output: Vec<Value>) -> Value {
+    json!({
+        "id": "resp_mock",
+        "object": "response",
+        "created_at": created,
+        "model": model,
+        "status": status,
+        "output": output,
+        "tools": [],
+        "usage": { "input_tokens": 10, "output_tokens": 6, "total_tokens": 16 }
+    })
+}
+
+fn output_message(text: &str) -> Value {
+    json!({
+        "type": "message",
+        "id": "msg_mock",
+        "role": "assistant",
+        "status": "completed",
+        "content": [{ "type": "output_text", "text": text, "annotations": [] }]
+    })
+}
+
+fn file_object(id: &str, created: u64) -> Value {
+    json!({
+        "id": id,
+        "object": "file",
+        "bytes": 28,
+        "created_at": created,
+        "filename": "mock.jsonl",
+        "purpose": "batch"
+    })
+}
+
+fn upload_object(id: &str, body: &Value, status: &str, created: u64) -> Value {
+    json!({
+        "id": id,
+        "object": "upload",
+        "created_at": created,
+        "filename": body["filename"].as_str().unwrap_or("mock.jsonl"),
+        "bytes": body["bytes"].as_u64().unwrap_or(28),
+        "purpose": body["purpose"].as_str().unwrap_or("batch"),
+        "status": status,
+        "expires_at": created + 3600,
+        "file": null
+    })
+}
+
+fn batch_object(id: &str, body: &Value, status: &str, created: u64) -> Value {
+    json!({
+        "id": id,
+        "object": "batch",
+        "endpoint": body["endpoint"].as_str().unwrap_or("/v1/chat/completions"),
+        "input_file_id": body["input_file_id"].as_str().unwrap_or("file-mock"),
+        "completion_window": "24h",
+        "status": status,
+        "created_at": created,
+        "request_counts": { "total": 0, "completed": 0, "failed": 0 }
+    })
+}
+
+fn vector_store_object(id: &str, body: &Value, created: u64) -> Value {
+    json!({
+        "id": id,
+        "object": "vector_store",
+        "created_at": created,
+        "name": body["name"].as_str().unwrap_or("mock"),
+        "usage_bytes": 0,
+        "file_counts": { "in_progress": 0, "completed": 0, "failed": 0, "cancelled": 0, "total": 0 },
+        "status": "completed"
+    })
+}
+
+fn vector_store_file_object(id: &str, vector_store_id: &str, created: u64) -> Value {
+    json!({
+        "id": id,
+        "object": "vector_store.file",
+        "created_at": created,
+        "usage_bytes": 28,
+        "vector_store_id": vector_store_id,
+        "status": "completed"
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::error::APIError;
+    use crate::v1::resources::audio::{AudioSpeechParametersBuilder, AudioVoice};
+    use crate::v1::resources::batch::CreateBatchParametersBuilder;
+    use crate::v1::resources::chat::{
+        ChatCompletionParametersBuilder, ChatMessage, ChatMessageContent,
+    };
+    use crate::v1::resources::embedding::{EmbeddingInput, EmbeddingParametersBuilder};
+    use crate::v1::resources::file::FilePurpose;
+    use crate::v1::resources::image::CreateImageParametersBuilder;
+    use crate::v1::resources::response::request::{ResponseInput, ResponseParametersBuilder};
+    use crate::v1::resources::upload::CreateUploadParametersBuilder;
+    use crate::v1::resources::vector_store::CreateVectorStoreParametersBuilder;
+    use crate::v1::resources::vector_store_file::CreateVectorStoreFileParameters;
+    use crate::v1::retry::RetryPolicyBuilder;
+    use crate::v1::testing::{Mock, MockResponse, MockServer};
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_default_routes() {
+        let server = MockServer::start().await;
+        let client = server.client();
+
+        let chat = client
+            .chat()
+            .create(
+                ChatCompletionParametersBuilder::default()
+                    .model("gpt-4o-mini")
+                    .messages(vec![ChatMessage::User {
+                        content: ChatMessageContent::Text("Hello".to_string()),
+                        name: None,
+                    }])
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(chat.model, "gpt-4o-mini");
+
+        let response = client
+            .responses()
+            .create(
+                ResponseParametersBuilder::default()
+                    .model("gpt-4o")
+                    .input(ResponseInput::Text("Hello".to_string()))
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.output.len(), 1);
+
+        let embeddings = client
+            .embeddings()
+            .create(
+                EmbeddingParametersBuilder::default()
+                    .model("text-embedding-3-small")
+                    .input(EmbeddingInput::StringArray(vec![
+                        "a".to_string(),
+                        "b".to_string(),
+                    ]))
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(embeddings.data.len(), 2);
+
+        client.files().list(None).await.unwrap();
+        client.files().retrieve("file-abc").await.unwrap();
+        client.files().delete("file-abc").await.unwrap();
+
+        let upload = client
+            .uploads()
+            .create(
+                CreateUploadParametersBuilder::default()
+                    .filename("data.jsonl")
+                    .purpose(FilePurpose::Batch)
+                    .bytes(1024u64)
+                    .mime_type("text/jsonl")
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(upload.filename, "data.jsonl");
+
+        client
+            .batches()
+            .create(
+                CreateBatchParametersBuilder::default()
+                    .input_file_id("file-abc")
+                    .endpoint("/v1/chat/completions")
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        client.batches().retrieve("batch-abc").await.unwrap();
+        client.batches().list(None).await.unwrap();
+
+        client
+            .vector_stores()
+            .create(
+                CreateVectorStoreParametersBuilder::default()
+                    .name("docs")
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        client.vector_stores().list(None).await.unwrap();
+
+        let vector_store_file = client
+            .vector_store_files()
+            .create(
+                "vs-abc",
+                CreateVectorStoreFileParameters {
+                    file_id: "file-abc".to_string(),
+                    chunking_strategy: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(vector_store_file.id, "file-abc");
+        assert_eq!(vector_store_file.vector_store_id, "vs-abc");
+        client
+            .vector_store_files()
+            .retrieve("vs-abc", "file-abc")
+            .await
+            .unwrap();
+        client
+            .vector_store_files()
+            .list("vs-abc", None)
+            .await
+            .unwrap();
+        let deleted = client
+            .vector_store_files()
+            .delete("vs-abc", "file-abc")
+            .await
+            .unwrap();
+        assert!(deleted.deleted);
+
+        let speech = client
+            .audio()
+            .create_speech(
+                AudioSpeechParametersBuilder::default()
+                    .model("tts-1")
+                    .input("Hello")
+                    .voice(AudioVoice::Alloy)
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(!speech.bytes.is_empty());
+
+        let images = client
+            .images()
+            .create(
+                CreateImageParametersBuilder::default()
+                    .prompt("A mock")
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(images.data.len(), 1);
+
+        let result = client.models().get("gpt-4o").await;
+        assert!(result.is_ok());
+
+        let result = client.fine_tuning().retrieve("ftjob-abc").await;
+        assert!(matches!(result, Err(APIError::NotFoundError(_))));
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_default_stream_routes() {
+        use futures::StreamExt;
+
+        let server = MockServer::start().await;
+        let client = server.client();
+
+        let chunks: Vec<_> = client
+            .chat()
+            .create_stream(
+                ChatCompletionParametersBuilder::default()
+                    .model("gpt-4o")
+                    .messages(vec![])
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().all(|chunk| chunk.is_ok()));
+
+        let events: Vec<_> = client
+            .responses()
+            .create_stream(
+                ResponseParametersBuilder::default()
+                    .model("gpt-4o")
+                    .input(ResponseInput::Text("Hello".to_string()))
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|event| event.is_ok()));
+    }
+
+    #[tokio::test]
+    async fn test_injected_errors_and_latency() {
+        let server = MockServer::start().await;
+
+        server
+            .mock(
+                Mock::get("/models/*")
+                    .respond_with(
+                        MockResponse::error(503, "Overloaded", "server_error", None)
+                            .header("retry-after-ms", "1"),
+                    )
+                    .times(2),
+            )
+            .mock(
+                Mock::get("/models/gpt-4o")
+                    .respond_with(MockResponse::json(serde_json::json!({
+                        "id": "gpt-4o",
+                        "object": "model",
+                        "created": 1,
+                        "owned_by": "mock"
+                    })))
+                    .delay(Duration::from_millis(50)),
+            );
+
+        let mut client = server.client();
+        client.set_retry_policy(RetryPolicyBuilder::default().build().unwrap());
+
+        let started = Instant::now();
+        let model = client.models().get("gpt-4o").await.unwrap();
+
+        assert_eq!(model.owned_by, "mock");
+        assert!(started.elapsed() >= Duration::from_millis(50));
+
+        let requests = server.received_requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].path, "/models/gpt-4o");
+        assert_eq!(requests[0].headers["authorization"], "Bearer sk-test");
+    }
+}
@@ -0,0 +1,484 @@
+use crate::v1::error::APIError;
+use crate::v1::resources::chat::{
+    ChatCompletionParameters, ChatCompletionTool, ChatMessage, ChatMessageContent,
+    ChatMessageContentPart, ImageUrlDetail,
+};
+use crate::v1::resources::embedding::EmbeddingInput;
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::Value;
+use std::fmt::{Debug, Formatter};
+use std::path::Path;
+use std::sync::Arc;
+use tiktoken_rs::{CoreBPE, Rank};
+
+/// The tokens added to every message for its role and separators.
+const TOKENS_PER_MESSAGE: usize = 3;
+/// The tokens added when a message has a name.
+const TOKENS_PER_NAME: usize = 1;
+/// The tokens that prime the reply of the assistant.
+const TOKENS_PER_REPLY: usize = 3;
+
+/// A byte pair encoding used by the OpenAI models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// The encoding of the GPT-4o, GPT-4.1, GPT-5 and o-series models.
+    O200kBase,
+    /// The encoding of the GPT-4, GPT-3.5 Turbo and `text-embedding-*` models.
+    Cl100kBase,
+}
+
+impl Encoding {
+    /// Returns the encoding used by a model, defaulting to `O200kBase` for unknown models.
+    pub fn for_model(model: &str) -> Self {
+        const O200K_PREFIXES: [&str; 6] = [
+            "gpt-4o",
+            "gpt-4.1",
+            "gpt-4.5",
+            "chatgpt-4o",
+            "ft:gpt-4o",
+            "ft:gpt-4.1",
+        ];
+        const CL100K_PREFIXES: [&str; 8] = [
+            "gpt-4",
+            "gpt-3.5",
+            "gpt-35",
+            "text-embedding-",
+            "ft:gpt-4",
+            "ft:gpt-3.5",
+            "davinci-002",
+            "babbage-002",
+        ];
+
+        if O200K_PREFIXES
+            .iter()
+            .any(|prefix| model.starts_with(prefix))
+        {
+            Encoding::O200kBase
+        } else if CL100K_PREFIXES
+            .iter()
+            .any(|prefix| model.starts_with(prefix))
+        {
+            Encoding::Cl100kBase
+        } else {
+            Encoding::O200kBase
+        }
+    }
+
+    fn pattern(&self) -> String {
+        match self {
+            Encoding::O200kBase => [
+                "[^\\r\\n\\p{L}\\p{N}]?[\\p{Lu}\\p{Lt}\\p{Lm}\\p{Lo}\\p{M}]*[\\p{Ll}\\p{Lm}\\p{Lo}\\p{M}]+(?i:'s|'t|'re|'ve|'m|'ll|'d)?",
+                "[^\\r\\n\\p{L}\\p{N}]?[\\p{Lu}\\p{Lt}\\p{Lm}\\p{Lo}\\p{M}]+[\\p{Ll}\\p{Lm}\\p{Lo}\\p{M}]*(?i:'s|'t|'re|'ve|'m|'ll|'d)?",
+                "\\p{N}{1,3}",
+                " ?[^\\s\\p{L}\\p{N}]+[\\r\\n/]*",
+                "\\s*[\\r\\n]+",
+                "\\s+(?!\\S)",
+                "\\s+",
+            ]
+            .join("|"),
+            Encoding::Cl100kBase => "(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\\r\\n\\p{L}\\p{N}]?\\p{L}+|\\p{N}{1,3}| ?[^\\s\\p{L}\\p{N}]+[\\r\\n]*|\\s*[\\r\\n]+|\\s+(?!\\S)|\\s+".to_string(),
+        }
+    }
+
+    fn special_tokens(&self) -> Vec<(String, Rank)> {
+        let tokens: &[(&str, Rank)] = match self {
+            Encoding::O200kBase => &[("<|endoftext|>", 199999), ("<|endofprompt|>", 200018)],
+            Encoding::Cl100kBase => &[
+                ("<|endoftext|>", 100257),
+                ("<|fim_prefix|>", 100258),
+                ("<|fim_middle|>", 100259),
+                ("<|fim_suffix|>", 100260),
+                ("<|endofprompt|>", 100276),
+            ],
+        };
+
+        tokens
+            .iter()
+            .map(|(token, rank)| (token.to_string(), *rank))
+            .collect()
+    }
+
+    /// The fixed token costs of function definitions, as (per function, per property, end of the definitions).
+    fn function_overhead(&self) -> (usize, usize, usize) {
+        match self {
+            Encoding::O200kBase => (7, 3, 12),
+            Encoding::Cl100kBase => (10, 3, 12),
+        }
+    }
+}
+
+/// Counts tokens offline, to estimate the size of a request before sending it.
+///
+/// The counts for chat messages and tool definitions follow the way the API formats them, so they are close
+/// to, but not guaranteed to match, the `prompt_tokens` reported in the usage.
+#[derive(Clone)]
+pub struct Tokenizer {
+    encoding: Encoding,
+    bpe: Bpe,
+}
+
+#[derive(Clone)]
+enum Bpe {
+    Embedded(&'static CoreBPE),
+    Loaded(Arc<CoreBPE>),
+}
+
+impl Tokenizer {
+    /// Creates a tokenizer with the embedded vocabulary of an encoding.
+    pub fn new(encoding: Encoding) -> Self {
+        let bpe = match encoding {
+            Encoding::O200kBase => tiktoken_rs::o200k_base_singleton(),
+            Encoding::Cl100kBase => tiktoken_rs::cl100k_base_singleton(),
+        };
+
+        Self {
+            encoding,
+            bpe: Bpe::Embedded(bpe),
+        }
+    }
+
+    /// Creates a tokenizer with the embedded vocabulary of the encoding used by a model.
+    pub fn for_model(model: &str) -> Self {
+        Self::new(Encoding::for_model(model))
+    }
+
+    /// Creates a tokenizer with the vocabulary of an encoding loaded from a local `.tiktoken` file.
+    pub fn from_file<P: AsRef<Path>>(encoding: Encoding, path: P) -> Result<Self, APIError> {
+        let vocabulary = std::fs::read_to_string(path.as_ref()).map_err(|error| {
+            APIError::FileError(format!(
+                "failed to read {}: {error}",
+                path.as_ref().display()
+            ))
+        })?;
+
+        let encoder = vocabulary
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let (token, rank) = line.split_once(' ').ok_or_else(|| {
+                    APIError::ParseError(format!("invalid vocabulary line: {line}"))
+                })?;
+                let token = general_purpose::STANDARD
+                    .decode(token)
+                    .map_err(|error| APIError::ParseError(error.to_string()))?;
+                let rank = rank
+                    .trim()
+                    .parse::<Rank>()
+                    .map_err(|error| APIError::ParseError(error.to_string()))?;
+
+                Ok((token, rank))
+            })
+            .collect::<Result<_, APIError>>()?;
+
+        let bpe = CoreBPE::new(
+            encoder,
+            encoding.special_tokens().into_iter().collect(),
+            &encoding.pattern(),
+        )
+        .map_err(|error| APIError::ParseError(error.to_string()))?;
+
+        Ok(Self {
+            encoding,
+            bpe: Bpe::Loaded(Arc::new(bpe)),
+        })
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Encodes text into tokens. Special tokens in the text are encoded as plain text.
+    pub fn encode(&self, text: &str) -> Vec<u32> {
+        self.bpe().encode_ordinary(text)
+    }
+
+    /// Decodes tokens into text.
+    pub fn decode(&self, tokens: &[u32]) -> Result<String, APIError> {
+        self.bpe()
+            .decode(tokens.to_vec())
+            .map_err(|error| APIError::ParseError(error.to_string()))
+    }
+
+    /// Returns the number of tokens in the text.
+    pub fn count(&self, text: &str) -> usize {
+        self.encode(text).len()
+    }
+
+    /// Encodes text into the `EmbeddingInput::IntegerArray` form.
+    pub fn embedding_input(&self, text: &str) -> EmbeddingInput {
+        EmbeddingInput::IntegerArray(self.encode(text))
+    }
+
+    /// Returns the number of tokens in an embedding input.
+    pub fn count_embedding_input(&self, input: &EmbeddingInput) -> usize {
+        match input {
+            EmbeddingInput::String(text) => self.count(text),
+            EmbeddingInput::StringArray(texts) => texts.iter().map(|text| self.count(text)).sum(),
+            EmbeddingInput::IntegerArray(tokens) => tokens.len(),
+            EmbeddingInput::IntegerArrayArray(tokens) => tokens.iter().map(Vec::len).sum(),
+        }
+    }
+
+    /// Returns the number of prompt tokens of the messages, including the per-message overhead and the
+    /// tokens that prime the reply.
+    ///
+    /// Images are counted by their detail, assuming a 1024x1024 image for `high` and `auto` (see `image_tokens`).
+    /// Audio input is not counted.
+    pub fn count_messages(&self, messages: &[ChatMessage]) -> usize {
+        messages
+            .iter()
+            .map(|message| self.count_message(message))
+            .sum::<usize>()
+            + TOKENS_PER_REPLY
+    }
+
+    /// Returns the number of prompt tokens of the function definitions of the tools.
+    pub fn count_tools(&self, tools: &[ChatCompletionTool]) -> usize {
+        if tools.is_empty() {
+            return 0;
+        }
+
+        let (per_function, per_property, end) = self.encoding.function_overhead();
+
+        tools
+            .iter()
+            .map(|tool| {
+                let function = &tool.function;
+                let description = function.description.as_deref().unwrap_or_default();
+
+                let mut tokens = per_function
+                    + self.count(&format!(
+                        "{}:{}",
+                        function.name,
+                        description.trim_end_matches('.')
+                    ));
+
+                if let Some(properties) = function
+                    .parameters
+                    .get("properties")
+                    .and_then(Value::as_object)
+                    .filter(|properties| !properties.is_empty())
+                {
+                    tokens += per_property;
+
+                    for (name, property) in properties {
+                        tokens += per_property;
+
+                        if let Some(values) = property.get("enum").and_then(Value::as_array) {
+                            // The enum replaces part of the property overhead.
+                            tokens -= per_property;
+
+                            for value in values {
+                                tokens += per_property + self.count(&json_text(value));
+                            }
+                        }
+
+                        let kind = property.get("type").map(json_text).unwrap_or_default();
+                        let description = property
+                            .get("description")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default();
+
+                        tokens += self.count(&format!(
+                            "{name}:{kind}:{}",
+                            description.trim_end_matches('.')
+                        ));
+                    }
+                }
+
+                tokens
+            })
+            .sum::<usize>()
+            + end
+    }
+
+    /// Returns the number of prompt tokens of the messages and tools of a chat completion request.
+    pub fn count_parameters(&self, parameters: &ChatCompletionParameters) -> usize {
+        self.count_messages(&parameters.messages)
+            + parameters
+                .tools
+                .as_deref()
+                .map(|tools| self.count_tools(tools))
+                .unwrap_or_default()
+    }
+
+    fn count_message(&self, message: &ChatMessage) -> usize {
+        let (role, content, name) = match message {
+            ChatMessage::Developer { content, name } => ("developer", Some(content), name),
+            ChatMessage::System { content, name } => ("system", Some(content), name),
+            ChatMessage::User { content, name } => ("user", Some(content), name),
+            ChatMessage::Assistant { content, name, .. } => ("assistant", content.as_ref(), name),
+            ChatMessage::Tool { content, .. } => ("tool", Some(content), &None),
+        };
+
+        let mut tokens = TOKENS_PER_MESSAGE + self.count(role);
+
+        if let Some(content) = content {
+            tokens += self.count_content(content);
+        }
+
+        if let Some(name) = name {
+            tokens += TOKENS_PER_NAME + self.count(name);
+        }
+
+        match message {
+            ChatMessage::Assistant {
+                refusal,
+                tool_calls,
+                ..
+            } => {
+                tokens += refusal
+                    .as_deref()
+                    .map(|refusal| self.count(refusal))
+                    .unwrap_or_default();
+
+                for tool_call in tool_calls.iter().flatten() {
+                    tokens += self.count(&tool_call.function.name)
+                        + self.count(&tool_call.function.arguments);
+                }
+            }
+            ChatMessage::Tool { tool_call_id, .. } => tokens += self.count(tool_call_id),
+            _ => {}
+        }
+
+        tokens
+    }
+
+    fn count_content(&self, content: &ChatMessageContent) -> usize {
+        match content {
+            ChatMessageContent::Text(text) => self.count(text),
+            ChatMessageContent::ContentPart(parts) => parts
+                .iter()
+                .map(|part| match part {
+                    ChatMessageContentPart::Text(part) => self.count(&part.text),
+                    ChatMessageContentPart::Image(part) => image_tokens(
+                        1024,
+                        1024,
+                        part.image_url
+                            .detail
+                            .as_ref()
+                            .unwrap_or(&ImageUrlDetail::Auto),
+                    ),
+                    ChatMessageContentPart::Audio(_) => 0,
+                })
+                .sum(),
+            ChatMessageContent::None => 0,
+        }
+    }
+
+    fn bpe(&self) -> &CoreBPE {
+        match &self.bpe {
+            Bpe::Embedded(bpe) => bpe,
+            Bpe::Loaded(bpe) => bpe,
+        }
+    }
+}
+
+impl Debug for Tokenizer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tokenizer")
+            .field("encoding", &self.encoding)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the number of tokens of an image input with the given size and detail.
+///
+/// A `low` detail image costs a fixed 85 tokens. Otherwise the image is scaled to fit within 2048x2048 and then
+/// down to 768px on its shortest side, and every 512px tile costs 170 tokens on top of the base 85.
+pub fn image_tokens(width: u32, height: u32, detail: &ImageUrlDetail) -> usize {
+    const BASE_TOKENS: usize = 85;
+    const TILE_TOKENS: usize = 170;
+
+    if *detail == ImageUrlDetail::Low || width == 0 || height == 0 {
+        return BASE_TOKENS;
+    }
+
+    let (mut width, mut height) = (width as f64, height as f64);
+
+    let scale = (2048.0 / width.max(height)).min(1.0);
+    (width, height) = (width * scale, height * scale);
+
+    let scale = (768.0 / width.min(height)).min(1.0);
+    (width, height) = (width * scale, height * scale);
+
+    let tiles = (width / 512.0).ceil() as usize * (height / 512.0).ceil() as usize;
+
+    BASE_TOKENS + TILE_TOKENS * tiles
+}
+
+fn json_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::resources::chat::{
+        ChatCompletionFunction, ChatCompletionTool, ChatCompletionToolType, ChatMessage,
+        ChatMessageContent, ImageUrlDetail,
+    };
+    use crate::v1::resources::embedding::EmbeddingInput;
+    use crate::v1::tokenizer::{image_tokens, Encoding, Tokenizer};
+    use serde_json::json;
+
+    #[test]
+    fn test_tokenizer_encodes_text() {
+        let tokenizer = Tokenizer::for_model("gpt-4o");
+        assert_eq!(tokenizer.encoding(), Encoding::O200kBase);
+
+        let tokens = tokenizer.encode("hello world");
+        assert_eq!(tokens, vec![24912, 2375]);
+        assert_eq!(tokenizer.decode(&tokens).unwrap(), "hello world");
+
+        let tokenizer = Tokenizer::for_model("text-embedding-3-small");
+        assert_eq!(tokenizer.encoding(), Encoding::Cl100kBase);
+        assert_eq!(
+            tokenizer.embedding_input("hello world"),
+            EmbeddingInput::IntegerArray(vec![15339, 1917])
+        );
+    }
+
+    #[test]
+    fn test_tokenizer_counts_messages_and_tools() {
+        let tokenizer = Tokenizer::new(Encoding::O200kBase);
+
+        let messages = vec![ChatMessage::User {
+            content: ChatMessageContent::Text("hello world".to_string()),
+            name: None,
+        }];
+        // 3 per message, 1 for the role, 2 for the content and 3 to prime the reply.
+        assert_eq!(tokenizer.count_messages(&messages), 9);
+
+        let tools = vec![ChatCompletionTool {
+            r#type: ChatCompletionToolType::Function,
+            function: ChatCompletionFunction {
+                name: "get_weather".to_string(),
+                description: Some("Get the weather.".to_string()),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "unit": {"type": "string", "enum": ["celsius", "fahrenheit"]}
+                    }
+                }),
+                strict: None,
+            },
+        }];
+        let expected = 7
+            + tokenizer.count("get_weather:Get the weather")
+            + 3
+            + 3
+            + tokenizer.count("celsius")
+            + 3
+            + tokenizer.count("fahrenheit")
+            + tokenizer.count("unit:string:")
+            + 12;
+        assert_eq!(tokenizer.count_tools(&tools), expected);
+
+        assert_eq!(image_tokens(1024, 1024, &ImageUrlDetail::Low), 85);
+        assert_eq!(image_tokens(1024, 1024, &ImageUrlDetail::High), 765);
+        assert_eq!(image_tokens(2048, 4096, &ImageUrlDetail::High), 1105);
+    }
+}
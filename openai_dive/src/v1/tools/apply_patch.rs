@@ -0,0 +1,426 @@
+use crate::v1::resources::response::items::{
+    ApplyPatchCall, ApplyPatchCallOutput, ApplyPatchOperation, ApplyPatchStatus,
+};
+use crate::v1::resources::response::request::ResponseInputItem;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Applies the operations of `apply_patch` calls to the files below a root directory.
+///
+/// Paths are relative to the root. Paths that leave the root or go through a symlink are rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApplyPatchExecutor {
+    root: PathBuf,
+}
+
+impl ApplyPatchExecutor {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Applies the operation of an `apply_patch` call and returns the output item to send back to the model.
+    pub fn execute(&self, call: &ApplyPatchCall) -> ResponseInputItem {
+        let (status, output) = match self.apply_operation(&call.operation) {
+            Ok(output) => (ApplyPatchStatus::Completed, output),
+            Err(error) => (ApplyPatchStatus::Failed, format!("Error: {error}")),
+        };
+
+        ResponseInputItem::ApplyPatchCallOutput(ApplyPatchCallOutput {
+            id: None,
+            call_id: call.call_id.clone(),
+            status,
+            output: Some(output),
+        })
+    }
+
+    /// Creates, updates or deletes a file, returning a short description of the change.
+    pub fn apply_operation(&self, operation: &ApplyPatchOperation) -> Result<String, String> {
+        match operation {
+            ApplyPatchOperation::CreateFile { path, diff } => {
+                let file = resolve_path(&self.root, path)?;
+                let content = diff
+                    .lines()
+                    .map(|line| line.strip_prefix('+').unwrap_or(line))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                if let Some(parent) = file.parent() {
+                    fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+                }
+
+                // Like `*** Add File` in a V4A patch, creating a file that already exists fails
+                let mut created = fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&file)
+                    .map_err(|error| match error.kind() {
+                        ErrorKind::AlreadyExists => format!("`{path}` already exists"),
+                        _ => error.to_string(),
+                    })?;
+                created
+                    .write_all((content + "\n").as_bytes())
+                    .map_err(|error| error.to_string())?;
+
+                Ok(format!("Created {path}"))
+            }
+            ApplyPatchOperation::UpdateFile { path, diff } => {
+                let file = resolve_path(&self.root, path)?;
+                let original = fs::read_to_string(&file)
+                    .map_err(|error| format!("could not read `{path}`: {error}"))?;
+
+                fs::write(&file, apply_diff(&original, diff)?)
+                    .map_err(|error| error.to_string())?;
+
+                Ok(format!("Updated {path}"))
+            }
+            ApplyPatchOperation::DeleteFile { path } => {
+                let file = resolve_path(&self.root, path)?;
+
+                fs::remove_file(&file)
+                    .map_err(|error| format!("could not delete `{path}`: {error}"))?;
+
+                Ok(format!("Deleted {path}"))
+            }
+        }
+    }
+
+    /// Applies a complete V4A patch, from `*** Begin Patch` to `*** End Patch`, see `parse_patch`.
+    ///
+    /// The operations are applied in order and the first failing operation stops the patch.
+    pub fn apply_patch(&self, patch: &str) -> Result<Vec<String>, String> {
+        parse_patch(patch)?
+            .iter()
+            .map(|operation| self.apply_operation(operation))
+            .collect()
+    }
+}
+
+/// Parses a V4A patch into its operations.
+///
+/// A patch starts with `*** Begin Patch`, ends with `*** End Patch` and contains `*** Add File: <path>`,
+/// `*** Update File: <path>` and `*** Delete File: <path>` sections.
+pub fn parse_patch(patch: &str) -> Result<Vec<ApplyPatchOperation>, String> {
+    let mut lines = patch.trim().lines();
+
+    if lines.next().map(str::trim) != Some("*** Begin Patch") {
+        return Err("the patch does not start with `*** Begin Patch`".to_string());
+    }
+
+    let mut operations = Vec::new();
+    let mut header = None;
+    let mut body: Vec<&str> = Vec::new();
+
+    for line in lines {
+        let end = line.trim() == "*** End Patch";
+        let section = [
+            ("add", "*** Add File: "),
+            ("update", "*** Update File: "),
+            ("delete", "*** Delete File: "),
+        ]
+        .into_iter()
+        .find_map(|(kind, prefix)| Some((kind, line.strip_prefix(prefix)?.trim())));
+
+        if !end && section.is_none() {
+            if header.is_none() {
+                return Err(format!(
+                    "unexpected line outside of a file section: `{line}`"
+                ));
+            }
+            body.push(line);
+            continue;
+        }
+
+        if let Some((kind, path)) = header.take() {
+            operations.push(operation(kind, path, &body)?);
+            body.clear();
+        }
+
+        if end {
+            return Ok(operations);
+        }
+        header = section;
+    }
+
+    Err("the patch does not end with `*** End Patch`".to_string())
+}
+
+fn operation(kind: &str, path: &str, body: &[&str]) -> Result<ApplyPatchOperation, String> {
+    let path = path.to_string();
+    let diff = body.join("\n");
+
+    Ok(match kind {
+        "add" => ApplyPatchOperation::CreateFile { path, diff },
+        "update" => {
+            if body.iter().any(|line| line.starts_with("*** Move to: ")) {
+                return Err(format!("moving `{path}` is not supported"));
+            }
+            ApplyPatchOperation::UpdateFile { path, diff }
+        }
+        _ => ApplyPatchOperation::DeleteFile { path },
+    })
+}
+
+/// A section of a V4A diff: the lines to find, optionally after an `@@` anchor line, and their replacement.
+#[derive(Default)]
+struct Chunk<'a> {
+    anchor: Option<&'a str>,
+    old: Vec<&'a str>,
+    new: Vec<&'a str>,
+    end_of_file: bool,
+}
+
+/// Applies a V4A diff to the content of a file.
+///
+/// Sections start with an `@@` line, optionally followed by a line to search for first, and contain context
+/// lines (prefixed with a space), removed lines (`-`) and added lines (`+`). Lines are matched exactly first,
+/// then ignoring trailing whitespace and then ignoring surrounding whitespace.
+pub fn apply_diff(original: &str, diff: &str) -> Result<String, String> {
+    let mut chunks = vec![Chunk::default()];
+
+    for line in diff.lines() {
+        let chunk = chunks.last_mut().expect("there is always a chunk");
+
+        if let Some(anchor) = line.strip_prefix("@@") {
+            let anchor = anchor.trim();
+            chunks.push(Chunk {
+                anchor: (!anchor.is_empty()).then_some(anchor),
+                ..Chunk::default()
+            });
+        } else if line.trim() == "*** End of File" {
+            chunk.end_of_file = true;
+        } else if let Some(line) = line.strip_prefix('+') {
+            chunk.new.push(line);
+        } else if let Some(line) = line.strip_prefix('-') {
+            chunk.old.push(line);
+        } else {
+            let line = line.strip_prefix(' ').unwrap_or(line);
+            chunk.old.push(line);
+            chunk.new.push(line);
+        }
+    }
+
+    let trailing_newline = original.ends_with('\n') || original.is_empty();
+    let mut lines: Vec<String> = original
+        .strip_suffix('\n')
+        .unwrap_or(original)
+        .split('\n')
+        .map(str::to_string)
+        .collect();
+    if original.is_empty() {
+        lines.clear();
+    }
+
+    let mut cursor = 0;
+
+    for chunk in chunks {
+        if chunk.anchor.is_none() && chunk.old.is_empty() && chunk.new.is_empty() {
+            continue;
+        }
+
+        if let Some(anchor) = chunk.anchor {
+            let index = lines[cursor..]
+                .iter()
+                .position(|line| line.trim() == anchor)
+                .ok_or_else(|| format!("could not find `{anchor}`"))?;
+            cursor += index + 1;
+        }
+
+        let position = if chunk.old.is_empty() {
+            if chunk.end_of_file {
+                lines.len()
+            } else {
+                cursor
+            }
+        } else {
+            find_lines(&lines, &chunk.old, cursor, chunk.end_of_file).ok_or_else(|| {
+                format!(
+                    "could not find the lines to replace:\n{}",
+                    chunk.old.join("\n")
+                )
+            })?
+        };
+
+        lines.splice(
+            position..position + chunk.old.len(),
+            chunk.new.iter().map(|line| line.to_string()),
+        );
+        cursor = position + chunk.new.len();
+    }
+
+    let mut content = lines.join("\n");
+    if trailing_newline && !content.is_empty() {
+        content.push('\n');
+    }
+
+    Ok(content)
+}
+
+/// Finds `needle` in `lines` from `start`, first exactly and then with less strict comparisons.
+fn find_lines(lines: &[String], needle: &[&str], start: usize, end_of_file: bool) -> Option<usize> {
+    let comparisons: [fn(&str, &str) -> bool; 3] = [
+        |a, b| a == b,
+        |a, b| a.trim_end() == b.trim_end(),
+        |a, b| a.trim() == b.trim(),
+    ];
+
+    if needle.len() > lines.len() {
+        return None;
+    }
+    let last = lines.len() - needle.len();
+
+    comparisons.iter().find_map(|matches| {
+        let mut candidates: Box<dyn Iterator<Item = usize>> = if end_of_file {
+            Box::new((start..=last).rev())
+        } else {
+            Box::new(start..=last)
+        };
+
+        candidates.find(|&index| {
+            needle
+                .iter()
+                .zip(&lines[index..])
+                .all(|(expected, line)| matches(line, expected))
+        })
+    })
+}
+
+/// Resolves a path of a tool call inside `root`, rejecting paths that leave it.
+pub(crate) fn resolve_path(root: &Path, path: &str) -> Result<PathBuf, String> {
+    let outside = || format!("`{path}` is outside of the root directory");
+
+    let root = root
+        .canonicalize()
+        .map_err(|error| format!("invalid root directory: {error}"))?;
+
+    let relative = Path::new(path);
+    let relative = if relative.is_absolute() {
+        relative.strip_prefix(&root).map_err(|_| outside())?
+    } else {
+        relative
+    };
+
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(outside());
+    }
+
+    // A symlink below the root can point outside of it, even when its target does not exist yet, so every
+    // existing component is checked without following links.
+    let mut resolved = root.clone();
+    for component in relative.components() {
+        resolved.push(component);
+
+        match fs::symlink_metadata(&resolved) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                return Err(format!("`{path}` contains a symbolic link"));
+            }
+            Ok(_) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => break,
+            Err(error) => return Err(error.to_string()),
+        }
+    }
+
+    let resolved = root.join(relative);
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::resources::response::items::{
+        ApplyPatchCall, ApplyPatchOperation, ApplyPatchStatus,
+    };
+    use crate::v1::resources::response::request::ResponseInputItem;
+    use crate::v1::tools::apply_patch::{apply_diff, ApplyPatchExecutor};
+    use std::fs;
+
+    #[test]
+    fn test_apply_diff() {
+        let original = "fn main() {\n    let a = 1;\n    println!(\"{a}\");\n}\n\nfn other() {\n    let a = 1;\n}\n";
+        let diff = "@@ fn other() {\n-    let a = 1;\n+    let a = 2;\n@@\n }\n+\n+fn added() {}\n*** End of File";
+
+        assert_eq!(
+            apply_diff(original, diff).unwrap(),
+            "fn main() {\n    let a = 1;\n    println!(\"{a}\");\n}\n\nfn other() {\n    let a = 2;\n}\n\nfn added() {}\n"
+        );
+        assert!(apply_diff(original, "@@\n-    let b = 1;").is_err());
+    }
+
+    #[test]
+    fn test_apply_patch_executor() {
+        let root = std::env::temp_dir().join(format!("openai_dive_patch_{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("hello.txt"), "Hello\nworld\n").unwrap();
+        fs::write(root.join("old.txt"), "old\n").unwrap();
+
+        let executor = ApplyPatchExecutor::new(&root);
+        let patch = "*** Begin Patch\n*** Add File: src/new.txt\n+first\n+second\n*** Update File: hello.txt\n@@\n Hello\n-world\n+there\n*** Delete File: old.txt\n*** End Patch\n";
+
+        assert_eq!(executor.apply_patch(patch).unwrap().len(), 3);
+        assert_eq!(
+            fs::read_to_string(root.join("src/new.txt")).unwrap(),
+            "first\nsecond\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("hello.txt")).unwrap(),
+            "Hello\nthere\n"
+        );
+        assert!(!root.join("old.txt").exists());
+
+        let result = executor.apply_operation(&ApplyPatchOperation::CreateFile {
+            path: "hello.txt".to_string(),
+            diff: "+replaced".to_string(),
+        });
+        assert_eq!(result, Err("`hello.txt` already exists".to_string()));
+        assert_eq!(
+            fs::read_to_string(root.join("hello.txt")).unwrap(),
+            "Hello\nthere\n"
+        );
+
+        let output = executor.execute(&ApplyPatchCall {
+            id: None,
+            call_id: "call_1".to_string(),
+            operation: ApplyPatchOperation::CreateFile {
+                path: "../escape.txt".to_string(),
+                diff: "+nope".to_string(),
+            },
+            status: None,
+        });
+        let ResponseInputItem::ApplyPatchCallOutput(output) = output else {
+            panic!("expected an apply_patch_call_output");
+        };
+        assert_eq!(output.status, ApplyPatchStatus::Failed);
+        assert!(!root.parent().unwrap().join("escape.txt").exists());
+
+        #[cfg(unix)]
+        {
+            let outside = root.with_extension("outside");
+            std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+            let ResponseInputItem::ApplyPatchCallOutput(output) =
+                executor.execute(&ApplyPatchCall {
+                    id: None,
+                    call_id: "call_2".to_string(),
+                    operation: ApplyPatchOperation::CreateFile {
+                        path: "link".to_string(),
+                        diff: "+nope".to_string(),
+                    },
+                    status: None,
+                })
+            else {
+                panic!("expected an apply_patch_call_output");
+            };
+            assert_eq!(output.status, ApplyPatchStatus::Failed);
+            assert!(!outside.exists());
+        }
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
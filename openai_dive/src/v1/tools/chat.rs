@@ -0,0 +1,342 @@
+use crate::v1::api::Client;
+#[cfg(feature = "stream")]
+use crate::v1::endpoints::chat::AccumulatingStream;
+#[cfg(feature = "stream")]
+use crate::v1::resources::chat::ChatCompletionChunkResponse;
+use crate::v1::resources::chat::{
+    ChatCompletionFunction, ChatCompletionParameters, ChatCompletionResponse, ChatCompletionTool,
+    ChatCompletionToolType, ChatMessage, ChatMessageContent,
+};
+use crate::v1::tools::{call_tool, handler, ToolHandler, ToolRunError};
+#[cfg(feature = "stream")]
+use futures::StreamExt;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+
+/// Runs a chat completion, calls the requested tools and sends their output back to the model until it
+/// answers without calling a tool.
+pub struct ToolRunner<'a> {
+    client: &'a Client,
+    functions: Vec<ChatCompletionFunction>,
+    handlers: HashMap<String, ToolHandler>,
+    max_iterations: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolRunResult {
+    /// The last response, which contains no tool calls unless the run reached its iteration limit.
+    pub response: ChatCompletionResponse,
+    /// The messages of the conversation, including the assistant messages and tool outputs added during the run.
+    pub messages: Vec<ChatMessage>,
+    /// The number of chat completions created during the run.
+    pub iterations: usize,
+}
+
+impl<'a> ToolRunner<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self {
+            client,
+            functions: Vec::new(),
+            handlers: HashMap::new(),
+            max_iterations: 10,
+        }
+    }
+
+    /// Registers a function the model can call, with the handler that is called with its parsed arguments.
+    pub fn register<F, Fut>(&mut self, function: ChatCompletionFunction, handler_fn: F) -> &mut Self
+    where
+        F: Fn(Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, String>> + Send + 'static,
+    {
+        self.handlers
+            .insert(function.name.clone(), handler(handler_fn));
+        self.functions
+            .retain(|existing| existing.name != function.name);
+        self.functions.push(function);
+
+        self
+    }
+
+    /// Sets the maximum number of chat completions created during a run, defaults to 10.
+    ///
+    /// A run that reaches it stops with `ToolRunError::MaxIterations`, which contains the messages of the run so far.
+    pub fn set_max_iterations(&mut self, max_iterations: usize) -> &mut Self {
+        self.max_iterations = max_iterations;
+
+        self
+    }
+
+    /// The tool definitions of the registered functions.
+    pub fn tools(&self) -> Vec<ChatCompletionTool> {
+        self.functions
+            .iter()
+            .map(|function| ChatCompletionTool {
+                r#type: ChatCompletionToolType::Function,
+                function: function.clone(),
+            })
+            .collect()
+    }
+
+    /// Runs the conversation in `parameters` until the model answers without calling a tool.
+    pub async fn run(
+        &self,
+        parameters: ChatCompletionParameters,
+    ) -> Result<ToolRunResult, ToolRunError<ToolRunResult>> {
+        let mut messages = parameters.messages.clone();
+        let mut iteration = 0;
+
+        loop {
+            iteration += 1;
+            let response = self
+                .client
+                .chat()
+                .create(self.parameters(&parameters, &messages))
+                .await?;
+
+            if let Some(result) = self
+                .handle_response(response, &mut messages, iteration)
+                .await
+            {
+                return result;
+            }
+        }
+    }
+
+    /// Runs the conversation in `parameters` like `run`, streaming every chat completion and passing each chunk to `on_chunk`.
+    #[cfg(feature = "stream")]
+    pub async fn run_stream<F>(
+        &self,
+        parameters: ChatCompletionParameters,
+        mut on_chunk: F,
+    ) -> Result<ToolRunResult, ToolRunError<ToolRunResult>>
+    where
+        F: FnMut(&ChatCompletionChunkResponse),
+    {
+        let mut messages = parameters.messages.clone();
+        let mut iteration = 0;
+
+        loop {
+            iteration += 1;
+            let mut stream = AccumulatingStream::new(
+                self.client
+                    .chat()
+                    .create_stream(self.parameters(&parameters, &messages))
+                    .await?,
+            );
+
+            while let Some(chunk) = stream.next().await {
+                on_chunk(&chunk?);
+            }
+
+            if let Some(result) = self
+                .handle_response(stream.response(), &mut messages, iteration)
+                .await
+            {
+                return result;
+            }
+        }
+    }
+
+    fn parameters(
+        &self,
+        parameters: &ChatCompletionParameters,
+        messages: &[ChatMessage],
+    ) -> ChatCompletionParameters {
+        let mut tools = parameters.tools.clone().unwrap_or_default();
+        tools.extend(self.tools());
+
+        ChatCompletionParameters {
+            messages: messages.to_vec(),
+            tools: Some(tools),
+            ..parameters.clone()
+        }
+    }
+
+    /// Appends the assistant message and the output of its tool calls to `messages`, or returns the result
+    /// when the model did not call a tool or the run reached its iteration limit.
+    async fn handle_response(
+        &self,
+        response: ChatCompletionResponse,
+        messages: &mut Vec<ChatMessage>,
+        iteration: usize,
+    ) -> Option<Result<ToolRunResult, ToolRunError<ToolRunResult>>> {
+        let message = response
+            .choices
+            .first()
+            .map(|choice| choice.message.clone());
+
+        let tool_calls = match &message {
+            Some(ChatMessage::Assistant {
+                tool_calls: Some(tool_calls),
+                ..
+            }) if !tool_calls.is_empty() => tool_calls.clone(),
+            _ => {
+                messages.extend(message);
+
+                return Some(Ok(ToolRunResult {
+                    response,
+                    messages: messages.clone(),
+                    iterations: iteration,
+                }));
+            }
+        };
+
+        messages.extend(message);
+
+        let outputs = futures::future::join_all(tool_calls.iter().map(|tool_call| {
+            call_tool(
+                self.handlers.get(&tool_call.function.name).cloned(),
+                &tool_call.function.name,
+                &tool_call.function.arguments,
+            )
+        }))
+        .await;
+
+        messages.extend(
+            tool_calls
+                .into_iter()
+                .zip(outputs)
+                .map(|(tool_call, output)| ChatMessage::Tool {
+                    content: ChatMessageContent::Text(output),
+                    tool_call_id: tool_call.id,
+                }),
+        );
+
+        if iteration >= self.max_iterations {
+            return Some(Err(ToolRunError::MaxIterations(Box::new(ToolRunResult {
+                response,
+                messages: messages.clone(),
+                iterations: iteration,
+            }))));
+        }
+
+        None
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::v1::resources::chat::{
+        ChatCompletionFunction, ChatCompletionParameters, ChatCompletionParametersBuilder,
+        ChatMessage, ChatMessageContent,
+    };
+    use crate::v1::testing::{Mock, MockResponse, MockServer};
+    use crate::v1::tools::chat::ToolRunner;
+    use crate::v1::tools::ToolRunError;
+    use serde_json::{json, Value};
+    use std::time::Duration;
+
+    fn tool_calls_response() -> Value {
+        json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [
+                        {"id": "call_1", "type": "function", "function": {"name": "add", "arguments": "{\"a\": 1, \"b\": 2}"}},
+                        {"id": "call_2", "type": "function", "function": {"name": "add", "arguments": "{\"a\": 3, \"b\": 4}"}},
+                        {"id": "call_3", "type": "function", "function": {"name": "subtract", "arguments": "{}"}}
+                    ]
+                },
+                "finish_reason": "tool_calls"
+            }]
+        })
+    }
+
+    fn runner_parameters() -> ChatCompletionParameters {
+        ChatCompletionParametersBuilder::default()
+            .model("gpt-4o")
+            .messages(vec![ChatMessage::User {
+                content: ChatMessageContent::Text("What is 1 + 2 and 3 + 4?".to_string()),
+                name: None,
+            }])
+            .build()
+            .unwrap()
+    }
+
+    fn add_function() -> ChatCompletionFunction {
+        ChatCompletionFunction {
+            name: "add".to_string(),
+            description: Some("Adds two numbers".to_string()),
+            parameters: json!({
+                "type": "object",
+                "properties": {"a": {"type": "integer"}, "b": {"type": "integer"}},
+                "required": ["a", "b"]
+            }),
+            strict: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_runner_calls_tools_until_final_answer() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/chat/completions")
+                .respond_with(MockResponse::json(tool_calls_response()))
+                .times(1),
+        );
+
+        let client = server.client();
+        let mut runner = ToolRunner::new(&client);
+        runner.register(add_function(), |arguments| async move {
+            // The slower first call checks that the outputs keep the order of the tool calls.
+            if arguments["a"] == 1 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+
+            Ok(json!(
+                arguments["a"].as_i64().unwrap() + arguments["b"].as_i64().unwrap()
+            ))
+        });
+
+        let result = runner.run(runner_parameters()).await.unwrap();
+
+        assert_eq!(result.iterations, 2);
+        assert_eq!(result.messages.len(), 6);
+        assert_eq!(result.messages[2].text(), Some("3"));
+        assert_eq!(result.messages[3].text(), Some("7"));
+        assert_eq!(
+            result.messages[4].text(),
+            Some("Error: unknown tool `subtract`")
+        );
+
+        let requests = server.received_requests();
+        let second = requests[1].json().unwrap();
+        assert_eq!(second["tools"][0]["function"]["name"], "add");
+        assert_eq!(second["messages"][2]["tool_call_id"], "call_1");
+    }
+
+    #[tokio::test]
+    async fn test_tool_runner_stops_at_max_iterations() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/chat/completions").respond_with(MockResponse::json(tool_calls_response())),
+        );
+
+        let client = server.client();
+        let mut runner = ToolRunner::new(&client);
+        runner
+            .register(add_function(), |_| async move { Ok(json!(0)) })
+            .set_max_iterations(2);
+
+        let Err(ToolRunError::MaxIterations(result)) = runner.run(runner_parameters()).await else {
+            panic!("expected the run to stop at the iteration limit");
+        };
+
+        assert_eq!(server.received_requests().len(), 2);
+        assert_eq!(result.iterations, 2);
+        assert_eq!(result.response.id, Some("chatcmpl-1".to_string()));
+        // The user message and, per completion, the assistant message and the three tool outputs.
+        assert_eq!(result.messages.len(), 9);
+        assert!(matches!(
+            result.messages.last(),
+            Some(ChatMessage::Tool { .. })
+        ));
+    }
+}
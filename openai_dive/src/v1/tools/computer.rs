@@ -0,0 +1,560 @@
+use crate::v1::api::Client;
+use crate::v1::error::APIError;
+use crate::v1::resources::response::items::{
+    AcknowledgedSafetyCheck, ComputerAction, ComputerToolCall, ComputerToolCallOutput,
+    ComputerToolCallOutputOutput, Coordinate, InputItemStatus, MouseButton, PendingSafetyCheck,
+};
+use crate::v1::resources::response::request::{
+    ContentInput, InputMessage, ResponseInput, ResponseInputItem, ResponseParameters,
+};
+use crate::v1::resources::response::response::{ResponseObject, ResponseOutput, Role};
+use crate::v1::tools::{input_item, ToolRunError};
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+
+/// The computer or browser that executes the actions of a computer use model.
+///
+/// Errors are returned as a message and stop the run with a `ToolError`.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait ComputerEnvironment: Send {
+    /// Returns a PNG screenshot of the current screen.
+    async fn screenshot(&mut self) -> Result<Vec<u8>, String>;
+
+    async fn click(&mut self, x: i32, y: i32, button: MouseButton) -> Result<(), String>;
+
+    async fn double_click(&mut self, x: i32, y: i32) -> Result<(), String> {
+        self.click(x, y, MouseButton::Left).await?;
+        self.click(x, y, MouseButton::Left).await
+    }
+
+    async fn move_to(&mut self, _x: i32, _y: i32) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn type_text(&mut self, text: &str) -> Result<(), String>;
+
+    async fn scroll(&mut self, x: i32, y: i32, scroll_x: i32, scroll_y: i32) -> Result<(), String>;
+
+    async fn keypress(&mut self, keys: &[String]) -> Result<(), String>;
+
+    async fn drag(&mut self, path: &[Coordinate]) -> Result<(), String>;
+
+    /// Waits for the screen to update, e.g. after a page started loading.
+    async fn wait(&mut self) -> Result<(), String>;
+}
+
+type SafetyCheckCallback =
+    Box<dyn Fn(&ComputerToolCall, &[PendingSafetyCheck]) -> bool + Send + Sync>;
+
+/// Creates a model response with the computer use tool, executes the requested actions in a `ComputerEnvironment`
+/// and sends a screenshot back to the model until it answers without a computer call.
+pub struct ComputerRunner<'a, E> {
+    client: &'a Client,
+    environment: E,
+    on_safety_checks: Option<SafetyCheckCallback>,
+    max_iterations: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComputerRunResult {
+    /// The last response, which contains no computer calls unless the run reached its iteration limit.
+    pub response: ResponseObject,
+    /// The input items followed by the output items and screenshots added during the run.
+    pub items: Vec<ResponseInputItem>,
+    /// The number of responses created during the run.
+    pub iterations: usize,
+}
+
+impl<'a, E: ComputerEnvironment> ComputerRunner<'a, E> {
+    pub fn new(client: &'a Client, environment: E) -> Self {
+        Self {
+            client,
+            environment,
+            on_safety_checks: None,
+            max_iterations: 50,
+        }
+    }
+
+    /// Sets the callback that decides whether the pending safety checks of a computer call are acknowledged.
+    ///
+    /// Returning `false` stops the run with `ToolRunError::SafetyChecksNotAcknowledged`, which contains the items of
+    /// the run so far and the pending checks. Without a callback, safety checks are never acknowledged.
+    pub fn on_safety_checks<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: Fn(&ComputerToolCall, &[PendingSafetyCheck]) -> bool + Send + Sync + 'static,
+    {
+        self.on_safety_checks = Some(Box::new(callback));
+
+        self
+    }
+
+    /// Sets the maximum number of responses created during a run, defaults to 50.
+    ///
+    /// A run that reaches it stops with `ToolRunError::MaxIterations`, which contains the items of the run so far.
+    pub fn set_max_iterations(&mut self, max_iterations: usize) -> &mut Self {
+        self.max_iterations = max_iterations;
+
+        self
+    }
+
+    pub fn environment(&self) -> &E {
+        &self.environment
+    }
+
+    pub fn into_environment(self) -> E {
+        self.environment
+    }
+
+    /// Runs the input in `parameters`, which should contain a `ResponseTool::ComputerUse`, until the model answers
+    /// without a computer call.
+    pub async fn run(
+        &mut self,
+        parameters: ResponseParameters,
+    ) -> Result<ComputerRunResult, ToolRunError<ComputerRunResult>> {
+        let mut items = match &parameters.input {
+            ResponseInput::Text(text) => vec![ResponseInputItem::Message(InputMessage {
+                id: None,
+                status: None,
+                role: Role::User,
+                content: ContentInput::Text(text.clone()),
+            })],
+            ResponseInput::List(items) => items.clone(),
+        };
+        let mut request = parameters;
+        let mut iteration = 0;
+
+        loop {
+            iteration += 1;
+            let response = self.client.responses().create(request.clone()).await?;
+
+            items.extend(response.output.iter().filter_map(input_item));
+
+            let calls: Vec<ComputerToolCall> = response
+                .output
+                .iter()
+                .filter_map(|item| match item {
+                    ResponseOutput::ComputerToolCall(call) => Some(call.clone()),
+                    _ => None,
+                })
+                .collect();
+
+            let mut outputs = Vec::new();
+            for call in calls {
+                let acknowledged_safety_checks = match self.acknowledge(&call) {
+                    Ok(acknowledged) => acknowledged,
+                    Err(checks) => {
+                        items.extend(outputs);
+
+                        return Err(ToolRunError::SafetyChecksNotAcknowledged {
+                            result: Box::new(ComputerRunResult {
+                                response,
+                                items,
+                                iterations: iteration,
+                            }),
+                            call: Box::new(call),
+                            checks,
+                        });
+                    }
+                };
+
+                outputs.push(self.handle_call(&call, acknowledged_safety_checks).await?);
+            }
+
+            if outputs.is_empty() {
+                return Ok(ComputerRunResult {
+                    response,
+                    items,
+                    iterations: iteration,
+                });
+            }
+
+            items.extend(outputs.iter().cloned());
+
+            if iteration >= self.max_iterations {
+                return Err(ToolRunError::MaxIterations(Box::new(ComputerRunResult {
+                    response,
+                    items,
+                    iterations: iteration,
+                })));
+            }
+
+            request = ResponseParameters {
+                input: ResponseInput::List(outputs),
+                previous_response_id: Some(response.id),
+                ..request
+            };
+        }
+    }
+
+    /// Returns the safety checks of a computer call to acknowledge, or the pending checks when they were not acknowledged.
+    fn acknowledge(
+        &self,
+        call: &ComputerToolCall,
+    ) -> Result<Option<Vec<AcknowledgedSafetyCheck>>, Vec<PendingSafetyCheck>> {
+        let checks = call.pending_safety_checks.clone().unwrap_or_default();
+        if checks.is_empty() {
+            return Ok(None);
+        }
+
+        let acknowledged = self
+            .on_safety_checks
+            .as_ref()
+            .is_some_and(|callback| callback(call, &checks));
+
+        if !acknowledged {
+            return Err(checks);
+        }
+
+        Ok(Some(checks.into_iter().map(Into::into).collect()))
+    }
+
+    /// Executes the action of a computer call and returns the screenshot taken afterwards.
+    async fn handle_call(
+        &mut self,
+        call: &ComputerToolCall,
+        acknowledged_safety_checks: Option<Vec<AcknowledgedSafetyCheck>>,
+    ) -> Result<ResponseInputItem, APIError> {
+        let call_id = call.call_id.clone().ok_or_else(|| {
+            APIError::ToolError(format!("computer call {} has no call_id", call.id))
+        })?;
+
+        if let Some(action) = &call.action {
+            self.execute(action)
+                .await
+                .map_err(|error| APIError::ToolError(format!("computer action failed: {error}")))?;
+        }
+
+        let screenshot = self
+            .environment
+            .screenshot()
+            .await
+            .map_err(|error| APIError::ToolError(format!("screenshot failed: {error}")))?;
+
+        Ok(ResponseInputItem::ComputerToolCallOutput(
+            ComputerToolCallOutput {
+                id: None,
+                call_id,
+                output: ComputerToolCallOutputOutput {
+                    file_id: None,
+                    image_url: Some(format!(
+                        "data:image/png;base64,{}",
+                        general_purpose::STANDARD.encode(screenshot)
+                    )),
+                },
+                acknowledged_safety_checks,
+                status: InputItemStatus::Completed,
+            },
+        ))
+    }
+
+    async fn execute(&mut self, action: &ComputerAction) -> Result<(), String> {
+        let environment = &mut self.environment;
+
+        match action {
+            ComputerAction::Click { x, y, button } => {
+                environment.click(*x, *y, button.clone()).await
+            }
+            ComputerAction::DoubleClick { x, y } => environment.double_click(*x, *y).await,
+            ComputerAction::Drag { path } => environment.drag(path).await,
+            ComputerAction::KeyPress { keys } => environment.keypress(keys).await,
+            ComputerAction::Move { x, y } => environment.move_to(*x, *y).await,
+            ComputerAction::Screenshot => Ok(()),
+            ComputerAction::Scroll {
+                x,
+                y,
+                scroll_x,
+                scroll_y,
+            } => environment.scroll(*x, *y, *scroll_x, *scroll_y).await,
+            ComputerAction::Type { text } => environment.type_text(text).await,
+            ComputerAction::Wait => environment.wait().await,
+        }
+    }
+}
+
+/// A headless `ComputerEnvironment` that records the actions it receives and returns a fixed screenshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakeComputer {
+    actions: Vec<ComputerAction>,
+    screenshot: Vec<u8>,
+    screenshots: usize,
+}
+
+impl Default for FakeComputer {
+    fn default() -> Self {
+        Self {
+            actions: Vec::new(),
+            screenshot: FakeComputer::BLANK_PNG.to_vec(),
+            screenshots: 0,
+        }
+    }
+}
+
+impl FakeComputer {
+    /// A 1x1 transparent PNG.
+    const BLANK_PNG: &'static [u8] = &[
+        0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f,
+        0x15, 0xc4, 0x89, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0x00,
+        0x01, 0x00, 0x00, 0x05, 0x00, 0x01, 0x0d, 0x0a, 0x2d, 0xb4, 0x00, 0x00, 0x00, 0x00, 0x49,
+        0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+    ];
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the PNG returned by every screenshot.
+    pub fn with_screenshot(screenshot: Vec<u8>) -> Self {
+        Self {
+            screenshot,
+            ..Self::default()
+        }
+    }
+
+    /// The actions received so far, in order. Screenshots are counted separately.
+    pub fn actions(&self) -> &[ComputerAction] {
+        &self.actions
+    }
+
+    /// The number of screenshots taken so far.
+    pub fn screenshots(&self) -> usize {
+        self.screenshots
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ComputerEnvironment for FakeComputer {
+    async fn screenshot(&mut self) -> Result<Vec<u8>, String> {
+        self.screenshots += 1;
+
+        Ok(self.screenshot.clone())
+    }
+
+    async fn click(&mut self, x: i32, y: i32, button: MouseButton) -> Result<(), String> {
+        self.actions.push(ComputerAction::Click { x, y, button });
+
+        Ok(())
+    }
+
+    async fn double_click(&mut self, x: i32, y: i32) -> Result<(), String> {
+        self.actions.push(ComputerAction::DoubleClick { x, y });
+
+        Ok(())
+    }
+
+    async fn move_to(&mut self, x: i32, y: i32) -> Result<(), String> {
+        self.actions.push(ComputerAction::Move { x, y });
+
+        Ok(())
+    }
+
+    async fn type_text(&mut self, text: &str) -> Result<(), String> {
+        self.actions.push(ComputerAction::Type {
+            text: text.to_string(),
+        });
+
+        Ok(())
+    }
+
+    async fn scroll(&mut self, x: i32, y: i32, scroll_x: i32, scroll_y: i32) -> Result<(), String> {
+        self.actions.push(ComputerAction::Scroll {
+            x,
+            y,
+            scroll_x,
+            scroll_y,
+        });
+
+        Ok(())
+    }
+
+    async fn keypress(&mut self, keys: &[String]) -> Result<(), String> {
+        self.actions.push(ComputerAction::KeyPress {
+            keys: keys.to_vec(),
+        });
+
+        Ok(())
+    }
+
+    async fn drag(&mut self, path: &[Coordinate]) -> Result<(), String> {
+        self.actions.push(ComputerAction::Drag {
+            path: path.to_vec(),
+        });
+
+        Ok(())
+    }
+
+    async fn wait(&mut self) -> Result<(), String> {
+        self.actions.push(ComputerAction::Wait);
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::v1::resources::response::items::{ComputerAction, MouseButton};
+    use crate::v1::resources::response::request::{
+        ResponseInput, ResponseInputItem, ResponseParameters, ResponseParametersBuilder,
+    };
+    use crate::v1::resources::response::shared::{ComputerUseEnvironment, ResponseTool};
+    use crate::v1::testing::{Mock, MockResponse, MockServer};
+    use crate::v1::tools::computer::{ComputerRunner, FakeComputer};
+    use crate::v1::tools::ToolRunError;
+    use serde_json::{json, Value};
+
+    fn computer_call_response(id: &str, action: Value, safety_checks: Value) -> Value {
+        json!({
+            "id": id,
+            "object": "response",
+            "created_at": 1,
+            "model": "computer-use-preview",
+            "status": "completed",
+            "output": [{
+                "type": "computer_call",
+                "id": format!("cu_{id}"),
+                "call_id": format!("call_{id}"),
+                "action": action,
+                "pending_safety_checks": safety_checks,
+                "status": "completed"
+            }],
+            "tools": []
+        })
+    }
+
+    fn runner_parameters() -> ResponseParameters {
+        ResponseParametersBuilder::default()
+            .model("computer-use-preview")
+            .input(ResponseInput::Text("Search for rust".to_string()))
+            .tools(vec![ResponseTool::ComputerUse {
+                display_height: Some(768),
+                display_width: Some(1024),
+                environment: Some(ComputerUseEnvironment::Browser),
+            }])
+            .build()
+            .unwrap()
+    }
+
+    async fn server() -> MockServer {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/responses")
+                .respond_with(MockResponse::json(computer_call_response(
+                    "resp_1",
+                    json!({"type": "click", "x": 10, "y": 20, "button": "left"}),
+                    json!([]),
+                )))
+                .times(1),
+        );
+        server.mock(
+            Mock::post("/responses")
+                .respond_with(MockResponse::json(computer_call_response(
+                    "resp_2",
+                    json!({"type": "type", "text": "rust"}),
+                    json!([{"id": "sc_1", "code": "malicious_instructions", "message": "Check the page."}]),
+                )))
+                .times(1),
+        );
+
+        server
+    }
+
+    #[tokio::test]
+    async fn test_computer_runner_executes_actions_until_completion() {
+        let server = server().await;
+        let client = server.client();
+
+        let mut runner = ComputerRunner::new(&client, FakeComputer::new());
+        runner.on_safety_checks(|_, checks| {
+            checks[0].code.as_deref() == Some("malicious_instructions")
+        });
+
+        let result = runner.run(runner_parameters()).await.unwrap();
+        assert_eq!(result.iterations, 3);
+
+        let computer = runner.into_environment();
+        assert_eq!(
+            computer.actions(),
+            [
+                ComputerAction::Click {
+                    x: 10,
+                    y: 20,
+                    button: MouseButton::Left
+                },
+                ComputerAction::Type {
+                    text: "rust".to_string()
+                },
+            ]
+        );
+        assert_eq!(computer.screenshots(), 2);
+
+        let requests = server.received_requests();
+        let second = requests[1].json().unwrap();
+        assert_eq!(second["previous_response_id"], "resp_1");
+        assert_eq!(second["input"][0]["type"], "computer_call_output");
+        assert_eq!(second["input"][0]["call_id"], "call_resp_1");
+        assert_eq!(second["input"][0]["output"]["type"], "computer_screenshot");
+        assert!(second["input"][0]["output"]["image_url"]
+            .as_str()
+            .unwrap()
+            .starts_with("data:image/png;base64,"));
+
+        let third = requests[2].json().unwrap();
+        assert_eq!(
+            third["input"][0]["acknowledged_safety_checks"][0]["id"],
+            "sc_1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_computer_runner_stops_on_unacknowledged_safety_checks() {
+        let server = server().await;
+        let client = server.client();
+
+        let mut runner = ComputerRunner::new(&client, FakeComputer::new());
+        let Err(ToolRunError::SafetyChecksNotAcknowledged {
+            result,
+            call,
+            checks,
+        }) = runner.run(runner_parameters()).await
+        else {
+            panic!("expected the run to stop at the safety checks");
+        };
+
+        assert_eq!(runner.environment().actions().len(), 1);
+        assert_eq!(server.received_requests().len(), 2);
+
+        assert_eq!(call.id, "cu_resp_2");
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].id, "sc_1");
+        assert_eq!(result.iterations, 2);
+        assert_eq!(result.response.id, "resp_2");
+        // The user message, the first call and its screenshot, and the blocked call.
+        assert_eq!(result.items.len(), 4);
+        assert!(matches!(
+            result.items.last(),
+            Some(ResponseInputItem::ComputerToolCall(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_computer_runner_returns_items_at_max_iterations() {
+        let server = server().await;
+        let client = server.client();
+
+        let mut runner = ComputerRunner::new(&client, FakeComputer::new());
+        runner.set_max_iterations(1);
+
+        let Err(ToolRunError::MaxIterations(result)) = runner.run(runner_parameters()).await else {
+            panic!("expected the run to stop at the iteration limit");
+        };
+
+        assert_eq!(server.received_requests().len(), 1);
+        assert_eq!(result.iterations, 1);
+        assert_eq!(result.response.id, "resp_1");
+        // The user message, the computer call and its screenshot.
+        assert_eq!(result.items.len(), 3);
+        assert_eq!(runner.environment().screenshots(), 1);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::v1::resources::response::items::{
+    McpApprovalRequest, McpApprovalResponse, McpListTools, McpTool,
+};
+use crate::v1::resources::response::request::ResponseInputItem;
+use crate::v1::resources::response::response::ResponseObject;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// The future returned by an approval handler, resolving to whether the MCP tool call is approved.
+pub type ApprovalFuture = Pin<Box<dyn Future<Output = bool> + Send>>;
+
+/// An async function that decides on an MCP approval request, e.g. by asking a human.
+pub type ApprovalHandler = Arc<dyn Fn(McpApprovalRequest) -> ApprovalFuture + Send + Sync>;
+
+/// Decides which MCP tool calls are approved.
+///
+/// Calls of allow-listed servers and tools are approved right away, the other calls are passed to the approval
+/// handler. Without a handler they are rejected.
+#[derive(Clone, Default)]
+pub struct McpApprovalPolicy {
+    allowed: Vec<(String, Option<String>)>,
+    handler: Option<ApprovalHandler>,
+}
+
+impl McpApprovalPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Approves all tool calls of the server with the given label.
+    pub fn allow_server(&mut self, server_label: &str) -> &mut Self {
+        self.allowed.push((server_label.to_string(), None));
+
+        self
+    }
+
+    /// Approves the calls of a single tool of the server with the given label.
+    pub fn allow_tool(&mut self, server_label: &str, name: &str) -> &mut Self {
+        self.allowed
+            .push((server_label.to_string(), Some(name.to_string())));
+
+        self
+    }
+
+    /// Sets the handler that decides on the requests that are not allow-listed.
+    pub fn on_approval<F, Fut>(&mut self, handler: F) -> &mut Self
+    where
+        F: Fn(McpApprovalRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = bool> + Send + 'static,
+    {
+        self.handler = Some(Arc::new(move |request| Box::pin(handler(request))));
+
+        self
+    }
+
+    /// Whether the server or tool of the request is allow-listed.
+    pub fn is_allowed(&self, request: &McpApprovalRequest) -> bool {
+        self.allowed.iter().any(|(server_label, name)| {
+            *server_label == request.server_label
+                && name.as_ref().is_none_or(|name| *name == request.name)
+        })
+    }
+
+    /// Decides on an approval request and returns the response to send back to the model.
+    pub async fn respond(&self, request: &McpApprovalRequest) -> McpApprovalResponse {
+        let (approve, reason) = if self.is_allowed(request) {
+            (true, None)
+        } else if let Some(handler) = &self.handler {
+            (handler(request.clone()).await, None)
+        } else {
+            (false, Some("the tool is not allow-listed".to_string()))
+        };
+
+        McpApprovalResponse {
+            id: None,
+            approval_request_id: request.id.clone(),
+            approve,
+            reason,
+        }
+    }
+
+    /// Decides on all approval requests of a response, returning the input items to continue it with.
+    pub async fn respond_all(&self, response: &ResponseObject) -> Vec<ResponseInputItem> {
+        futures::future::join_all(
+            response
+                .mcp_approval_requests()
+                .map(|request| self.respond(request)),
+        )
+        .await
+        .into_iter()
+        .map(ResponseInputItem::McpApprovalResponse)
+        .collect()
+    }
+}
+
+/// Keeps the tools listed by MCP servers, so a stateless request can include them instead of listing them again.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct McpToolCache {
+    servers: BTreeMap<String, McpListTools>,
+}
+
+impl McpToolCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores the tool lists in the output of a response, replacing earlier lists of the same servers.
+    pub fn update(&mut self, response: &ResponseObject) -> &mut Self {
+        for list in response.mcp_list_tools() {
+            if list.error.is_none() {
+                self.servers.insert(list.server_label.clone(), list.clone());
+            }
+        }
+
+        self
+    }
+
+    /// The tool list of the server with the given label.
+    pub fn get(&self, server_label: &str) -> Option<&McpListTools> {
+        self.servers.get(server_label)
+    }
+
+    /// The tools of the server with the given label.
+    pub fn tools(&self, server_label: &str) -> &[McpTool] {
+        self.get(server_label)
+            .and_then(|list| list.tools.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// The tool lists as input items, to add to the input of a request.
+    pub fn input_items(&self) -> Vec<ResponseInputItem> {
+        self.servers
+            .values()
+            .cloned()
+            .map(ResponseInputItem::McpListTools)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v1::resources::response::items::McpApprovalRequest;
+    use crate::v1::resources::response::response::ResponseObject;
+    use crate::v1::tools::mcp::{McpApprovalPolicy, McpToolCache};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_mcp_approval_policy() {
+        let request = |name: &str, server_label: &str| McpApprovalRequest {
+            id: format!("mcpr_{name}"),
+            name: name.to_string(),
+            server_label: server_label.to_string(),
+            arguments: "{}".to_string(),
+        };
+
+        let mut policy = McpApprovalPolicy::new();
+        policy
+            .allow_server("docs")
+            .allow_tool("github", "search_issues")
+            .on_approval(|request| async move { request.name == "create_issue" });
+
+        assert!(policy.respond(&request("search", "docs")).await.approve);
+        assert!(
+            policy
+                .respond(&request("search_issues", "github"))
+                .await
+                .approve
+        );
+        assert!(
+            policy
+                .respond(&request("create_issue", "github"))
+                .await
+                .approve
+        );
+        assert!(
+            !policy
+                .respond(&request("delete_repo", "github"))
+                .await
+                .approve
+        );
+
+        let response = McpApprovalPolicy::new()
+            .respond(&request("search", "docs"))
+            .await;
+        assert!(!response.approve);
+        assert_eq!(response.approval_request_id, "mcpr_search");
+    }
+
+    #[test]
+    fn test_mcp_tool_cache() {
+        let response: ResponseObject = serde_json::from_value(json!({
+            "id": "resp_1",
+            "object": "response",
+            "created_at": 1,
+            "model": "gpt-4.1",
+            "status": "completed",
+            "output": [
+                {"type": "mcp_list_tools", "id": "mcpl_1", "server_label": "docs", "tools": [
+                    {"name": "search", "input_schema": {"type": "object"}}
+                ]},
+                {"type": "mcp_list_tools", "id": "mcpl_2", "server_label": "broken", "error": "unreachable"},
+                {"type": "mcp_approval_request", "id": "mcpr_1", "name": "search", "server_label": "docs", "arguments": "{}"}
+            ],
+            "tools": []
+        }))
+        .unwrap();
+
+        let mut cache = McpToolCache::new();
+        cache.update(&response);
+
+        assert_eq!(cache.tools("docs")[0].name, "search");
+        assert!(cache.get("broken").is_none());
+        assert_eq!(
+            serde_json::to_value(cache.input_items()).unwrap()[0]["type"],
+            "mcp_list_tools"
+        );
+        assert_eq!(response.mcp_approval_requests().count(), 1);
+    }
+}
@@ -0,0 +1,542 @@
+use crate::v1::error::APIError;
+use crate::v1::resources::chat::{
+    ChatCompletionFunction, ChatCompletionTool, ChatCompletionToolType,
+};
+use crate::v1::resources::response::items::McpTool;
+use crate::v1::resources::response::shared::ResponseTool;
+use crate::v1::tools::{chat, responses};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::ffi::OsStr;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+
+/// The MCP protocol version sent during initialization.
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+type Reader = Lines<BufReader<Box<dyn AsyncRead + Send + Unpin>>>;
+type Writer = Box<dyn AsyncWrite + Send + Unpin>;
+
+struct Connection {
+    reader: Reader,
+    writer: Writer,
+    next_id: u64,
+}
+
+impl Connection {
+    async fn send(&mut self, message: &Value) -> Result<(), APIError> {
+        let mut line = message.to_string();
+        line.push('\n');
+
+        self.writer
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|error| {
+                APIError::ToolError(format!("could not write to the MCP server: {error}"))
+            })?;
+
+        self.writer.flush().await.map_err(|error| {
+            APIError::ToolError(format!("could not write to the MCP server: {error}"))
+        })
+    }
+}
+
+/// Connects to a local MCP server over stdio and exposes its tools as function tools.
+///
+/// The bridge lists the tools of the server when it connects. Register them on a `ToolRunner` to route the tool
+/// calls of the model to the server. Clones share the same connection, the server process is stopped when the
+/// last clone is dropped.
+#[derive(Clone)]
+pub struct McpBridge {
+    connection: Arc<Mutex<Connection>>,
+    tools: Vec<McpTool>,
+    _process: Option<Arc<Child>>,
+}
+
+impl McpBridge {
+    /// Starts an MCP server process and connects to it over its stdin and stdout.
+    pub async fn spawn<I, S>(program: &str, args: I) -> Result<Self, APIError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut process = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|error| {
+                APIError::ToolError(format!("could not start `{program}`: {error}"))
+            })?;
+
+        let (Some(stdin), Some(stdout)) = (process.stdin.take(), process.stdout.take()) else {
+            return Err(APIError::ToolError(format!(
+                "could not connect to the stdio of `{program}`"
+            )));
+        };
+
+        let mut bridge = Self::connect(stdout, stdin).await?;
+        bridge._process = Some(Arc::new(process));
+
+        Ok(bridge)
+    }
+
+    /// Connects to an MCP server that reads newline-delimited JSON-RPC messages from `writer` and answers on `reader`.
+    pub async fn connect<R, W>(reader: R, writer: W) -> Result<Self, APIError>
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let reader: Box<dyn AsyncRead + Send + Unpin> = Box::new(reader);
+
+        let mut bridge = Self {
+            connection: Arc::new(Mutex::new(Connection {
+                reader: BufReader::new(reader).lines(),
+                writer: Box::new(writer),
+                next_id: 0,
+            })),
+            tools: Vec::new(),
+            _process: None,
+        };
+
+        bridge
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": {"name": "openai_dive", "version": env!("CARGO_PKG_VERSION")}
+                }),
+            )
+            .await?;
+
+        bridge
+            .connection
+            .lock()
+            .await
+            .send(&json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+            .await?;
+
+        bridge.tools = bridge.list_tools().await?;
+
+        Ok(bridge)
+    }
+
+    /// The tools listed by the server when the bridge connected.
+    pub fn tools(&self) -> &[McpTool] {
+        &self.tools
+    }
+
+    /// Lists the tools of the server.
+    pub async fn list_tools(&self) -> Result<Vec<McpTool>, APIError> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let parameters = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+
+            let result: ListToolsResult =
+                serde_json::from_value(self.request("tools/list", parameters).await?)
+                    .map_err(|error| APIError::ParseError(error.to_string()))?;
+
+            tools.extend(result.tools.into_iter().map(|tool| McpTool {
+                name: tool.name,
+                description: tool.description,
+                input_schema: tool.input_schema,
+                annotations: tool.annotations,
+            }));
+
+            match result.next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => return Ok(tools),
+            }
+        }
+    }
+
+    /// Calls a tool of the server and returns its text output.
+    ///
+    /// The error of a failed call is returned as a message, like the handlers of a `ToolRunner`.
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<Value, String> {
+        let result = self
+            .request(
+                "tools/call",
+                json!({ "name": name, "arguments": arguments }),
+            )
+            .await
+            .map_err(|error| error.to_string())?;
+
+        let result: CallToolResult =
+            serde_json::from_value(result).map_err(|error| error.to_string())?;
+
+        let output = if result.content.is_empty() {
+            result
+                .structured_content
+                .map(|content| content.to_string())
+                .unwrap_or_default()
+        } else {
+            result
+                .content
+                .iter()
+                .map(|content| match content["text"].as_str() {
+                    Some(text) if content["type"] == "text" => text.to_string(),
+                    _ => content.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        if result.is_error {
+            return Err(output);
+        }
+
+        Ok(Value::String(output))
+    }
+
+    /// The tools of the server as Chat Completions function tools.
+    ///
+    /// Tools whose name is not a valid function name (`^[a-zA-Z0-9_-]{1,64}$`) are left out.
+    pub fn chat_tools(&self) -> Vec<ChatCompletionTool> {
+        self.function_tools()
+            .map(|tool| ChatCompletionTool {
+                r#type: ChatCompletionToolType::Function,
+                function: ChatCompletionFunction {
+                    name: tool.name.clone(),
+                    description: tool.description.clone(),
+                    parameters: tool.input_schema.clone(),
+                    strict: None,
+                },
+            })
+            .collect()
+    }
+
+    /// The tools of the server as Responses function tools.
+    ///
+    /// Tools whose name is not a valid function name (`^[a-zA-Z0-9_-]{1,64}$`) are left out.
+    pub fn response_tools(&self) -> Vec<ResponseTool> {
+        self.function_tools()
+            .map(|tool| ResponseTool::Function {
+                name: tool.name.clone(),
+                description: tool.description.clone(),
+                parameters: tool.input_schema.clone(),
+                strict: false,
+            })
+            .collect()
+    }
+
+    /// Registers the tools of the server on a Chat Completions `ToolRunner`, calling the server for every tool call.
+    pub fn register_chat<'a, 'b>(
+        &self,
+        runner: &'b mut chat::ToolRunner<'a>,
+    ) -> &'b mut chat::ToolRunner<'a> {
+        for tool in self.chat_tools() {
+            let bridge = self.clone();
+            let name = tool.function.name.clone();
+
+            runner.register(tool.function, move |arguments| {
+                let bridge = bridge.clone();
+                let name = name.clone();
+
+                async move { bridge.call_tool(&name, arguments).await }
+            });
+        }
+
+        runner
+    }
+
+    /// Registers the tools of the server on a Responses `ToolRunner`, calling the server for every function call.
+    pub fn register_responses<'a, 'b>(
+        &self,
+        runner: &'b mut responses::ToolRunner<'a>,
+    ) -> &'b mut responses::ToolRunner<'a> {
+        for tool in self.response_tools() {
+            let bridge = self.clone();
+            let ResponseTool::Function { name, .. } = &tool else {
+                continue;
+            };
+            let name = name.clone();
+
+            runner.register(tool, move |arguments| {
+                let bridge = bridge.clone();
+                let name = name.clone();
+
+                async move { bridge.call_tool(&name, arguments).await }
+            });
+        }
+
+        runner
+    }
+
+    /// The tools that can be exposed as function tools, the API rejects other names.
+    fn function_tools(&self) -> impl Iterator<Item = &McpTool> {
+        self.tools.iter().filter(|tool| {
+            let valid = (1..=64).contains(&tool.name.len())
+                && tool
+                    .name
+                    .chars()
+                    .all(|char| char.is_ascii_alphanumeric() || char == '_' || char == '-');
+
+            #[cfg(feature = "log")]
+            if !valid {
+                log::warn!("skipping MCP tool `{}`: invalid function name", tool.name);
+            }
+
+            valid
+        })
+    }
+
+    /// Sends a JSON-RPC request and waits for its response, answering pings of the server in the meantime.
+    async fn request(&self, method: &str, parameters: Value) -> Result<Value, APIError> {
+        let mut connection = self.connection.lock().await;
+
+        connection.next_id += 1;
+        let id = connection.next_id;
+
+        connection
+            .send(&json!({"jsonrpc": "2.0", "id": id, "method": method, "params": parameters}))
+            .await?;
+
+        loop {
+            let line = connection
+                .reader
+                .next_line()
+                .await
+                .map_err(|error| {
+                    APIError::ToolError(format!("could not read from the MCP server: {error}"))
+                })?
+                .ok_or_else(|| {
+                    APIError::ToolError("the MCP server closed the connection".to_string())
+                })?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let message: Value = serde_json::from_str(&line)
+                .map_err(|error| APIError::ParseError(format!("invalid MCP message: {error}")))?;
+
+            match (message.get("id"), message.get("method")) {
+                // A request of the server, only pings are supported.
+                (Some(request_id), Some(request_method)) => {
+                    let reply = if request_method == "ping" {
+                        json!({"jsonrpc": "2.0", "id": request_id, "result": {}})
+                    } else {
+                        json!({"jsonrpc": "2.0", "id": request_id, "error": {"code": -32601, "message": "method not found"}})
+                    };
+
+                    connection.send(&reply).await?;
+                }
+                (Some(response_id), None) if *response_id == json!(id) => {
+                    if let Some(error) = message.get("error") {
+                        return Err(APIError::ToolError(format!(
+                            "the MCP server returned an error for `{method}`: {}",
+                            error["message"].as_str().unwrap_or("unknown error")
+                        )));
+                    }
+
+                    return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+                }
+                // Notifications and responses to earlier requests.
+                _ => {}
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListToolsResult {
+    tools: Vec<ServerTool>,
+    next_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerTool {
+    name: String,
+    description: Option<String>,
+    input_schema: Value,
+    annotations: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CallToolResult {
+    #[serde(default)]
+    content: Vec<Value>,
+    structured_content: Option<Value>,
+    #[serde(default)]
+    is_error: bool,
+}
+
+/// Serves a minimal MCP server for tests. Its `echo` tool returns the `text` argument, `echo.upper` returns it in
+/// upper case (and has a name that is not a valid function name). The `mcp_echo_server` binary serves it over stdio.
+#[cfg(feature = "testing")]
+pub async fn serve_echo_server<R, W>(reader: R, mut writer: W) -> std::io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        let Ok(request) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        let Some(id) = request.get("id") else {
+            continue;
+        };
+
+        let result = match request["method"].as_str() {
+            Some("initialize") => json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": {"tools": {}},
+                "serverInfo": {"name": "echo", "version": "1.0.0"}
+            }),
+            Some("tools/list") => {
+                let schema = json!({
+                    "type": "object",
+                    "properties": {"text": {"type": "string"}},
+                    "required": ["text"]
+                });
+
+                json!({
+                    "tools": [
+                        {"name": "echo", "description": "Returns the given text", "inputSchema": schema},
+                        {"name": "echo.upper", "description": "Returns the given text in upper case", "inputSchema": schema}
+                    ]
+                })
+            }
+            Some("tools/call") if request["params"]["name"] == "echo" => json!({
+                "content": [{"type": "text", "text": request["params"]["arguments"]["text"]}],
+                "isError": false
+            }),
+            Some("tools/call") if request["params"]["name"] == "echo.upper" => json!({
+                "content": [{
+                    "type": "text",
+                    "text": request["params"]["arguments"]["text"].as_str().unwrap_or_default().to_uppercase()
+                }],
+                "isError": false
+            }),
+            Some("tools/call") => json!({
+                "content": [{"type": "text", "text": format!("unknown tool {}", request["params"]["name"])}],
+                "isError": true
+            }),
+            _ => {
+                let error = json!({"jsonrpc": "2.0", "id": id, "error": {"code": -32601, "message": "method not found"}});
+                writer.write_all(format!("{error}\n").as_bytes()).await?;
+                continue;
+            }
+        };
+
+        let response = json!({"jsonrpc": "2.0", "id": id, "result": result});
+        writer.write_all(format!("{response}\n").as_bytes()).await?;
+        writer.flush().await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::v1::resources::chat::{
+        ChatCompletionParametersBuilder, ChatMessage, ChatMessageContent,
+    };
+    use crate::v1::resources::response::shared::ResponseTool;
+    use crate::v1::testing::{Mock, MockResponse, MockServer};
+    use crate::v1::tools::chat::ToolRunner;
+    use crate::v1::tools::mcp_bridge::{serve_echo_server, McpBridge};
+    use serde_json::json;
+
+    async fn echo_bridge() -> McpBridge {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+
+        let (server_reader, server_writer) = tokio::io::split(server);
+        tokio::spawn(serve_echo_server(server_reader, server_writer));
+
+        let (client_reader, client_writer) = tokio::io::split(client);
+        McpBridge::connect(client_reader, client_writer)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_mcp_bridge_lists_and_calls_tools() {
+        let bridge = echo_bridge().await;
+
+        assert_eq!(bridge.tools().len(), 2);
+        assert_eq!(bridge.chat_tools().len(), 1);
+        assert_eq!(bridge.chat_tools()[0].function.name, "echo");
+        assert_eq!(bridge.response_tools().len(), 1);
+        assert!(matches!(
+            &bridge.response_tools()[0],
+            ResponseTool::Function { name, parameters, .. } if name == "echo" && parameters["required"] == json!(["text"])
+        ));
+
+        assert_eq!(
+            bridge.call_tool("echo", json!({"text": "hi"})).await,
+            Ok(json!("hi"))
+        );
+        assert_eq!(
+            bridge.call_tool("echo.upper", json!({"text": "hi"})).await,
+            Ok(json!("HI"))
+        );
+        assert_eq!(
+            bridge.call_tool("missing", json!({})).await,
+            Err("unknown tool \"missing\"".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mcp_bridge_routes_tool_calls() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/chat/completions")
+                .respond_with(MockResponse::json(json!({
+                    "id": "chatcmpl-1",
+                    "object": "chat.completion",
+                    "created": 1,
+                    "model": "gpt-4o",
+                    "choices": [{
+                        "index": 0,
+                        "message": {
+                            "role": "assistant",
+                            "content": null,
+                            "tool_calls": [{"id": "call_1", "type": "function", "function": {"name": "echo", "arguments": "{\"text\": \"hello\"}"}}]
+                        },
+                        "finish_reason": "tool_calls"
+                    }]
+                })))
+                .times(1),
+        );
+
+        let client = server.client();
+        let bridge = echo_bridge().await;
+
+        let mut runner = ToolRunner::new(&client);
+        bridge.register_chat(&mut runner);
+
+        let parameters = ChatCompletionParametersBuilder::default()
+            .model("gpt-4o")
+            .messages(vec![ChatMessage::User {
+                content: ChatMessageContent::Text("Say hello".to_string()),
+                name: None,
+            }])
+            .build()
+            .unwrap();
+
+        let result = runner.run(parameters).await.unwrap();
+
+        assert_eq!(result.messages[2].text(), Some("hello"));
+
+        let request = server.received_requests()[0].json().unwrap();
+        assert_eq!(request["tools"][0]["function"]["name"], "echo");
+    }
+}
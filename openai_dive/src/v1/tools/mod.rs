@@ -0,0 +1,173 @@
+use crate::v1::error::APIError;
+use crate::v1::resources::response::items::{ComputerToolCall, PendingSafetyCheck};
+use crate::v1::resources::response::request::{
+    ContentInput, ContentItem, InputMessage, ResponseInputItem,
+};
+use crate::v1::resources::response::response::{OutputContent, ResponseOutput};
+use serde_json::Value;
+use std::fmt::{Debug, Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+pub mod apply_patch;
+pub mod chat;
+pub mod computer;
+pub mod mcp;
+#[cfg(feature = "mcp")]
+pub mod mcp_bridge;
+pub mod responses;
+#[cfg(feature = "shell")]
+pub mod shell;
+
+/// The future returned by a tool handler.
+pub type ToolFuture = Pin<Box<dyn Future<Output = Result<Value, String>> + Send>>;
+
+/// An async function that is called with the parsed arguments of a tool call.
+///
+/// The `Ok` value is sent back to the model as the tool output. An `Err` is sent back as an error message,
+/// so the model can correct its arguments.
+pub type ToolHandler = Arc<dyn Fn(Value) -> ToolFuture + Send + Sync>;
+
+/// The error of a tool run, e.g. of a `chat::ToolRunner`, `responses::ToolRunner` or `computer::ComputerRunner`.
+#[derive(Debug)]
+pub enum ToolRunError<T> {
+    /// A request or tool call failed.
+    APIError(APIError),
+    /// The model was still calling tools when the run reached its iteration limit. Contains the result of the run
+    /// so far, whose response is the last one created.
+    MaxIterations(Box<T>),
+    /// The pending safety checks of a computer call were not acknowledged. Contains the result of the run so far,
+    /// whose response contains the call, and the call with its checks, so the run can be resumed once they are
+    /// acknowledged.
+    SafetyChecksNotAcknowledged {
+        result: Box<T>,
+        call: Box<ComputerToolCall>,
+        checks: Vec<PendingSafetyCheck>,
+    },
+}
+
+impl<T> From<APIError> for ToolRunError<T> {
+    fn from(error: APIError) -> Self {
+        ToolRunError::APIError(error)
+    }
+}
+
+impl<T> Display for ToolRunError<T> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            ToolRunError::APIError(error) => Display::fmt(error, f),
+            ToolRunError::MaxIterations(_) => {
+                write!(
+                    f,
+                    "the model was still calling tools after the iteration limit"
+                )
+            }
+            ToolRunError::SafetyChecksNotAcknowledged { call, .. } => {
+                write!(
+                    f,
+                    "the safety checks of computer call {} were not acknowledged",
+                    call.id
+                )
+            }
+        }
+    }
+}
+
+impl<T: Debug> std::error::Error for ToolRunError<T> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ToolRunError::APIError(error) => Some(error),
+            ToolRunError::MaxIterations(_) | ToolRunError::SafetyChecksNotAcknowledged { .. } => {
+                None
+            }
+        }
+    }
+}
+
+pub(crate) fn handler<F, Fut>(handler: F) -> ToolHandler
+where
+    F: Fn(Value) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Value, String>> + Send + 'static,
+{
+    Arc::new(move |arguments| Box::pin(handler(arguments)))
+}
+
+/// Parses the arguments of a tool call and calls the handler, returning the output to send back to the model.
+pub(crate) async fn call_tool(handler: Option<ToolHandler>, name: &str, arguments: &str) -> String {
+    let Some(handler) = handler else {
+        return format!("Error: unknown tool `{name}`");
+    };
+
+    let arguments = if arguments.trim().is_empty() {
+        Value::Object(Default::default())
+    } else {
+        match serde_json::from_str(arguments) {
+            Ok(arguments) => arguments,
+            Err(error) => return format!("Error: invalid arguments: {error}"),
+        }
+    };
+
+    match handler(arguments).await {
+        Ok(Value::String(output)) => output,
+        Ok(output) => output.to_string(),
+        Err(error) => format!("Error: {error}"),
+    }
+}
+
+/// Converts an output item into the input item that replays it, or `None` for items that cannot be sent back.
+pub(crate) fn input_item(output: &ResponseOutput) -> Option<ResponseInputItem> {
+    match output {
+        ResponseOutput::Message(message) => Some(ResponseInputItem::Message(InputMessage {
+            id: Some(message.id.clone()),
+            status: None,
+            role: message.role.clone(),
+            content: ContentInput::List(
+                message
+                    .content
+                    .iter()
+                    .filter_map(|content| match content {
+                        OutputContent::Text { text, annotations } => {
+                            Some(ContentItem::OutputText {
+                                text: text.clone(),
+                                annotations: annotations.clone(),
+                            })
+                        }
+                        OutputContent::Refusal { refusal } => Some(ContentItem::Refusal {
+                            refusal: refusal.clone(),
+                        }),
+                        OutputContent::ReasoningText { .. } => None,
+                    })
+                    .collect(),
+            ),
+        })),
+        ResponseOutput::FunctionToolCall(call) => {
+            Some(ResponseInputItem::FunctionToolCall(call.clone()))
+        }
+        ResponseOutput::Reasoning(reasoning) => {
+            Some(ResponseInputItem::Reasoning(reasoning.clone()))
+        }
+        ResponseOutput::FileSearchToolCall(call) => {
+            Some(ResponseInputItem::FileSearchToolCall(call.clone()))
+        }
+        ResponseOutput::WebSearchToolCall(call) => {
+            Some(ResponseInputItem::WebSearchToolCall(call.clone()))
+        }
+        ResponseOutput::ComputerToolCall(call) => {
+            Some(ResponseInputItem::ComputerToolCall(call.clone()))
+        }
+        ResponseOutput::LocalShellCall(call) => {
+            Some(ResponseInputItem::LocalShellCall(call.clone()))
+        }
+        ResponseOutput::ShellCall(call) => Some(ResponseInputItem::ShellCall(call.clone())),
+        ResponseOutput::ApplyPatchCall(call) => {
+            Some(ResponseInputItem::ApplyPatchCall(call.clone()))
+        }
+        ResponseOutput::McpListTools(list) => Some(ResponseInputItem::McpListTools(list.clone())),
+        ResponseOutput::McpToolCall(call) => Some(ResponseInputItem::McpToolCall(call.clone())),
+        ResponseOutput::McpApprovalRequest(request) => {
+            Some(ResponseInputItem::McpApprovalRequest(request.clone()))
+        }
+        _ => None,
+    }
+}
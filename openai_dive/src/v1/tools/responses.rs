@@ -0,0 +1,481 @@
+use crate::v1::api::Client;
+use crate::v1::resources::response::items::{FunctionToolCallOutput, InputItemStatus};
+use crate::v1::resources::response::request::{
+    ContentInput, InputMessage, ResponseInclude, ResponseInput, ResponseInputItem,
+    ResponseParameters,
+};
+use crate::v1::resources::response::response::{ResponseObject, ResponseOutput, Role};
+use crate::v1::resources::response::shared::ResponseTool;
+use crate::v1::tools::mcp::McpApprovalPolicy;
+use crate::v1::tools::{call_tool, handler, input_item, ToolHandler, ToolRunError};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+
+/// How the output of the previous response is passed to the next request of a run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ToolRunMode {
+    /// Only the tool outputs are sent, together with the `previous_response_id` of the last response. When the
+    /// parameters set a `conversation`, the outputs are added to the conversation instead.
+    #[default]
+    Stateful,
+    /// The complete item trail is sent with every request, with `store` disabled unless it is set. Reasoning items
+    /// are replayed with their encrypted content, which is added to `include`.
+    Stateless,
+}
+
+/// Creates a model response, calls the requested functions and sends their output back to the model until it
+/// answers without calling a function.
+pub struct ToolRunner<'a> {
+    client: &'a Client,
+    tools: Vec<ResponseTool>,
+    handlers: HashMap<String, ToolHandler>,
+    max_iterations: usize,
+    mode: ToolRunMode,
+    mcp_approval: Option<McpApprovalPolicy>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolRunResult {
+    /// The last response, which contains no function calls unless the run reached its iteration limit.
+    pub response: ResponseObject,
+    /// The input items followed by the output items and function outputs added during the run.
+    pub items: Vec<ResponseInputItem>,
+    /// The number of responses created during the run.
+    pub iterations: usize,
+}
+
+impl<'a> ToolRunner<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self {
+            client,
+            tools: Vec::new(),
+            handlers: HashMap::new(),
+            max_iterations: 10,
+            mode: ToolRunMode::default(),
+            mcp_approval: None,
+        }
+    }
+
+    /// Registers a `ResponseTool::Function` the model can call, with the handler that is called with its parsed arguments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tool` is not a `ResponseTool::Function`. Use `add_tool` for tools that are run by OpenAI.
+    pub fn register<F, Fut>(&mut self, tool: ResponseTool, handler_fn: F) -> &mut Self
+    where
+        F: Fn(Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, String>> + Send + 'static,
+    {
+        let ResponseTool::Function { name, .. } = &tool else {
+            panic!("only function tools can be registered with a handler, use `add_tool` for other tools");
+        };
+
+        self.handlers.insert(name.clone(), handler(handler_fn));
+        self.tools.retain(|existing| {
+            !matches!(existing, ResponseTool::Function { name: existing, .. } if existing == name)
+        });
+        self.tools.push(tool);
+
+        self
+    }
+
+    /// Adds a tool that is run by OpenAI, e.g. web search or a remote MCP server, to every request of a run.
+    pub fn add_tool(&mut self, tool: ResponseTool) -> &mut Self {
+        self.tools.push(tool);
+
+        self
+    }
+
+    /// Sets the maximum number of responses created during a run, defaults to 10.
+    ///
+    /// A run that reaches it stops with `ToolRunError::MaxIterations`, which contains the items of the run so far.
+    pub fn set_max_iterations(&mut self, max_iterations: usize) -> &mut Self {
+        self.max_iterations = max_iterations;
+
+        self
+    }
+
+    /// Sets how the output of a response is passed to the next request, defaults to `ToolRunMode::Stateful`.
+    pub fn set_mode(&mut self, mode: ToolRunMode) -> &mut Self {
+        self.mode = mode;
+
+        self
+    }
+
+    /// Sets the policy that answers the MCP approval requests of a response, so the run continues after them.
+    ///
+    /// Without a policy the run stops at a response that only contains approval requests.
+    pub fn set_mcp_approval(&mut self, policy: McpApprovalPolicy) -> &mut Self {
+        self.mcp_approval = Some(policy);
+
+        self
+    }
+
+    /// The tool definitions of the registered tools.
+    pub fn tools(&self) -> &[ResponseTool] {
+        &self.tools
+    }
+
+    /// Runs the input in `parameters` until the model answers without calling a function or, with an MCP approval
+    /// policy, without requesting an approval.
+    pub async fn run(
+        &self,
+        parameters: ResponseParameters,
+    ) -> Result<ToolRunResult, ToolRunError<ToolRunResult>> {
+        let mut items = match &parameters.input {
+            ResponseInput::Text(text) => vec![ResponseInputItem::Message(InputMessage {
+                id: None,
+                status: None,
+                role: Role::User,
+                content: ContentInput::Text(text.clone()),
+            })],
+            ResponseInput::List(items) => items.clone(),
+        };
+        let mut request = self.parameters(&parameters, &items);
+        let mut iteration = 0;
+
+        loop {
+            iteration += 1;
+            let response = self.client.responses().create(request.clone()).await?;
+
+            items.extend(response.output.iter().filter_map(input_item));
+
+            let mut outputs = self.call_functions(&response).await;
+            if let Some(policy) = &self.mcp_approval {
+                outputs.extend(policy.respond_all(&response).await);
+            }
+
+            if outputs.is_empty() {
+                return Ok(ToolRunResult {
+                    response,
+                    items,
+                    iterations: iteration,
+                });
+            }
+
+            items.extend(outputs.iter().cloned());
+
+            if iteration >= self.max_iterations {
+                return Err(ToolRunError::MaxIterations(Box::new(ToolRunResult {
+                    response,
+                    items,
+                    iterations: iteration,
+                })));
+            }
+
+            request = match self.mode {
+                // A request cannot set both a conversation and a previous response
+                ToolRunMode::Stateful if request.conversation.is_some() => ResponseParameters {
+                    input: ResponseInput::List(outputs),
+                    ..request
+                },
+                ToolRunMode::Stateful => ResponseParameters {
+                    input: ResponseInput::List(outputs),
+                    previous_response_id: Some(response.id),
+                    ..request
+                },
+                ToolRunMode::Stateless => ResponseParameters {
+                    input: ResponseInput::List(items.clone()),
+                    ..request
+                },
+            };
+        }
+    }
+
+    fn parameters(
+        &self,
+        parameters: &ResponseParameters,
+        items: &[ResponseInputItem],
+    ) -> ResponseParameters {
+        let mut tools = parameters.tools.clone().unwrap_or_default();
+        tools.extend(self.tools.iter().cloned());
+
+        let parameters = ResponseParameters {
+            tools: Some(tools),
+            ..parameters.clone()
+        };
+
+        match self.mode {
+            ToolRunMode::Stateful => parameters,
+            ToolRunMode::Stateless => {
+                let mut include = parameters.include.clone().unwrap_or_default();
+                if !include.contains(&ResponseInclude::ReasoningEncryptedContent) {
+                    include.push(ResponseInclude::ReasoningEncryptedContent);
+                }
+
+                ResponseParameters {
+                    input: ResponseInput::List(items.to_vec()),
+                    include: Some(include),
+                    store: parameters.store.or(Some(false)),
+                    ..parameters
+                }
+            }
+        }
+    }
+
+    /// Calls the handlers of the function calls in `response`, returning their outputs in the order of the calls.
+    async fn call_functions(&self, response: &ResponseObject) -> Vec<ResponseInputItem> {
+        let calls: Vec<_> = response
+            .output
+            .iter()
+            .filter_map(|item| match item {
+                ResponseOutput::FunctionToolCall(call) => Some(call),
+                _ => None,
+            })
+            .collect();
+
+        let outputs = futures::future::join_all(calls.iter().map(|call| {
+            call_tool(
+                self.handlers.get(&call.name).cloned(),
+                &call.name,
+                &call.arguments,
+            )
+        }))
+        .await;
+
+        calls
+            .into_iter()
+            .zip(outputs)
+            .map(|(call, output)| {
+                ResponseInputItem::FunctionToolCallOutput(FunctionToolCallOutput {
+                    id: None,
+                    call_id: call.call_id.clone(),
+                    output,
+                    status: InputItemStatus::Completed,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::v1::resources::response::request::{
+        ResponseInput, ResponseInputItem, ResponseParameters, ResponseParametersBuilder,
+    };
+    use crate::v1::resources::response::shared::{ResponseConversation, ResponseTool};
+    use crate::v1::testing::{Mock, MockResponse, MockServer};
+    use crate::v1::tools::responses::{ToolRunMode, ToolRunner};
+    use crate::v1::tools::ToolRunError;
+    use serde_json::{json, Value};
+
+    fn function_call_response() -> Value {
+        json!({
+            "id": "resp_1",
+            "object": "response",
+            "created_at": 1,
+            "model": "o4-mini",
+            "status": "completed",
+            "output": [
+                {"type": "reasoning", "id": "rs_1", "summary": [], "encrypted_content": "gAAAA"},
+                {"type": "function_call", "id": "fc_1", "call_id": "call_1", "name": "add", "arguments": "{\"a\": 1, \"b\": 2}"},
+                {"type": "function_call", "id": "fc_2", "call_id": "call_2", "name": "subtract", "arguments": "{}"}
+            ],
+            "tools": []
+        })
+    }
+
+    fn runner_parameters() -> ResponseParameters {
+        ResponseParametersBuilder::default()
+            .model("o4-mini")
+            .input(ResponseInput::Text("What is 1 + 2?".to_string()))
+            .build()
+            .unwrap()
+    }
+
+    fn add_function() -> ResponseTool {
+        ResponseTool::Function {
+            name: "add".to_string(),
+            description: Some("Adds two numbers".to_string()),
+            parameters: json!({
+                "type": "object",
+                "properties": {"a": {"type": "integer"}, "b": {"type": "integer"}},
+                "required": ["a", "b"]
+            }),
+            strict: false,
+        }
+    }
+
+    async fn run(
+        mode: ToolRunMode,
+        parameters: ResponseParameters,
+    ) -> (Vec<ResponseInputItem>, Vec<Value>) {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/responses")
+                .respond_with(MockResponse::json(function_call_response()))
+                .times(1),
+        );
+
+        let client = server.client();
+        let mut runner = ToolRunner::new(&client);
+        runner
+            .set_mode(mode)
+            .register(add_function(), |arguments| async move {
+                Ok(json!(
+                    arguments["a"].as_i64().unwrap() + arguments["b"].as_i64().unwrap()
+                ))
+            });
+
+        let result = runner.run(parameters).await.unwrap();
+        assert_eq!(result.iterations, 2);
+
+        let requests = server
+            .received_requests()
+            .iter()
+            .map(|request| request.json().unwrap())
+            .collect();
+
+        (result.items, requests)
+    }
+
+    #[tokio::test]
+    async fn test_tool_runner_sends_outputs_with_previous_response_id() {
+        let (items, requests) = run(ToolRunMode::Stateful, runner_parameters()).await;
+
+        // The user message, the three output items, the two function outputs and the final message.
+        assert_eq!(items.len(), 7);
+        let ResponseInputItem::FunctionToolCallOutput(output) = &items[5] else {
+            panic!("expected a function call output");
+        };
+        assert_eq!(output.output, "Error: unknown tool `subtract`");
+
+        assert_eq!(requests[0]["tools"][0]["name"], "add");
+        assert_eq!(requests[1]["previous_response_id"], "resp_1");
+        assert_eq!(requests[1]["input"].as_array().unwrap().len(), 2);
+        assert_eq!(requests[1]["input"][0]["call_id"], "call_1");
+        assert_eq!(requests[1]["input"][0]["output"], "3");
+    }
+
+    #[tokio::test]
+    async fn test_tool_runner_adds_outputs_to_conversation() {
+        let parameters = ResponseParameters {
+            conversation: Some(ResponseConversation::Id("conv_1".to_string())),
+            ..runner_parameters()
+        };
+
+        let (_, requests) = run(ToolRunMode::Stateful, parameters).await;
+
+        assert_eq!(requests[1]["conversation"], "conv_1");
+        assert!(requests[1].get("previous_response_id").is_none());
+        assert_eq!(requests[1]["input"].as_array().unwrap().len(), 2);
+        assert_eq!(requests[1]["input"][0]["type"], "function_call_output");
+    }
+
+    #[test]
+    #[should_panic(expected = "only function tools")]
+    fn test_tool_runner_rejects_handlers_for_hosted_tools() {
+        let client = crate::v1::api::Client::new("sk-test".to_string());
+        let mut runner = ToolRunner::new(&client);
+
+        runner.register(
+            ResponseTool::WebSearch {
+                search_context_size: None,
+                user_location: None,
+            },
+            |_| async move { Ok(json!(0)) },
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tool_runner_replays_items_when_stateless() {
+        let (_, requests) = run(ToolRunMode::Stateless, runner_parameters()).await;
+
+        assert_eq!(requests[0]["store"], false);
+        assert_eq!(
+            requests[0]["include"],
+            json!(["reasoning.encrypted_content"])
+        );
+        assert!(requests[1].get("previous_response_id").is_none());
+
+        let input = requests[1]["input"].as_array().unwrap();
+        let types: Vec<&str> = input
+            .iter()
+            .map(|item| item["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                "message",
+                "reasoning",
+                "function_call",
+                "function_call",
+                "function_call_output",
+                "function_call_output"
+            ]
+        );
+        assert_eq!(input[1]["encrypted_content"], "gAAAA");
+    }
+
+    #[tokio::test]
+    async fn test_tool_runner_returns_items_at_max_iterations() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/responses").respond_with(MockResponse::json(function_call_response())),
+        );
+
+        let client = server.client();
+        let mut runner = ToolRunner::new(&client);
+        runner
+            .register(add_function(), |_| async move { Ok(json!(0)) })
+            .set_max_iterations(2);
+
+        let Err(ToolRunError::MaxIterations(result)) = runner.run(runner_parameters()).await else {
+            panic!("expected the run to stop at the iteration limit");
+        };
+
+        assert_eq!(server.received_requests().len(), 2);
+        assert_eq!(result.iterations, 2);
+        assert_eq!(result.response.id, "resp_1");
+        // The user message and, per response, the three output items and the two function outputs.
+        assert_eq!(result.items.len(), 11);
+        assert!(matches!(
+            result.items.last(),
+            Some(ResponseInputItem::FunctionToolCallOutput(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tool_runner_answers_mcp_approval_requests() {
+        use crate::v1::tools::mcp::McpApprovalPolicy;
+
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::post("/responses")
+                .respond_with(MockResponse::json(json!({
+                    "id": "resp_1",
+                    "object": "response",
+                    "created_at": 1,
+                    "model": "gpt-4.1",
+                    "status": "completed",
+                    "output": [
+                        {"type": "mcp_approval_request", "id": "mcpr_1", "name": "search", "server_label": "docs", "arguments": "{}"},
+                        {"type": "mcp_approval_request", "id": "mcpr_2", "name": "delete", "server_label": "docs", "arguments": "{}"}
+                    ],
+                    "tools": []
+                })))
+                .times(1),
+        );
+
+        let client = server.client();
+        let mut policy = McpApprovalPolicy::new();
+        policy.allow_tool("docs", "search");
+
+        let mut runner = ToolRunner::new(&client);
+        runner.set_mcp_approval(policy);
+
+        let result = runner.run(runner_parameters()).await.unwrap();
+        assert_eq!(result.iterations, 2);
+
+        let request = server.received_requests()[1].json().unwrap();
+        assert_eq!(request["previous_response_id"], "resp_1");
+        assert_eq!(
+            request["input"],
+            json!([
+                {"type": "mcp_approval_response", "approval_request_id": "mcpr_1", "approve": true},
+                {"type": "mcp_approval_response", "approval_request_id": "mcpr_2", "approve": false, "reason": "the tool is not allow-listed"}
+            ])
+        );
+    }
+}
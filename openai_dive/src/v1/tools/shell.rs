@@ -0,0 +1,493 @@
+use crate::v1::resources::response::items::{
+    InputItemStatus, LocalShellAction, LocalShellCall, LocalShellCallOutput, ShellCall,
+    ShellCallOutput, ShellCommandOutput, ShellOutcome,
+};
+use crate::v1::resources::response::request::ResponseInputItem;
+use crate::v1::tools::apply_patch::resolve_path;
+use futures::future::Either;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+
+type ApprovalCallback = Box<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// How long the output is read after the command exited, before the processes it started in the background are
+/// killed.
+const OUTPUT_GRACE_PERIOD: Duration = Duration::from_millis(100);
+
+/// Runs the commands of `shell` and `local_shell` calls in a working directory.
+///
+/// Commands only see the environment variables in the allow-list (`PATH` by default). A command that exceeds
+/// the timeout is killed along with the processes it started, and the output captured so far is returned. Processes
+/// a command leaves running in the background are killed shortly after it exited. The output of a command is
+/// truncated to the maximum output length.
+pub struct ShellExecutor {
+    working_directory: PathBuf,
+    allowed_env: Vec<String>,
+    timeout: Duration,
+    max_output_length: usize,
+    approval: Option<ApprovalCallback>,
+}
+
+impl ShellExecutor {
+    pub fn new(working_directory: impl Into<PathBuf>) -> Self {
+        Self {
+            working_directory: working_directory.into(),
+            allowed_env: vec!["PATH".to_string()],
+            timeout: Duration::from_secs(30),
+            max_output_length: 10_000,
+            approval: None,
+        }
+    }
+
+    pub fn working_directory(&self) -> &Path {
+        &self.working_directory
+    }
+
+    /// Sets the names of the environment variables that are passed to commands, defaults to `PATH`.
+    pub fn set_allowed_env<I, S>(&mut self, names: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_env = names.into_iter().map(Into::into).collect();
+
+        self
+    }
+
+    /// Sets the maximum duration of a command, defaults to 30 seconds. A shorter timeout of the call is respected.
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = timeout;
+
+        self
+    }
+
+    /// Sets the maximum number of bytes of the output of a command, defaults to 10,000.
+    pub fn set_max_output_length(&mut self, max_output_length: usize) -> &mut Self {
+        self.max_output_length = max_output_length;
+
+        self
+    }
+
+    /// Sets the hook that is called with every command before it runs. Commands are skipped when it returns `false`.
+    ///
+    /// Without a hook every command runs.
+    pub fn on_approval<F>(&mut self, approval: F) -> &mut Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.approval = Some(Box::new(approval));
+
+        self
+    }
+
+    /// Runs the commands of a `shell` call in order and returns the output item to send back to the model.
+    pub async fn execute_shell(&self, call: &ShellCall) -> ResponseInputItem {
+        let timeout = self.timeout(call.action.timeout_ms);
+        let max_output_length = self.max_output_length(call.action.max_output_length);
+
+        let mut output = Vec::new();
+        for command in &call.action.commands {
+            let mut process = Self::shell_command(command);
+            output.push(
+                self.run(
+                    command,
+                    &mut process,
+                    None,
+                    None,
+                    timeout,
+                    max_output_length,
+                )
+                .await,
+            );
+        }
+
+        ResponseInputItem::ShellCallOutput(ShellCallOutput {
+            id: None,
+            call_id: call.call_id.clone(),
+            output,
+            max_output_length: call.action.max_output_length,
+        })
+    }
+
+    /// Runs the command of a `local_shell` call and returns the output item to send back to the model.
+    pub async fn execute_local_shell(&self, call: &LocalShellCall) -> ResponseInputItem {
+        let output = match &call.action {
+            Some(LocalShellAction::Exec {
+                command,
+                env,
+                timeout_ms,
+                working_directory,
+                ..
+            }) if !command.is_empty() => {
+                let mut process = Command::new(&command[0]);
+                process.args(&command[1..]);
+
+                let output = self
+                    .run(
+                        &command.join(" "),
+                        &mut process,
+                        env.as_ref(),
+                        working_directory.as_deref(),
+                        self.timeout(*timeout_ms),
+                        self.max_output_length,
+                    )
+                    .await;
+
+                let mut text = output.stdout;
+                if !output.stderr.is_empty() {
+                    if !text.is_empty() && !text.ends_with('\n') {
+                        text.push('\n');
+                    }
+                    text.push_str(&output.stderr);
+                }
+                match output.outcome {
+                    ShellOutcome::Exit { exit_code: 0 } => {}
+                    ShellOutcome::Exit { exit_code } => {
+                        text.push_str(&format!("\n[exit code {exit_code}]"))
+                    }
+                    ShellOutcome::Timeout => text.push_str("\n[timed out]"),
+                }
+
+                text
+            }
+            _ => "Error: the call has no command".to_string(),
+        };
+
+        ResponseInputItem::LocalShellCallOutput(LocalShellCallOutput {
+            id: None,
+            call_id: call.call_id.clone().unwrap_or_else(|| call.id.clone()),
+            output,
+            status: Some(InputItemStatus::Completed),
+        })
+    }
+
+    async fn run(
+        &self,
+        description: &str,
+        process: &mut Command,
+        env: Option<&HashMap<String, String>>,
+        working_directory: Option<&str>,
+        timeout: Duration,
+        max_output_length: usize,
+    ) -> ShellCommandOutput {
+        let failed = |stderr: String| ShellCommandOutput {
+            stdout: String::new(),
+            stderr,
+            outcome: ShellOutcome::Exit { exit_code: 1 },
+        };
+
+        if let Some(approval) = &self.approval {
+            if !approval(description) {
+                return failed("the command was not approved".to_string());
+            }
+        }
+
+        let directory = match working_directory {
+            Some(directory) => match resolve_path(&self.working_directory, directory) {
+                Ok(directory) => directory,
+                Err(error) => return failed(error),
+            },
+            None => self.working_directory.clone(),
+        };
+
+        process
+            .current_dir(directory)
+            .env_clear()
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        // Start a new process group, so the processes started by the command can be killed with it
+        #[cfg(unix)]
+        process.process_group(0);
+
+        for name in &self.allowed_env {
+            let value = env
+                .and_then(|env| env.get(name).cloned())
+                .or_else(|| std::env::var(name).ok());
+
+            if let Some(value) = value {
+                process.env(name, value);
+            }
+        }
+
+        let mut child = match process.spawn() {
+            Ok(child) => child,
+            Err(error) => {
+                return ShellCommandOutput {
+                    outcome: ShellOutcome::Exit { exit_code: 127 },
+                    ..failed(error.to_string())
+                }
+            }
+        };
+
+        // The ID is gone once the process exited, but the process group lives on while it has processes
+        let process_group = child.id();
+
+        let stdout_reader = child.stdout.take();
+        let stderr_reader = child.stderr.take();
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let output = async {
+            let (stdout_result, stderr_result) = futures::join!(
+                read_into(stdout_reader, &mut stdout),
+                read_into(stderr_reader, &mut stderr)
+            );
+
+            stdout_result.and(stderr_result)
+        };
+
+        let result = tokio::time::timeout(timeout, async {
+            let output = std::pin::pin!(output);
+            let status = std::pin::pin!(child.wait());
+
+            match futures::future::select(output, status).await {
+                Either::Left((output_result, status)) => {
+                    output_result?;
+
+                    status.await
+                }
+                Either::Right((status, output)) => {
+                    // Background processes started by the command can keep the pipes open after it exited
+                    let output_result = tokio::time::timeout(OUTPUT_GRACE_PERIOD, output).await;
+                    kill_process_group(process_group);
+
+                    if let Ok(output_result) = output_result {
+                        output_result?;
+                    }
+
+                    status
+                }
+            }
+        })
+        .await;
+
+        let outcome = match result {
+            Ok(Ok(status)) => ShellOutcome::Exit {
+                exit_code: status.code().unwrap_or(-1),
+            },
+            Ok(Err(error)) => return failed(error.to_string()),
+            Err(_) => {
+                kill_process_group(process_group);
+                let _ = child.kill().await;
+
+                ShellOutcome::Timeout
+            }
+        };
+
+        ShellCommandOutput {
+            stdout: truncate(&stdout, max_output_length),
+            stderr: truncate(&stderr, max_output_length),
+            outcome,
+        }
+    }
+
+    fn shell_command(command: &str) -> Command {
+        let mut process = if cfg!(windows) {
+            let mut process = Command::new("cmd");
+            process.arg("/C");
+            process
+        } else {
+            let mut process = Command::new("sh");
+            process.arg("-c");
+            process
+        };
+        process.arg(command);
+
+        process
+    }
+
+    fn timeout(&self, timeout_ms: Option<u64>) -> Duration {
+        timeout_ms
+            .map(Duration::from_millis)
+            .map_or(self.timeout, |timeout| timeout.min(self.timeout))
+    }
+
+    fn max_output_length(&self, max_output_length: Option<u64>) -> usize {
+        max_output_length.map_or(self.max_output_length, |length| {
+            (length as usize).min(self.max_output_length)
+        })
+    }
+}
+
+/// Reads until the end of the output. The bytes read so far are kept when the future is dropped.
+async fn read_into(
+    reader: Option<impl AsyncRead + Unpin>,
+    buffer: &mut Vec<u8>,
+) -> std::io::Result<()> {
+    let Some(mut reader) = reader else {
+        return Ok(());
+    };
+
+    let mut chunk = [0; 8192];
+    loop {
+        let length = reader.read(&mut chunk).await?;
+        if length == 0 {
+            return Ok(());
+        }
+
+        buffer.extend_from_slice(&chunk[..length]);
+    }
+}
+
+/// Kills the processes of the process group that was created at spawn. Does nothing on other platforms than Unix.
+fn kill_process_group(process_group: Option<u32>) {
+    #[cfg(unix)]
+    if let Some(id) = process_group {
+        // SAFETY: `killpg` has no memory safety requirements.
+        unsafe {
+            libc::killpg(id as libc::pid_t, libc::SIGKILL);
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = process_group;
+}
+
+/// Converts output to text and cuts it off after `max_length` bytes.
+fn truncate(output: &[u8], max_length: usize) -> String {
+    let text = String::from_utf8_lossy(output);
+
+    if text.len() <= max_length {
+        return text.into_owned();
+    }
+
+    let mut end = max_length;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    format!("{}\n[truncated {} bytes]", &text[..end], text.len() - end)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use crate::v1::resources::response::items::{
+        ShellAction, ShellCall, ShellCommandOutput, ShellOutcome,
+    };
+    use crate::v1::resources::response::request::ResponseInputItem;
+    use crate::v1::tools::shell::ShellExecutor;
+    use std::time::Duration;
+
+    fn executor() -> ShellExecutor {
+        let mut executor = ShellExecutor::new(std::env::temp_dir());
+        executor.set_timeout(Duration::from_millis(500));
+
+        executor
+    }
+
+    async fn run(executor: &ShellExecutor, command: &str) -> ShellCommandOutput {
+        let call = ShellCall {
+            id: None,
+            call_id: "call_1".to_string(),
+            action: ShellAction {
+                commands: vec![command.to_string()],
+                timeout_ms: None,
+                max_output_length: None,
+            },
+            status: None,
+        };
+
+        let ResponseInputItem::ShellCallOutput(mut output) = executor.execute_shell(&call).await
+        else {
+            panic!("expected a shell_call_output");
+        };
+
+        output.output.remove(0)
+    }
+
+    fn marker(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("openai_dive_shell_{name}_{}", std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn test_shell_executor_passes_allowed_env_only() {
+        let mut executor = executor();
+
+        let output = run(&executor, "printf \"${HOME:-none}\"").await;
+        assert_eq!(output.stdout, "none");
+
+        executor.set_allowed_env(["PATH", "HOME"]);
+        let output = run(&executor, "printf \"${HOME:-none}\"").await;
+        assert_eq!(
+            output.stdout,
+            std::env::var("HOME").unwrap_or("none".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shell_executor_truncates_output() {
+        let mut executor = executor();
+        executor.set_max_output_length(5);
+
+        assert_eq!(
+            run(&executor, "printf 0123456789; exit 3").await,
+            ShellCommandOutput {
+                stdout: "01234\n[truncated 5 bytes]".to_string(),
+                stderr: String::new(),
+                outcome: ShellOutcome::Exit { exit_code: 3 },
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shell_executor_kills_command_on_timeout() {
+        let marker = marker("timeout");
+        let command = format!("printf part; (sleep 1; touch {}) & wait", marker.display());
+
+        assert_eq!(
+            run(&executor(), &command).await,
+            ShellCommandOutput {
+                stdout: "part".to_string(),
+                stderr: String::new(),
+                outcome: ShellOutcome::Timeout,
+            }
+        );
+
+        // The background process was killed along with the command
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!marker.exists());
+    }
+
+    #[tokio::test]
+    async fn test_shell_executor_skips_denied_command() {
+        let marker = marker("denied");
+        let mut executor = executor();
+        executor.on_approval(|command| !command.starts_with("touch"));
+
+        assert_eq!(
+            run(&executor, &format!("touch {}", marker.display())).await,
+            ShellCommandOutput {
+                stdout: String::new(),
+                stderr: "the command was not approved".to_string(),
+                outcome: ShellOutcome::Exit { exit_code: 1 },
+            }
+        );
+        assert!(!marker.exists());
+    }
+
+    #[tokio::test]
+    async fn test_shell_executor_kills_background_process() {
+        let marker = marker("background");
+        let command = format!("printf done; (sleep 1; touch {}) &", marker.display());
+
+        assert_eq!(
+            run(&executor(), &command).await,
+            ShellCommandOutput {
+                stdout: "done".to_string(),
+                stderr: String::new(),
+                outcome: ShellOutcome::Exit { exit_code: 0 },
+            }
+        );
+
+        // The background process was killed after the command exited
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!marker.exists());
+    }
+}
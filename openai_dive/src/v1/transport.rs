@@ -0,0 +1,320 @@
+use crate::v1::error::APIError;
+use crate::v1::resources::shared::MultipartForm;
+use async_trait::async_trait;
+use bytes::Bytes;
+#[cfg(feature = "stream")]
+use futures::{stream::StreamExt, Stream};
+use http::{HeaderMap, Method, StatusCode};
+use std::fmt::Debug;
+#[cfg(feature = "stream")]
+use std::pin::Pin;
+
+/// A stream of response body chunks.
+#[cfg(feature = "stream")]
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, APIError>> + Send>>;
+
+/// Sends HTTP requests on behalf of the client.
+///
+/// Implement this trait to use another HTTP stack, a test double or a recording transport.
+/// Transports only move bytes: status codes are checked and retries are applied by the client.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait Transport: Debug + Send + Sync {
+    /// Sends the request and reads the complete response body.
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError>;
+
+    /// Sends the request and returns the response body as a stream of chunks.
+    ///
+    /// The default implementation reads the complete body with `send`.
+    #[cfg(feature = "stream")]
+    async fn send_stream(&self, request: HttpRequest) -> Result<StreamingResponse, APIError> {
+        let response = self.send(request).await?;
+
+        Ok(StreamingResponse {
+            status: response.status,
+            headers: response.headers,
+            body: Box::pin(futures::stream::once(async move { Ok(response.body) })),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HttpRequest {
+    pub method: Method,
+    /// The full URL, including the query string.
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: RequestBody,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum RequestBody {
+    #[default]
+    Empty,
+    /// A serialized JSON body.
+    Json(Bytes),
+    /// A multipart form, see `MultipartForm::encode` for transports that need the raw bytes.
+    Multipart(MultipartForm),
+}
+
+#[derive(Clone, Debug)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+#[cfg(feature = "stream")]
+pub struct StreamingResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: ByteStream,
+}
+
+#[cfg(feature = "stream")]
+impl Debug for StreamingResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamingResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "stream")]
+impl StreamingResponse {
+    /// Reads the remaining chunks of the body.
+    pub async fn collect(self) -> Result<HttpResponse, APIError> {
+        let chunks: Vec<Bytes> = self
+            .body
+            .collect::<Vec<Result<Bytes, APIError>>>()
+            .await
+            .into_iter()
+            .collect::<Result<_, _>>()?;
+
+        Ok(HttpResponse {
+            status: self.status,
+            headers: self.headers,
+            body: chunks.concat().into(),
+        })
+    }
+}
+
+/// The default transport, backed by a `reqwest::Client`.
+#[cfg(feature = "reqwest")]
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport {
+    pub client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+
+    async fn execute(&self, request: HttpRequest) -> Result<reqwest::Response, APIError> {
+        let mut builder = self
+            .client
+            .request(request.method, request.url)
+            .headers(request.headers);
+
+        builder = match request.body {
+            RequestBody::Empty => builder,
+            RequestBody::Json(body) => builder.body(body),
+            RequestBody::Multipart(form) => builder.multipart(form.to_form().await?),
+        };
+
+        builder
+            .send()
+            .await
+            .map_err(|error| APIError::ServerError(error.to_string()))
+    }
+}
+
+#[cfg(feature = "reqwest")]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+        let response = self.execute(request).await?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(|error| APIError::ParseError(error.to_string()))?;
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    #[cfg(feature = "stream")]
+    async fn send_stream(&self, request: HttpRequest) -> Result<StreamingResponse, APIError> {
+        let response = self.execute(request).await?;
+
+        Ok(StreamingResponse {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: Box::pin(
+                response
+                    .bytes_stream()
+                    .map(|item| item.map_err(|error| APIError::StreamError(error.to_string()))),
+            ),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "reqwest"))]
+mod tests {
+    use crate::v1::api::Client;
+    use crate::v1::error::APIError;
+    use crate::v1::resources::file::{FilePurpose, UploadFileParametersBuilder};
+    use crate::v1::resources::shared::{FileUpload, FileUploadBytes, MultipartForm};
+    use crate::v1::transport::{HttpRequest, HttpResponse, RequestBody, Transport};
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use http::{HeaderMap, Method, StatusCode};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Default)]
+    struct FakeTransport {
+        body: &'static str,
+        requests: Arc<Mutex<Vec<HttpRequest>>>,
+    }
+
+    #[async_trait]
+    impl Transport for FakeTransport {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, APIError> {
+            self.requests.lock().unwrap().push(request);
+
+            Ok(HttpResponse {
+                status: StatusCode::OK,
+                headers: HeaderMap::new(),
+                body: Bytes::from_static(self.body.as_bytes()),
+            })
+        }
+    }
+
+    fn fake_client(body: &'static str) -> (Client, Arc<Mutex<Vec<HttpRequest>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let mut client = Client::new("sk-test".to_string());
+        client.set_transport(FakeTransport {
+            body,
+            requests: requests.clone(),
+        });
+
+        (client, requests)
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_sent_through_transport() {
+        let (client, requests) = fake_client(
+            r#"{"id": "gpt-4o", "object": "model", "created": 1686935002, "owned_by": "openai"}"#,
+        );
+
+        let model = client.models().get("gpt-4o").await.unwrap();
+
+        assert_eq!(model.id, "gpt-4o");
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(requests[0].url, "https://api.openai.com/v1/models/gpt-4o");
+        assert_eq!(requests[0].headers["authorization"], "Bearer sk-test");
+        assert_eq!(requests[0].body, RequestBody::Empty);
+    }
+
+    #[tokio::test]
+    async fn test_multipart_form_is_encoded() {
+        let (client, requests) = fake_client(
+            r#"{"id": "file-abc123", "object": "file", "bytes": 11, "created_at": 1677610602, "filename": "hello.jsonl", "purpose": "batch"}"#,
+        );
+
+        let parameters = UploadFileParametersBuilder::default()
+            .file(FileUpload::Bytes(FileUploadBytes::new(
+                "hello world",
+                "hello.jsonl",
+            )))
+            .purpose(FilePurpose::Batch)
+            .build()
+            .unwrap();
+
+        client.files().upload(parameters).await.unwrap();
+
+        let request = requests.lock().unwrap().remove(0);
+        let RequestBody::Multipart(form) = request.body else {
+            panic!("expected a multipart body");
+        };
+
+        let (content_type, body) = form.encode().await.unwrap();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.starts_with(&format!("--{boundary}\r\n")));
+        assert!(body.ends_with(&format!("--{boundary}--\r\n")));
+        assert!(body.contains(
+            "name=\"file\"; filename=\"hello.jsonl\"\r\nContent-Type: application/octet-stream\r\n\r\nhello world\r\n"
+        ));
+        assert!(body.contains("name=\"purpose\"\r\n\r\nbatch\r\n"));
+
+        let form = MultipartForm::new().text("meta data", "value").file(
+            "file",
+            FileUpload::Bytes(FileUploadBytes::new("x", "a\"b\r\nX-Injected: 1")),
+        );
+        let (_, body) = form.encode().await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains("name*=utf-8''meta%20data\r\n"));
+        assert!(body.contains("filename=\"a\\\"b%0D%0AX-Injected: 1\"\r\nContent-Type"));
+        assert!(!body.contains("\r\nX-Injected"));
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_server_sent_events_are_parsed() {
+        use crate::v1::resources::chat::ChatCompletionParametersBuilder;
+        use futures::StreamExt;
+
+        let (client, requests) = fake_client(concat!(
+            "data: {\"id\":\"chatcmpl-1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"gpt-4o\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"Hi\"},\"finish_reason\":null}]}\n\n",
+            "data: {\"error\":{\"message\":\"overloaded\",\"type\":\"server_error\",\"param\":null,\"code\":null}}\n\n",
+            "data: [DONE]\n\n",
+        ));
+
+        let parameters = ChatCompletionParametersBuilder::default()
+            .model("gpt-4o")
+            .messages(vec![])
+            .build()
+            .unwrap();
+
+        let events: Vec<_> = client
+            .chat()
+            .create_stream(parameters)
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0].as_ref().unwrap().id.as_deref(),
+            Some("chatcmpl-1")
+        );
+        assert!(matches!(
+            &events[1],
+            Err(APIError::InvalidRequestError(response)) if response.message() == "overloaded"
+        ));
+        assert_eq!(
+            requests.lock().unwrap()[0].headers["accept"],
+            "text/event-stream"
+        );
+    }
+}
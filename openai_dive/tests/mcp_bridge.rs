@@ -0,0 +1,35 @@
+use openai_dive::v1::tools::mcp_bridge::McpBridge;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_mcp_bridge_spawns_server_process() {
+    let bridge = McpBridge::spawn(env!("CARGO_BIN_EXE_mcp_echo_server"), Vec::<String>::new())
+        .await
+        .unwrap();
+
+    let names: Vec<_> = bridge.tools().iter().map(|tool| &tool.name).collect();
+    assert_eq!(names, vec!["echo", "echo.upper"]);
+    assert_eq!(bridge.chat_tools().len(), 1);
+
+    assert_eq!(
+        bridge.call_tool("echo", json!({"text": "hello"})).await,
+        Ok(json!("hello"))
+    );
+
+    // Clones share the process, which is stopped when the last one is dropped
+    let clone = bridge.clone();
+    drop(bridge);
+    assert_eq!(
+        clone
+            .call_tool("echo.upper", json!({"text": "hello"}))
+            .await,
+        Ok(json!("HELLO"))
+    );
+}
+
+#[tokio::test]
+async fn test_mcp_bridge_spawn_fails_for_missing_program() {
+    let result = McpBridge::spawn("openai-dive-missing-mcp-server", ["--stdio"]).await;
+
+    assert!(result.is_err());
+}